@@ -0,0 +1,126 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use lspower::lsp::{Position, Range, Url};
+use smali_lsp::server::{
+    document::Document,
+    index::ClassIndex,
+    lexer::lex_str,
+    settings::Settings,
+    validation::{validate, ValidationContext},
+};
+use tokio::runtime::Builder;
+
+const SIZES: &[(&str, usize)] = &[("small", 10), ("medium", 100), ("large", 1000)];
+
+/// Generates a class with `methods` methods, each touching a field, building
+/// an object and calling back into the class.
+fn generate_class(methods: usize) -> String {
+    let mut content = String::from(
+        ".class public Lme/l3af/bench/Generated;
+.super Ljava/lang/Object;
+.source \"Generated.java\"
+
+.field private count:I
+
+.field private static final TAG:Ljava/lang/String; = \"Generated\"
+",
+    );
+
+    for idx in 0..methods {
+        content.push_str(&format!(
+            "
+.method public method{idx}(ILjava/lang/String;)Ljava/lang/String;
+    .locals 3
+
+    # Method {idx}
+    iget v0, p0, Lme/l3af/bench/Generated;->count:I
+
+    add-int/2addr v0, p1
+
+    iput v0, p0, Lme/l3af/bench/Generated;->count:I
+
+    new-instance v1, Ljava/lang/StringBuilder;
+
+    invoke-direct {{v1}}, Ljava/lang/StringBuilder;-><init>()V
+
+    invoke-virtual {{v1, p2}}, Ljava/lang/StringBuilder;->append(Ljava/lang/String;)Ljava/lang/StringBuilder;
+
+    invoke-virtual {{v1}}, Ljava/lang/StringBuilder;->toString()Ljava/lang/String;
+
+    move-result-object v2
+
+    return-object v2
+.end method
+",
+            idx = idx
+        ));
+    }
+
+    content
+}
+
+fn bench_lex(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lex_str");
+
+    for (name, methods) in SIZES {
+        let content = generate_class(*methods);
+        group.throughput(Throughput::Bytes(content.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &content, |b, content| {
+            b.iter(|| lex_str(content))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_validate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate");
+    let index = ClassIndex::default();
+    let settings = Settings::default();
+    let context = ValidationContext {
+        index:    &index,
+        settings: &settings,
+    };
+
+    for (name, methods) in SIZES {
+        let content = generate_class(*methods);
+        group.throughput(Throughput::Bytes(content.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &content, |b, content| {
+            b.iter(|| validate(content.clone(), &context))
+        });
+    }
+
+    group.finish();
+}
+
+/// Replays a burst of single character inserts at the start of a method body,
+/// mirroring a user typing into a large file.
+fn bench_incremental_edits(c: &mut Criterion) {
+    let mut group = c.benchmark_group("document_update");
+    let runtime = Builder::new_current_thread().build().unwrap();
+    let uri = Url::parse("file:///Generated.smali").unwrap();
+
+    for (name, methods) in SIZES {
+        let content = generate_class(*methods);
+        let line = content.lines().position(|line| line.contains(".locals")).unwrap() as u32;
+
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter_batched(
+                || Document::new(uri.clone(), content.clone(), 0),
+                |document| {
+                    runtime.block_on(async {
+                        for chr in 0..32 {
+                            let position = Position::new(line, 4 + chr);
+                            document.update(Range::new(position, position), "a".to_string()).await.unwrap();
+                        }
+                    })
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_lex, bench_validate, bench_incremental_edits);
+criterion_main!(benches);
@@ -0,0 +1,19 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use smali_lsp::server::{
+    index::ClassIndex,
+    settings::Settings,
+    validation::{validate, ValidationContext},
+};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(content) = std::str::from_utf8(data) {
+        let index = ClassIndex::default();
+        let settings = Settings::default();
+
+        let _ = validate(content.to_string(), &ValidationContext {
+            index:    &index,
+            settings: &settings,
+        });
+    }
+});
@@ -0,0 +1,153 @@
+use std::{
+    io::{self, Read},
+    path::PathBuf,
+};
+
+use lspower::lsp::DiagnosticSeverity;
+use smali_lsp::server::{
+    lint::{lint_content, lint_paths, FileReport},
+    settings::Settings,
+};
+
+const USAGE: &str = "Usage: smali-lsp lint <path>... [--jobs N] [--format text|json] [--warnings-as-errors]
+       smali-lsp lint --stdin [--filename NAME] [--format text|json] [--warnings-as-errors]";
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug)]
+struct LintArgs {
+    paths:    Vec<PathBuf>,
+    /// Lint the document piped to stdin, reported under this name.
+    stdin:    Option<PathBuf>,
+    jobs:     usize,
+    format:   Format,
+    settings: Settings,
+}
+
+fn parse_args(args: &[String]) -> Result<LintArgs, String> {
+    let mut paths = Vec::new();
+    let mut stdin = false;
+    let mut filename = None;
+    let mut jobs = 0;
+    let mut format = Format::Text;
+    let mut settings = Settings::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_ref() {
+            "--jobs" | "-j" => {
+                jobs = args
+                    .next()
+                    .and_then(|jobs| jobs.parse().ok())
+                    .ok_or_else(|| "'--jobs' expects a number".to_string())?;
+            },
+            "--format" => {
+                format = match args.next().map(String::as_str) {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    _ => return Err("'--format' expects 'text' or 'json'".to_string()),
+                };
+            },
+            "--warnings-as-errors" => settings.treat_warnings_as_errors = true,
+            "--stdin" => stdin = true,
+            "--filename" => {
+                filename = Some(
+                    args.next()
+                        .map(PathBuf::from)
+                        .ok_or_else(|| "'--filename' expects a file name".to_string())?,
+                );
+            },
+            _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    let stdin = match (stdin, filename) {
+        (true, _) if !paths.is_empty() => return Err("'--stdin' can't be combined with paths".to_string()),
+        (true, filename) => Some(filename.unwrap_or_else(|| PathBuf::from("<stdin>"))),
+        (false, Some(_)) => return Err("'--filename' is only used with '--stdin'".to_string()),
+        (false, None) if paths.is_empty() => return Err("No paths to lint".to_string()),
+        (false, None) => None,
+    };
+
+    Ok(LintArgs {
+        paths,
+        stdin,
+        jobs,
+        format,
+        settings,
+    })
+}
+
+/// Runs `smali-lsp lint`, returning the process exit code.
+pub fn lint(args: &[String]) -> i32 {
+    let args = match parse_args(args) {
+        Ok(args) => args,
+        Err(why) => {
+            eprintln!("{}\n{}", why, USAGE);
+            return 2;
+        },
+    };
+
+    let reports = match &args.stdin {
+        Some(filename) => read_stdin()
+            .and_then(|content| lint_content(filename, content, &args.settings))
+            .map(|report| vec![report]),
+        None => lint_paths(&args.paths, args.jobs, &args.settings),
+    };
+
+    let reports = match reports {
+        Ok(reports) => reports,
+        Err(why) => {
+            eprintln!("{}", why);
+            return 2;
+        },
+    };
+
+    match args.format {
+        Format::Text => print_text(&reports),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&reports).unwrap()),
+    }
+
+    if reports.iter().any(|report| report.errors > 0) { 1 } else { 0 }
+}
+
+fn read_stdin() -> Result<String, String> {
+    let mut content = String::new();
+    io::stdin().read_to_string(&mut content).map_err(|why| format!("<stdin>: {}", why))?;
+
+    Ok(content)
+}
+
+fn print_text(reports: &[FileReport]) {
+    for report in reports {
+        for diag in &report.diagnostics {
+            let severity = match diag.severity {
+                Some(DiagnosticSeverity::Error) => "error",
+                Some(DiagnosticSeverity::Warning) => "warning",
+                Some(DiagnosticSeverity::Information) => "info",
+                _ => "hint",
+            };
+
+            println!(
+                "{}:{}:{}: {}: {}",
+                report.path.display(),
+                diag.range.start.line + 1,
+                diag.range.start.character + 1,
+                severity,
+                diag.message.replace('\n', " ")
+            );
+        }
+    }
+
+    println!(
+        "{} files checked, {} errors, {} warnings",
+        reports.len(),
+        reports.iter().map(|report| report.errors).sum::<usize>(),
+        reports.iter().map(|report| report.warnings).sum::<usize>()
+    );
+}
@@ -1,137 +1,767 @@
 #![feature(impl_trait_in_bindings)]
 
-pub mod server;
+mod cli;
 
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
-use lspower::{jsonrpc::Result as LspResult, lsp::*, Client, LanguageServer, LspService, Server};
+use lspower::{
+    jsonrpc::{Error, Result as LspResult},
+    lsp::*,
+    CancellationToken,
+    Client,
+    LanguageServer,
+    LspService,
+    Server,
+};
 use serde_json::Value;
-use server::{helper::lsp_range_to_range, validation::validate};
+use smali_lsp::server::{
+    blocks,
+    cache::{IndexCache, CACHE_FILE},
+    colors,
+    commands,
+    completion,
+    definition,
+    document::{self, Document},
+    error::{AnalysisError, CacheError},
+    folding,
+    format::{self, FormatStringParams},
+    hover,
+    ignore,
+    index::{self, ClassIndex},
+    inlay_hint::{self, InlayHintParams},
+    linked_editing,
+    lint,
+    log::LogLevel,
+    rename,
+    semantic_tokens,
+    settings::{self, Settings},
+    symbols,
+    validation::{validate, validate_method, ValidationContext},
+};
 use tokio::sync::RwLock;
 
-#[derive(Debug)]
-struct Document {
-    pub uri:     Url,
-    pub content: RwLock<String>,
-}
-
-impl Document {
-    async fn update(&self, range: Range, content: String) {
-        let range = lsp_range_to_range(range, &self.content.read().await);
-        self.content.write().await.replace_range(range, &content);
-    }
-}
-
 #[derive(Debug)]
 struct DocumentCache {
     pub map: RwLock<HashMap<Url, Document>>,
 }
 
 impl DocumentCache {
-    async fn update(&self, params: &DidChangeTextDocumentParams) -> Result<(), String> {
+    /// Applies the changes in `params`, recording the method they were
+    /// confined to when `track_methods` is set.
+    async fn update(&self, params: &DidChangeTextDocumentParams, track_methods: bool) -> Result<(), CacheError> {
         for change in &params.content_changes {
             let lock = self.map.read().await;
-            let doc = lock.get(&params.text_document.uri);
-
-            if doc.is_none() {
-                return Err("Unable to get document to update".to_string());
-            }
+            let doc = lock
+                .get(&params.text_document.uri)
+                .ok_or_else(|| CacheError::DocumentNotFound(params.text_document.uri.clone()))?;
+            let range = change.range.ok_or(CacheError::MissingRange)?;
 
-            if change.range.is_none() {
-                return Err("Unable to get range to update".to_string());
-            }
-
-            let doc = doc.unwrap();
-            let range = change.range.unwrap();
+            // Only a single change to content with published diagnostics can
+            // be validated incrementally
+            let method_edit = if track_methods
+                && params.content_changes.len() == 1
+                && *doc.validated_hash.read().await == Some(*doc.hash.read().await)
+            {
+                blocks::edited_method(&doc.content.read().await, range, &change.text)
+            } else {
+                None
+            };
 
-            doc.update(range, change.text.clone()).await;
+            doc.update(range, change.text.clone()).await?;
+            *doc.method_edit.write().await = method_edit;
+            *doc.version.write().await = params.text_document.version;
         }
 
         Ok(())
     }
 
+    async fn snapshot(&self, uri: &Url) -> Option<(String, i32)> {
+        let lock = self.map.read().await;
+        let doc = lock.get(uri)?;
+
+        let content = doc.content.read().await.clone();
+        let version = *doc.version.read().await;
+
+        Some((content, version))
+    }
+
+    async fn revision(&self, uri: &Url) -> Option<u64> {
+        let lock = self.map.read().await;
+        let revision = *lock.get(uri)?.revision.read().await;
+
+        Some(revision)
+    }
+
+    /// Whether `revision` is still the latest revision of the document,
+    /// results computed for an older revision must not be published.
+    async fn is_current(&self, uri: &Url, revision: u64) -> bool {
+        match self.map.read().await.get(uri) {
+            Some(doc) => *doc.revision.read().await == revision,
+            None => false,
+        }
+    }
+
     async fn did_open(&self, params: &DidOpenTextDocumentParams) {
         if !{ self.map.read().await.contains_key(&params.text_document.uri) } {
             self.map
                 .write()
                 .await
-                .insert(params.text_document.uri.clone(), Document {
-                    uri:     params.text_document.uri.clone(),
-                    content: RwLock::new(params.text_document.text.clone()),
-                });
+                .insert(
+                    params.text_document.uri.clone(),
+                    Document::new(
+                        params.text_document.uri.clone(),
+                        params.text_document.text.clone(),
+                        params.text_document.version,
+                    ),
+                );
         }
     }
 
-    async fn did_close(&self, params: &DidCloseTextDocumentParams) {
-        if !self.map.read().await.contains_key(&params.text_document.uri) {
-            self.map.write().await.remove(&params.text_document.uri.clone());
+    /// Replaces the cached content with the saved text when the client sends
+    /// it, picking up changes made outside of the editor.
+    async fn did_save(&self, params: &DidSaveTextDocumentParams) -> Result<(), CacheError> {
+        let lock = self.map.read().await;
+        let doc = lock
+            .get(&params.text_document.uri)
+            .ok_or_else(|| CacheError::DocumentNotFound(params.text_document.uri.clone()))?;
+
+        if let Some(text) = &params.text {
+            doc.replace(text.clone()).await;
         }
+
+        Ok(())
+    }
+
+    /// Whether the diagnostics last published for `uri` were computed for
+    /// its current content.
+    async fn is_validated(&self, uri: &Url) -> bool {
+        match self.map.read().await.get(uri) {
+            Some(doc) => *doc.validated_hash.read().await == Some(*doc.hash.read().await),
+            None => false,
+        }
+    }
+
+    async fn mark_validated(&self, uri: &Url, hash: u64) {
+        if let Some(doc) = self.map.read().await.get(uri) {
+            *doc.validated_hash.write().await = Some(hash);
+        }
+    }
+
+    /// Forgets what every document was last validated for, so the next
+    /// validation runs in full even if the content hasn't changed.
+    async fn invalidate(&self) {
+        for doc in self.map.read().await.values() {
+            *doc.validated_hash.write().await = None;
+            *doc.method_edit.write().await = None;
+        }
+    }
+
+    async fn did_close(&self, params: &DidCloseTextDocumentParams) -> Result<(), CacheError> {
+        self.map
+            .write()
+            .await
+            .remove(&params.text_document.uri)
+            .map(|_| ())
+            .ok_or_else(|| CacheError::DocumentNotFound(params.text_document.uri.clone()))
+    }
+
+    /// Validates the current revision of `uri`, returning the diagnostics
+    /// alongside the version and content hash they were computed for.
+    ///
+    /// When the last change was confined to one method, only that method is
+    /// validated and the rest is taken from the `previous` diagnostics.
+    async fn analyse(
+        &self,
+        uri: &Url,
+        context: &ValidationContext<'_>,
+        previous: Option<&[Diagnostic]>,
+    ) -> Result<(Vec<Diagnostic>, i32, u64), AnalysisError> {
+        // Read before the content, so a change in between can only make the
+        // results count as stale
+        let revision = self
+            .revision(uri)
+            .await
+            .ok_or_else(|| AnalysisError::DocumentNotFound(uri.clone()))?;
+        let (content, version) = self
+            .snapshot(uri)
+            .await
+            .ok_or_else(|| AnalysisError::DocumentNotFound(uri.clone()))?;
+        let method_edit = match (previous, self.map.read().await.get(uri)) {
+            (Some(previous), Some(doc)) => doc.method_edit.write().await.take().map(|edit| (edit, previous)),
+            _ => None,
+        };
+
+        let hash = document::content_hash(&content);
+        let diags = match method_edit {
+            Some((edit, previous)) => validate_method(&content, edit, previous, context)?,
+            None => validate(content, context)?,
+        };
+
+        if !self.is_current(uri, revision).await {
+            return Err(AnalysisError::Stale {
+                version,
+            });
+        }
+
+        Ok((diags, version, hash))
     }
 }
 
 #[derive(Debug)]
 struct Backend {
-    client:    Client,
-    documents: DocumentCache,
+    client:          Client,
+    documents:       DocumentCache,
+    diagnostics:     RwLock<HashMap<Url, Vec<Diagnostic>>>,
+    semantic_tokens: RwLock<SemanticTokenCache>,
+    index:           RwLock<ClassIndex>,
+    roots:           RwLock<Vec<PathBuf>>,
+    settings:        RwLock<Settings>,
+    work_progress:   RwLock<bool>,
+    /// Whether the client answers `workspace/configuration` requests.
+    config_pull:     RwLock<bool>,
+    /// Set by `shutdown`, work still running afterwards stops without
+    /// publishing anything to the client.
+    shut_down:       RwLock<bool>,
+}
+
+/// The semantic tokens last sent for each document, kept so the next request
+/// can be answered with a delta.
+#[derive(Debug, Default)]
+struct SemanticTokenCache {
+    map:            HashMap<Url, (String, Vec<SemanticToken>)>,
+    next_result_id: u64,
+}
+
+impl SemanticTokenCache {
+    /// Stores `tokens` for `uri`, returning the result id identifying them.
+    fn insert(&mut self, uri: Url, tokens: Vec<SemanticToken>) -> String {
+        self.next_result_id += 1;
+        let result_id = self.next_result_id.to_string();
+        self.map.insert(uri, (result_id.clone(), tokens));
+
+        result_id
+    }
 }
 
 impl Backend {
+    fn new(client: Client) -> Self {
+        Self {
+            client,
+            documents: DocumentCache {
+                map: RwLock::new(HashMap::new()),
+            },
+            diagnostics: RwLock::new(HashMap::new()),
+            semantic_tokens: RwLock::new(SemanticTokenCache::default()),
+            index: RwLock::new(ClassIndex::default()),
+            roots: RwLock::new(Vec::new()),
+            settings: RwLock::new(Settings::default()),
+            work_progress: RwLock::new(false),
+            config_pull: RwLock::new(false),
+            shut_down: RwLock::new(false),
+        }
+    }
+
     async fn validate(&self, uri: Url) {
+        if *self.shut_down.read().await {
+            return;
+        }
+
         let file_name = {
             let uri = uri.to_string();
             if uri.contains('/') { uri.split('/').last().unwrap().to_string() } else { uri }
         }
         .replace("%24", "$")
         .replace("%20", " ");
-        self.client.log_message(MessageType::Info, format!("[validator] Validating {}", &file_name),) .await;
 
-        if self.documents.map.read().await.contains_key(&uri) {
-            let content = {
-                let lock = self.documents.map.read().await;
-                let doc = lock.get(&uri).unwrap();
+        if self.is_ignored(&uri).await {
+            self.diagnostics.write().await.remove(&uri);
+            self.client.publish_diagnostics(uri, Vec::new(), None).await;
+            return;
+        }
+
+        // Some clients send changes that leave the content as it was
+        if self.documents.is_validated(&uri).await {
+            self.log(LogLevel::Trace, format!("[validator] Skipping unchanged {}", &file_name)).await;
+            return;
+        }
 
-                let lock = doc.content.read().await;
-                lock.clone()
+        self.log(LogLevel::Trace, format!("[validator] Validating {}", &file_name)).await;
+
+        let result = {
+            let index = self.index.read().await;
+            let settings = self.settings.read().await;
+            let context = ValidationContext {
+                index:    &index,
+                settings: &settings,
+            };
+            let previous = if settings.incremental_diagnostics {
+                self.diagnostics.read().await.get(&uri).cloned()
+            } else {
+                None
             };
 
-            match validate(content) {
-                Ok(diags) => {
-                    self.client.publish_diagnostics(uri, diags, None).await;
-                    self.client.log_message(MessageType::Info, format!("[validator] Succesfully validated {}", &file_name),) .await;
+            self.documents.analyse(&uri, &context, previous.as_deref()).await
+        };
+
+        // The client may have shut the server down while validating
+        if *self.shut_down.read().await {
+            return;
+        }
+
+        match result {
+            Ok((diags, version, hash)) => {
+                self.documents.mark_validated(&uri, hash).await;
+                self.diagnostics.write().await.insert(uri.clone(), diags.clone());
+                self.client.publish_diagnostics(uri, diags, Some(version)).await;
+                self.log(LogLevel::Trace, format!("[validator] Succesfully validated {}", &file_name)).await;
+            },
+            Err(AnalysisError::Stale { .. }) => {
+                self.log(LogLevel::Trace, format!("[validator] Discarding stale results for {}", &file_name)).await;
+            },
+            Err(why @ AnalysisError::DocumentNotFound(_)) => {
+                self.client.show_message(MessageType::Error, why.to_string()).await;
+                self.log(LogLevel::Info, format!("[validator] Error while validating {}", &file_name)).await;
+                self.log(LogLevel::Info, format!("[validator] {}", why)).await;
+            },
+        }
+    }
+
+    /// Validates every open document again, e.g. after the settings changed.
+    async fn revalidate_all(&self) {
+        self.documents.invalidate().await;
+
+        let uris = self.documents.map.read().await.keys().cloned().collect::<Vec<_>>();
+        for uri in uris {
+            self.validate(uri).await;
+        }
+    }
+
+    /// Requests the `smali-lsp` section of the client's configuration,
+    /// returning whether the settings were replaced.
+    ///
+    /// Clients that don't support `workspace/configuration` keep the
+    /// settings from `initializationOptions`.
+    async fn pull_settings(&self) -> bool {
+        if !*self.config_pull.read().await {
+            return false;
+        }
+
+        let items = vec![ConfigurationItem {
+            scope_uri: None,
+            section:   Some(settings::SECTION.to_string()),
+        }];
+
+        match self.client.configuration(items).await {
+            Ok(values) => match values.into_iter().next().filter(|value| !value.is_null()) {
+                Some(value) => {
+                    self.replace_settings(Some(value)).await;
+                    true
                 },
-                Err(why) => {
-                    self.client.show_message(MessageType::Error, why.clone()).await;
-                    self.client.log_message(MessageType::Info, format!("[validator] Error while validating {}", &file_name)).await;
-                    self.client.log_message(MessageType::Info, format!("[validator] {}", why)).await;
+                None => false,
+            },
+            Err(why) => {
+                self.log(LogLevel::Debug, format!("[settings] Couldn't pull settings: {}", why)).await;
+                false
+            },
+        }
+    }
+
+    /// Replaces the settings with the ones parsed from `value`, logging the
+    /// keys that were malformed and left at their defaults.
+    async fn replace_settings(&self, value: Option<Value>) {
+        let (settings, rejected) = Settings::parse(value);
+        *self.settings.write().await = settings;
+
+        for key in rejected {
+            self.log(LogLevel::Info, format!("[settings] Ignoring malformed '{}', using its default", key)).await;
+        }
+    }
+
+    /// Whether `uri` matches one of the `ignore` patterns in the settings.
+    async fn is_ignored(&self, uri: &Url) -> bool {
+        let settings = self.settings.read().await;
+
+        match uri.to_file_path() {
+            Ok(path) if !settings.ignore.is_empty() => {
+                ignore::is_ignored(&path, &self.roots.read().await, &settings.ignore)
+            },
+            _ => false,
+        }
+    }
+
+    /// Writes `message` to the client's output channel when `level` is
+    /// enabled by the `logLevel` setting.
+    async fn log(&self, level: LogLevel, message: String) {
+        if level.enabled(self.settings.read().await.log_level) {
+            self.client.log_message(level.message_type(), message).await;
+        }
+    }
+}
+
+impl Backend {
+    /// Indexes every `.smali` file under the workspace roots, reporting the
+    /// progress to the client when it supports `window/workDoneProgress`.
+    async fn index_workspace(&self) {
+        let roots = self.roots.read().await.clone();
+        let patterns = self.settings.read().await.ignore.clone();
+        let files = roots
+            .iter()
+            .flat_map(|root| index::smali_files(root))
+            .filter(|file| !ignore::is_ignored(file, &roots, &patterns))
+            .collect::<Vec<_>>();
+
+        let token = NumberOrString::String("smali-lsp/index".to_string());
+        let progress = *self.work_progress.read().await
+            && self
+                .client
+                .send_custom_request::<request::WorkDoneProgressCreate>(
+                    WorkDoneProgressCreateParams {
+                        token: token.clone(),
+                    },
+                    CancellationToken::default(),
+                )
+                .await
+                .is_ok();
+
+        if progress {
+            self.report_progress(&token, WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                title:       "Indexing".to_string(),
+                cancellable: Some(false),
+                message:     Some(format!("0/{} files", files.len())),
+                percentage:  Some(0),
+            }))
+            .await;
+        } else {
+            self.log(LogLevel::Info, format!("[index] Indexing {} files", files.len())).await;
+        }
+
+        // Open documents may have unsaved changes, so they take precedence
+        // over what is on disk
+        let mut open = Vec::new();
+        let uris = self.documents.map.read().await.keys().cloned().collect::<Vec<_>>();
+        for uri in uris {
+            if self.is_ignored(&uri).await {
+                continue;
+            }
+
+            if let Some((content, _)) = self.documents.snapshot(&uri).await {
+                open.push((uri, content));
+            }
+        }
+
+        // Each root keeps its own cache, so files are looked up in the one of
+        // the most specific root they are in
+        let mut caches = if self.settings.read().await.cache_index {
+            let mut roots = roots.clone();
+            roots.sort_by_key(|root| std::cmp::Reverse(root.components().count()));
+
+            roots
+                .into_iter()
+                .map(|root| {
+                    let cache = IndexCache::load(&root.join(CACHE_FILE));
+                    (root, cache)
+                })
+                .collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+        let mut cached = 0;
+
+        let mut index = self.index.write().await;
+        let mut last_percentage = 0;
+        for (idx, file) in files.iter().enumerate() {
+            if *self.shut_down.read().await {
+                // The progress was begun, so it has to be ended for the
+                // client to drop it
+                if progress {
+                    self.report_progress(&token, WorkDoneProgress::End(WorkDoneProgressEnd {
+                        message: Some("Stopped by shutdown".to_string()),
+                    }))
+                    .await;
+                }
+
+                return;
+            }
+
+            match caches.iter_mut().find(|(root, _)| file.starts_with(root)) {
+                Some((_, cache)) => {
+                    if cache.index_file(file, &mut index) {
+                        cached += 1;
+                    }
                 },
+                None => index.index_file(file),
             }
 
+            let percentage = ((idx + 1) * 100 / files.len()) as u32;
+            if progress && percentage != last_percentage {
+                last_percentage = percentage;
+                self.report_progress(&token, WorkDoneProgress::Report(WorkDoneProgressReport {
+                    cancellable: Some(false),
+                    message:     Some(format!("{}/{} files", idx + 1, files.len())),
+                    percentage:  Some(percentage),
+                }))
+                .await;
+            }
+        }
+
+        if !caches.is_empty() {
+            let indexed = files.iter().cloned().collect::<HashSet<_>>();
+            for (root, cache) in &mut caches {
+                cache.retain(&indexed);
+                if let Err(why) = cache.save(&root.join(CACHE_FILE)) {
+                    self.client
+                        .log_message(MessageType::Warning, format!("[index] Couldn't write cache: {}", why))
+                        .await;
+                }
+            }
+
+            self.log(LogLevel::Info, format!("[index] Reused {} cached files", cached)).await;
+        }
+
+        for (uri, content) in open {
+            index.index_content(&uri, &content);
+        }
+
+        if progress {
+            self.report_progress(&token, WorkDoneProgress::End(WorkDoneProgressEnd {
+                message: Some(format!("Indexed {} classes", index.len())),
+            }))
+            .await;
+        }
+    }
+
+    /// Reindexes `uri` from the open document when there is one, so
+    /// resolution reflects unsaved changes, otherwise from disk.
+    async fn reindex(&self, uri: &Url) {
+        if !self.settings.read().await.index_workspace {
+            return;
+        }
+
+        if self.is_ignored(uri).await {
+            self.index.write().await.remove_uri(uri);
             return;
         }
 
-        self.client .show_message(MessageType::Error, "Unable to get current document for validation") .await;
-        self.client.log_message(MessageType::Info, "[validator] Unable to get current document for validation.").await;
-        self.client.log_message(MessageType::Info, format!("[validator] Uri: {}", &file_name)).await;
+        let content = self.documents.snapshot(uri).await.map(|(content, _)| content);
+        let mut index = self.index.write().await;
+        match content {
+            Some(content) => index.index_content(uri, &content),
+            None => {
+                index.remove_uri(uri);
+                if let Ok(path) = uri.to_file_path() {
+                    index.index_file(&path);
+                }
+            },
+        }
+    }
+
+    async fn report_progress(&self, token: &NumberOrString, value: WorkDoneProgress) {
+        self.client
+            .send_custom_notification::<notification::Progress>(ProgressParams {
+                token: token.clone(),
+                value: ProgressParamsValue::WorkDone(value),
+            })
+            .await;
+    }
+
+    async fn apply_edit(&self, edit: WorkspaceEdit) {
+        match self.client.apply_edit(edit, Default::default()).await {
+            Ok(res) if res.applied => self.log(LogLevel::Debug, "applied".to_string()).await,
+            Ok(_) => self.log(LogLevel::Debug, "rejected".to_string()).await,
+            Err(err) => self.client.log_message(MessageType::Error, err).await,
+        }
+    }
+
+    async fn fix_locals(&self, arguments: &[Value]) -> Option<WorkspaceEdit> {
+        let params = serde_json::from_value::<TextDocumentPositionParams>(arguments.first()?.clone()).ok()?;
+        let (content, _) = self.documents.snapshot(&params.text_document.uri).await?;
+
+        let edits = commands::fix_locals(&content, params.position)?;
+        let mut changes = HashMap::new();
+        changes.insert(params.text_document.uri, edits);
+
+        Some(WorkspaceEdit::new(changes))
+    }
+
+    async fn generate_accessors(&self, arguments: &[Value]) -> Option<WorkspaceEdit> {
+        let params = serde_json::from_value::<TextDocumentPositionParams>(arguments.first()?.clone()).ok()?;
+        let (content, _) = self.documents.snapshot(&params.text_document.uri).await?;
+
+        let edits = commands::generate_accessors(&params.text_document.uri, &content, params.position)?;
+        let mut changes = HashMap::new();
+        changes.insert(params.text_document.uri, edits);
+
+        Some(WorkspaceEdit::new(changes))
+    }
+
+    async fn convert_register_directive(&self, arguments: &[Value]) -> Option<WorkspaceEdit> {
+        let params = serde_json::from_value::<TextDocumentPositionParams>(arguments.first()?.clone()).ok()?;
+        let (content, _) = self.documents.snapshot(&params.text_document.uri).await?;
+
+        let edits = commands::convert_register_directive(&content, params.position)?;
+        let mut changes = HashMap::new();
+        changes.insert(params.text_document.uri, edits);
+
+        Some(WorkspaceEdit::new(changes))
+    }
+
+    async fn strip_comments(&self, arguments: &[Value]) -> Option<WorkspaceEdit> {
+        let document = serde_json::from_value::<TextDocumentIdentifier>(arguments.first()?.clone()).ok()?;
+        let (content, _) = self.documents.snapshot(&document.uri).await?;
+
+        let edits = commands::strip_comments(&content);
+        let mut changes = HashMap::new();
+        changes.insert(document.uri, edits);
+
+        Some(WorkspaceEdit::new(changes))
+    }
+
+    /// Finds the diagnostic to jump to from the position in `arguments`,
+    /// using the last published diagnostics rather than revalidating.
+    async fn navigate_diagnostics(&self, arguments: &[Value], forward: bool) -> Option<Position> {
+        let params = serde_json::from_value::<TextDocumentPositionParams>(arguments.first()?.clone()).ok()?;
+        let diagnostics = self.diagnostics.read().await;
+        let diags = diagnostics.get(&params.text_document.uri)?;
+
+        if forward {
+            commands::next_diagnostic(diags, params.position)
+        } else {
+            commands::previous_diagnostic(diags, params.position)
+        }
+    }
+
+    /// Resolves the member that the accessor at the position in `arguments`
+    /// forwards to.
+    async fn accessor_target(&self, arguments: &[Value]) -> Option<Location> {
+        let params = serde_json::from_value::<TextDocumentPositionParams>(arguments.first()?.clone()).ok()?;
+        let (content, _) = self.documents.snapshot(&params.text_document.uri).await?;
+
+        commands::accessor_target(&params.text_document.uri, &content, params.position, &*self.index.read().await)
+    }
+
+    /// Finds the try body and handler ranges for the catch at the position in
+    /// `arguments`.
+    async fn try_ranges(&self, arguments: &[Value]) -> Option<Vec<Range>> {
+        let params = serde_json::from_value::<TextDocumentPositionParams>(arguments.first()?.clone()).ok()?;
+        let (content, _) = self.documents.snapshot(&params.text_document.uri).await?;
+
+        commands::try_ranges(&content, params.position)
+    }
+
+    /// Validates every file under the workspace roots that isn't ignored,
+    /// using the live content of open documents, and writes the report to the
+    /// path in `arguments`.
+    async fn export_diagnostics(&self, arguments: &[Value]) -> Result<PathBuf, String> {
+        let output = arguments
+            .first()
+            .and_then(Value::as_str)
+            .map(PathBuf::from)
+            .ok_or_else(|| "Expected an output path".to_string())?;
+        let format = match arguments.get(1).and_then(Value::as_str) {
+            Some(name) => {
+                commands::ReportFormat::from_name(name).ok_or_else(|| format!("Unknown report format '{}'", name))?
+            },
+            None => commands::ReportFormat::Json,
+        };
+
+        let roots = self.roots.read().await.clone();
+        let settings = self.settings.read().await.clone();
+        let mut reports = tokio::task::spawn_blocking(move || lint::lint_paths(&roots, 0, &settings))
+            .await
+            .map_err(|why| why.to_string())??;
+
+        let uris = self.documents.map.read().await.keys().cloned().collect::<Vec<_>>();
+        for uri in uris {
+            if self.is_ignored(&uri).await {
+                continue;
+            }
+
+            let index = self.index.read().await;
+            let settings = self.settings.read().await;
+            let context = ValidationContext {
+                index:    &index,
+                settings: &settings,
+            };
+
+            let (diags, ..) = match self.documents.analyse(&uri, &context, None).await {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let path = uri.to_file_path().unwrap_or_else(|_| PathBuf::from(uri.as_str()));
+            reports.retain(|report| report.path != path);
+            reports.push(lint::FileReport::new(path, diags));
+        }
+        reports.sort_by(|a, b| a.path.cmp(&b.path));
+
+        commands::export_diagnostics(&reports, format, &output).map_err(|why| why.to_string())?;
+
+        Ok(output)
     }
 }
 
 #[lspower::async_trait]
 impl LanguageServer for Backend {
-    async fn initialize(&self, _: InitializeParams) -> LspResult<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> LspResult<InitializeResult> {
+        let roots = match (params.workspace_folders, params.root_uri) {
+            (Some(folders), _) => folders.into_iter().map(|folder| folder.uri).collect(),
+            (None, Some(root)) => vec![root],
+            _ => Vec::new(),
+        };
+        *self.roots.write().await = roots.iter().filter_map(|uri| uri.to_file_path().ok()).collect();
+        self.replace_settings(params.initialization_options).await;
+        *self.work_progress.write().await =
+            params.capabilities.window.and_then(|window| window.work_done_progress).unwrap_or(false);
+        *self.config_pull.write().await =
+            params.capabilities.workspace.and_then(|workspace| workspace.configuration).unwrap_or(false);
+
         Ok(InitializeResult {
             server_info:  None,
             capabilities: ServerCapabilities {
-                text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::Incremental)),
+                text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+                    open_close:           Some(true),
+                    change:               Some(TextDocumentSyncKind::Incremental),
+                    will_save:            None,
+                    will_save_wait_until: None,
+                    save:                 Some(SaveOptions {
+                        include_text: Some(true),
+                    }.into()),
+                })),
                 completion_provider: Some(CompletionOptions {
                     resolve_provider: Some(false),
                     trigger_characters: Some(
                         // Do these actually change anything??
-                        vec![".".to_string(), "L".to_string(), "v".to_string(), "p".to_string()],
+                        vec![".".to_string(), "L".to_string(), "v".to_string(), "p".to_string(), ">".to_string()],
                     ),
                     ..Default::default()
                 }),
+                definition_provider: Some(OneOf::Left(true)),
+                type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                color_provider: Some(ColorProviderCapability::Simple(true)),
+                document_formatting_provider: Some(OneOf::Left(true)),
+                linked_editing_range_provider: Some(LinkedEditingRangeServerCapabilities::Simple(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider:           Some(true),
+                    work_done_progress_options: Default::default(),
+                })),
+                semantic_tokens_provider: Some(
+                    SemanticTokensOptions {
+                        legend: semantic_tokens::legend(),
+                        full: Some(SemanticTokensFullOptions::Delta {
+                            delta: Some(true),
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                ),
                 execute_command_provider: Some(ExecuteCommandOptions {
-                    commands: vec!["smali-lsp.format".to_string()],
+                    commands: commands::command_list(),
                     ..Default::default()
                 }),
                 workspace: Some(WorkspaceServerCapabilities {
@@ -147,12 +777,34 @@ impl LanguageServer for Backend {
     }
 
     async fn initialized(&self, _: InitializedParams) {
+        if self.pull_settings().await {
+            self.log(LogLevel::Debug, "[settings] Pulled settings from the client".to_string()).await;
+        }
+
+        if self.settings.read().await.index_workspace {
+            self.index_workspace().await;
+        }
+
+        let count = self.index.read().await.len();
+        self.log(LogLevel::Info, format!("[index] Indexed {} classes", count)).await;
+
+        let registration = Registration {
+            id:               inlay_hint::METHOD.to_string(),
+            method:           inlay_hint::METHOD.to_string(),
+            register_options: Some(serde_json::json!({ "documentSelector": [{ "language": "smali" }] })),
+        };
+        if self.client.register_capability(vec![registration]).await.is_err() {
+            self.log(LogLevel::Debug, "[inlay_hint] Client doesn't support inlay hints".to_string()).await;
+        }
+
         self.client
             .show_message(MessageType::Info, "Initialized smali-lsp")
             .await;
     }
 
     async fn shutdown(&self) -> LspResult<()> {
+        *self.shut_down.write().await = true;
+
         Ok(())
     }
 
@@ -160,67 +812,1088 @@ impl LanguageServer for Backend {
     }
 
     async fn did_change_configuration(&self, _: DidChangeConfigurationParams) {
+        if self.pull_settings().await {
+            self.revalidate_all().await;
+        }
     }
 
     async fn did_change_watched_files(&self, _: DidChangeWatchedFilesParams) {
     }
 
-    async fn execute_command(&self, _: ExecuteCommandParams) -> LspResult<Option<Value>> {
-        match self
-            .client
-            .apply_edit(WorkspaceEdit::default(), Default::default())
-            .await
-        {
-            Ok(res) if res.applied => self.client.log_message(MessageType::Info, "applied").await,
-            Ok(_) => self.client.log_message(MessageType::Info, "rejected").await,
-            Err(err) => self.client.log_message(MessageType::Error, err).await,
-        }
+    async fn execute_command(&self, params: ExecuteCommandParams) -> LspResult<Option<Value>> {
+        match params.command.as_ref() {
+            commands::FIX_LOCALS => {
+                let edit = match self.fix_locals(&params.arguments).await {
+                    Some(edit) => edit,
+                    None => return Ok(None),
+                };
+
+                self.apply_edit(edit.clone()).await;
+                Ok(serde_json::to_value(edit).ok())
+            },
+            commands::GENERATE_ACCESSORS => {
+                let edit = match self.generate_accessors(&params.arguments).await {
+                    Some(edit) => edit,
+                    None => return Ok(None),
+                };
+
+                self.apply_edit(edit.clone()).await;
+                Ok(serde_json::to_value(edit).ok())
+            },
+            commands::CONVERT_REGISTER_DIRECTIVE => {
+                let edit = match self.convert_register_directive(&params.arguments).await {
+                    Some(edit) => edit,
+                    None => return Ok(None),
+                };
+
+                self.apply_edit(edit.clone()).await;
+                Ok(serde_json::to_value(edit).ok())
+            },
+            commands::STRIP_COMMENTS => {
+                let edit = match self.strip_comments(&params.arguments).await {
+                    Some(edit) => edit,
+                    None => return Ok(None),
+                };
+
+                self.apply_edit(edit.clone()).await;
+                Ok(serde_json::to_value(edit).ok())
+            },
+            commands::EXPORT_DIAGNOSTICS => match self.export_diagnostics(&params.arguments).await {
+                Ok(path) => Ok(Some(Value::String(path.to_string_lossy().to_string()))),
+                Err(why) => Err(Error::invalid_params(why)),
+            },
+            commands::NEXT_DIAGNOSTIC | commands::PREV_DIAGNOSTIC => {
+                let forward = params.command == commands::NEXT_DIAGNOSTIC;
+                let position = self.navigate_diagnostics(&params.arguments, forward).await;
+
+                Ok(position.and_then(|position| serde_json::to_value(position).ok()))
+            },
+            commands::GOTO_ACCESSOR_TARGET => {
+                let location = self.accessor_target(&params.arguments).await;
+
+                Ok(location.and_then(|location| serde_json::to_value(location).ok()))
+            },
+            commands::HIGHLIGHT_TRY_RANGE => {
+                let ranges = self.try_ranges(&params.arguments).await;
 
-        Ok(None)
+                Ok(ranges.and_then(|ranges| serde_json::to_value(ranges).ok()))
+            },
+            _ => {
+                self.apply_edit(WorkspaceEdit::default()).await;
+                Ok(None)
+            },
+        }
     }
 
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
         self.documents.did_open(&params).await;
+        self.reindex(&params.text_document.uri).await;
 
         self.validate(params.text_document.uri).await;
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
-        self.documents.did_close(&params).await;
+        match self.documents.did_close(&params).await {
+            Ok(()) | Err(CacheError::DocumentNotFound(_)) => {},
+            Err(why) => self.client.log_message(MessageType::Error, why.to_string()).await,
+        }
 
-        self.validate(params.text_document.uri).await;
+        self.reindex(&params.text_document.uri).await;
+        self.diagnostics.write().await.remove(&params.text_document.uri);
+        self.semantic_tokens.write().await.map.remove(&params.text_document.uri);
+        self.client.publish_diagnostics(params.text_document.uri, Vec::new(), None).await;
     }
 
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
-        if let Err(why) = self.documents.update(&params).await {
-            self.client.show_message(MessageType::Error, why).await;
+        let incremental = self.settings.read().await.incremental_diagnostics;
+        if let Err(why) = self.documents.update(&params, incremental).await {
+            self.client.show_message(MessageType::Error, why.to_string()).await;
         }
 
+        self.reindex(&params.text_document.uri).await;
         self.validate(params.text_document.uri).await;
     }
 
-    async fn did_save(&self, _: DidSaveTextDocumentParams) {
-        self.client.log_message(MessageType::Info, "file saved!").await;
+    async fn did_save(&self, params: DidSaveTextDocumentParams) {
+        if let Err(why) = self.documents.did_save(&params).await {
+            self.client.log_message(MessageType::Error, why.to_string()).await;
+            return;
+        }
+
+        let uri = params.text_document.uri;
+        self.reindex(&uri).await;
+
+        self.validate(uri).await;
     }
 
-    async fn completion(&self, _: CompletionParams) -> LspResult<Option<CompletionResponse>> {
-        Ok(Some(CompletionResponse::Array(vec![
-            CompletionItem::new_simple("Hello".to_string(), "Some detail".to_string()),
-            CompletionItem::new_simple("Bye".to_string(), "More detail".to_string()),
-        ])))
+    async fn request_else(&self, method: &str, params: Option<Value>) -> LspResult<Option<Value>> {
+        match method {
+            inlay_hint::METHOD => {
+                let params = params
+                    .and_then(|params| serde_json::from_value::<InlayHintParams>(params).ok())
+                    .ok_or_else(Error::invalid_request)?;
+
+                let hints = match self.documents.snapshot(&params.text_document.uri).await {
+                    Some((content, _)) => {
+                        inlay_hint::inlay_hints(&content, params.range, &*self.settings.read().await)
+                    },
+                    None => Vec::new(),
+                };
+
+                Ok(serde_json::to_value(hints).ok())
+            },
+            format::FORMAT_STRING => {
+                let params = params
+                    .and_then(|params| serde_json::from_value::<FormatStringParams>(params).ok())
+                    .ok_or_else(Error::invalid_request)?;
+
+                let result = format::format_string(&params.text, &*self.settings.read().await);
+                Ok(serde_json::to_value(result).ok())
+            },
+            _ => Err(Error::method_not_found()),
+        }
+    }
+
+    async fn goto_definition(&self, params: GotoDefinitionParams) -> LspResult<Option<GotoDefinitionResponse>> {
+        let params = params.text_document_position_params;
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        let location = definition::definition(&content, params.position, &*self.index.read().await);
+
+        Ok(location.map(GotoDefinitionResponse::Scalar))
+    }
+
+    async fn goto_type_definition(
+        &self,
+        params: request::GotoTypeDefinitionParams,
+    ) -> LspResult<Option<request::GotoTypeDefinitionResponse>> {
+        let params = params.text_document_position_params;
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        let location = definition::type_definition(&content, params.position, &*self.index.read().await);
+
+        Ok(location.map(request::GotoTypeDefinitionResponse::Scalar))
+    }
+
+    async fn hover(&self, params: HoverParams) -> LspResult<Option<Hover>> {
+        let params = params.text_document_position_params;
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        let index = self.index.read().await;
+        let hover = hover::register_hover(&content, params.position)
+            .or_else(|| hover::enum_hover(&content, params.position))
+            .or_else(|| hover::class_hover(&content, params.position, &index));
+
+        Ok(hover)
+    }
+
+    async fn folding_range(&self, params: FoldingRangeParams) -> LspResult<Option<Vec<FoldingRange>>> {
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        Ok(Some(folding::folding_ranges(&content)))
+    }
+
+    async fn linked_editing_range(
+        &self,
+        params: LinkedEditingRangeParams,
+    ) -> LspResult<Option<LinkedEditingRanges>> {
+        let params = params.text_document_position_params;
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        Ok(linked_editing::linked_editing_ranges(&content, params.position))
+    }
+
+    async fn prepare_rename(&self, params: TextDocumentPositionParams) -> LspResult<Option<PrepareRenameResponse>> {
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        Ok(rename::prepare_rename(&content, params.position).map(PrepareRenameResponse::Range))
+    }
+
+    async fn rename(&self, params: RenameParams) -> LspResult<Option<WorkspaceEdit>> {
+        let uri = params.text_document_position.text_document.uri;
+        let content = match self.documents.snapshot(&uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        let position = params.text_document_position.position;
+        let renamed = match rename::rename_register(&content, position, &params.new_name) {
+            Ok(Some(rename)) => {
+                if let Some(warning) = rename.warning {
+                    self.client.show_message(MessageType::Warning, warning).await;
+                }
+
+                Ok(Some(rename.edits))
+            },
+            Ok(None) => rename::rename_method(&content, position, &params.new_name),
+            Err(why) => Err(why),
+        };
+        let edits = match renamed {
+            Ok(Some(edits)) => edits,
+            Ok(None) => return Ok(None),
+            Err(why) => return Err(Error::invalid_params(why)),
+        };
+        let mut changes = HashMap::new();
+        changes.insert(uri, edits);
+
+        Ok(Some(WorkspaceEdit::new(changes)))
+    }
+
+    async fn document_symbol(&self, params: DocumentSymbolParams) -> LspResult<Option<DocumentSymbolResponse>> {
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        Ok(Some(DocumentSymbolResponse::Nested(symbols::document_symbols(&content))))
+    }
+
+    async fn semantic_tokens_full(&self, params: SemanticTokensParams) -> LspResult<Option<SemanticTokensResult>> {
+        let uri = params.text_document.uri;
+        let content = match self.documents.snapshot(&uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        let data = semantic_tokens::semantic_tokens(&content);
+        let result_id = self.semantic_tokens.write().await.insert(uri, data.clone());
+
+        Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+            result_id: Some(result_id),
+            data,
+        })))
+    }
+
+    async fn semantic_tokens_full_delta(
+        &self,
+        params: SemanticTokensDeltaParams,
+    ) -> LspResult<Option<SemanticTokensFullDeltaResult>> {
+        let uri = params.text_document.uri;
+        let content = match self.documents.snapshot(&uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        let data = semantic_tokens::semantic_tokens(&content);
+        let mut cache = self.semantic_tokens.write().await;
+        let edits = match cache.map.get(&uri) {
+            Some((result_id, previous)) if *result_id == params.previous_result_id => {
+                Some(semantic_tokens::semantic_tokens_delta(previous, &data))
+            },
+            _ => None,
+        };
+        let result_id = cache.insert(uri, data.clone());
+
+        // Without the tokens the client is diffing against, resend everything
+        Ok(Some(match edits {
+            Some(edits) => SemanticTokensFullDeltaResult::TokensDelta(SemanticTokensDelta {
+                result_id: Some(result_id),
+                edits,
+            }),
+            None => SemanticTokensFullDeltaResult::Tokens(SemanticTokens {
+                result_id: Some(result_id),
+                data,
+            }),
+        }))
+    }
+
+    async fn formatting(&self, params: DocumentFormattingParams) -> LspResult<Option<Vec<TextEdit>>> {
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        Ok(Some(format::format_document(&content, &*self.settings.read().await)))
+    }
+
+    async fn document_color(&self, params: DocumentColorParams) -> LspResult<Vec<ColorInformation>> {
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(Vec::new()),
+        };
+
+        Ok(colors::document_colors(&content, &*self.settings.read().await))
+    }
+
+    async fn color_presentation(&self, params: ColorPresentationParams) -> LspResult<Vec<ColorPresentation>> {
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(Vec::new()),
+        };
+
+        Ok(colors::color_presentations(&content, params.color, params.range))
+    }
+
+    async fn completion(&self, params: CompletionParams) -> LspResult<Option<CompletionResponse>> {
+        let params = params.text_document_position;
+        let content = match self.documents.snapshot(&params.text_document.uri).await {
+            Some((content, _)) => content,
+            None => return Ok(None),
+        };
+
+        let items = completion::completions(&content, params.position, &*self.index.read().await);
+        Ok(Some(CompletionResponse::Array(items)))
     }
 }
 
 #[tokio::main]
 async fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("lint") {
+        std::process::exit(cli::lint(&args[1..]));
+    }
+
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
-    let (service, messages) = LspService::new(|client| Backend {
-        client,
-        documents: DocumentCache {
-            map: RwLock::new(HashMap::new()),
-        },
-    });
+    let (service, messages) = LspService::new(Backend::new);
     Server::new(stdin, stdout).interleave(messages).serve(service).await;
 }
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, future::Future};
+
+    use futures::StreamExt;
+    use lspower::{jsonrpc::Incoming, lsp::*, Client, LanguageServer, LspService};
+    use serde_json::{json, Value};
+    use smali_lsp::server::{
+        blocks::MethodEdit,
+        document,
+        error::{AnalysisError, CacheError},
+        index::ClassIndex,
+        settings::Settings,
+        validation::ValidationContext,
+    };
+    use tokio::sync::{mpsc, RwLock};
+    use tower_service::Service;
+
+    use super::{Backend, DocumentCache};
+
+    fn cache() -> DocumentCache {
+        DocumentCache {
+            map: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn close_params(uri: &Url) -> DidCloseTextDocumentParams {
+        DidCloseTextDocumentParams {
+            text_document: TextDocumentIdentifier {
+                uri: uri.clone(),
+            },
+        }
+    }
+
+    fn open_params(uri: &Url, text: &str, version: i32) -> DidOpenTextDocumentParams {
+        DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "smali".to_string(),
+                version,
+                text: text.to_string(),
+            },
+        }
+    }
+
+    fn change_params(uri: &Url, range: Range, text: &str, version: i32) -> DidChangeTextDocumentParams {
+        DidChangeTextDocumentParams {
+            text_document:   VersionedTextDocumentIdentifier {
+                uri: uri.clone(),
+                version,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range:        Some(range),
+                range_length: None,
+                text:         text.to_string(),
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn stale_version_is_not_current() {
+        let cache = cache();
+        let uri = Url::parse("file:///Test.smali").unwrap();
+
+        cache.did_open(&open_params(&uri, ".class public LTest;\n", 1)).await;
+        let old_revision = cache.revision(&uri).await.unwrap();
+
+        let range = Range::new(Position::new(0, 7), Position::new(0, 13));
+        cache.update(&change_params(&uri, range, "final", 2), false).await.unwrap();
+        let (content, version) = cache.snapshot(&uri).await.unwrap();
+        let new_revision = cache.revision(&uri).await.unwrap();
+
+        assert_eq!(content, ".class final LTest;\n");
+        assert_eq!(version, 2);
+
+        // The validation of version 1 finishing after version 2 was received
+        // must be discarded rather than published.
+        assert!(!cache.is_current(&uri, old_revision).await);
+        assert!(cache.is_current(&uri, new_revision).await);
+    }
+
+    #[tokio::test]
+    async fn change_during_analysis_is_stale() {
+        let mut client = TestClient::start(json!({})).await;
+
+        // The backend is driven directly to change the document while it is
+        // being analysed
+        let backend = Backend::new(client.client.clone());
+        backend.settings.write().await.incremental_diagnostics = true;
+        let uri = Url::parse("file:///Test.smali").unwrap();
+        let text = ".class public LTest;\n.super Ljava/lang/Object;\n";
+        backend.documents.did_open(&open_params(&uri, text, 1)).await;
+        backend.diagnostics.write().await.insert(uri.clone(), Vec::new());
+
+        let index = ClassIndex::default();
+        let settings = Settings::default();
+        let context = ValidationContext {
+            index:    &index,
+            settings: &settings,
+        };
+
+        // Version 2 arrives after the analysis took its snapshot of version 1
+        // and before it finished, while it waits for the method edit
+        let map = backend.documents.map.read().await;
+        let doc = &map[&uri];
+        let method_edit = doc.method_edit.write().await;
+        let analysis = backend.documents.analyse(&uri, &context, Some(&[]));
+        futures::pin_mut!(analysis);
+        assert!(futures::poll!(&mut analysis).is_pending());
+
+        let range = Range::new(Position::new(0, 7), Position::new(0, 13));
+        doc.update(range, "final".to_string()).await.unwrap();
+        *doc.version.write().await = 2;
+        drop(method_edit);
+        assert_eq!(analysis.await, Err(AnalysisError::Stale {
+            version: 1,
+        }));
+
+        // The server discards the results the same way
+        let method_edit = doc.method_edit.write().await;
+        let validation = backend.validate(uri.clone());
+        futures::pin_mut!(validation);
+        assert!(futures::poll!(&mut validation).is_pending());
+
+        let range = Range::new(Position::new(0, 7), Position::new(0, 12));
+        doc.update(range, "abstract".to_string()).await.unwrap();
+        *doc.version.write().await = 3;
+        drop(method_edit);
+        validation.await;
+        drop(map);
+
+        // Only the diagnostics for the latest version are published
+        backend.validate(uri).await;
+        assert_eq!(client.next_publish().await["version"], 3);
+    }
+
+    #[tokio::test]
+    async fn saved_content_is_current() {
+        let cache = cache();
+        let uri = Url::parse("file:///Test.smali").unwrap();
+
+        cache.did_open(&open_params(&uri, ".class public LTest;\n", 1)).await;
+        let old_revision = cache.revision(&uri).await.unwrap();
+
+        let params = DidSaveTextDocumentParams {
+            text_document: TextDocumentIdentifier {
+                uri: uri.clone(),
+            },
+            text:          Some(".class final LTest;\n".to_string()),
+        };
+        cache.did_save(&params).await.unwrap();
+        let (_, version) = cache.snapshot(&uri).await.unwrap();
+        let new_revision = cache.revision(&uri).await.unwrap();
+
+        // Text replaced on save must not be covered by the earlier revision,
+        // while the version stays the one the client assigned
+        assert_eq!(version, 1);
+        assert!(!cache.is_current(&uri, old_revision).await);
+        assert!(cache.is_current(&uri, new_revision).await);
+    }
+
+    #[tokio::test]
+    async fn update_errors() {
+        let cache = cache();
+        let uri = Url::parse("file:///Test.smali").unwrap();
+        let range = Range::new(Position::new(0, 0), Position::new(0, 1));
+
+        assert_eq!(
+            cache.update(&change_params(&uri, range, "", 2), false).await,
+            Err(CacheError::DocumentNotFound(uri.clone()))
+        );
+
+        cache.did_open(&open_params(&uri, ".class public LTest;\n", 1)).await;
+
+        let mut params = change_params(&uri, range, "", 2);
+        params.content_changes[0].range = None;
+        assert_eq!(cache.update(&params, false).await, Err(CacheError::MissingRange));
+
+        let range = Range::new(Position::new(4, 0), Position::new(4, 1));
+        assert_eq!(
+            cache.update(&change_params(&uri, range, "", 2), false).await,
+            Err(CacheError::InvalidRange(range))
+        );
+    }
+
+    #[tokio::test]
+    async fn close_errors() {
+        let cache = cache();
+        let uri = Url::parse("file:///Test.smali").unwrap();
+
+        assert_eq!(cache.did_close(&close_params(&uri)).await, Err(CacheError::DocumentNotFound(uri.clone())));
+
+        cache.did_open(&open_params(&uri, ".class public LTest;\n", 1)).await;
+        assert_eq!(cache.did_close(&close_params(&uri)).await, Ok(()));
+        assert!(cache.snapshot(&uri).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn analyse_errors() {
+        let cache = cache();
+        let uri = Url::parse("file:///Test.smali").unwrap();
+        let index = ClassIndex::default();
+        let settings = Settings::default();
+        let context = ValidationContext {
+            index:    &index,
+            settings: &settings,
+        };
+
+        assert_eq!(
+            cache.analyse(&uri, &context, None).await,
+            Err(AnalysisError::DocumentNotFound(uri.clone()))
+        );
+
+        let text = ".class public LTest;\n.super Ljava/lang/Object;\n";
+        cache.did_open(&open_params(&uri, text, 3)).await;
+        assert_eq!(cache.analyse(&uri, &context, None).await, Ok((Vec::new(), 3, document::content_hash(text))));
+    }
+
+    #[tokio::test]
+    async fn method_edits_are_tracked() {
+        let cache = cache();
+        let uri = Url::parse("file:///Test.smali").unwrap();
+        let text = ".class public LTest;\n\n.method public test()V\n    return-void\n.end method\n";
+        let range = Range::new(Position::new(3, 4), Position::new(3, 4));
+
+        // Without published diagnostics there is nothing to update
+        cache.did_open(&open_params(&uri, text, 1)).await;
+        cache.update(&change_params(&uri, range, "nop\n    ", 2), true).await.unwrap();
+        assert_eq!(*cache.map.read().await[&uri].method_edit.read().await, None);
+
+        let (content, _) = cache.snapshot(&uri).await.unwrap();
+        cache.mark_validated(&uri, document::content_hash(&content)).await;
+        cache.update(&change_params(&uri, range, "nop\n    ", 3), true).await.unwrap();
+        assert_eq!(
+            *cache.map.read().await[&uri].method_edit.read().await,
+            Some(MethodEdit {
+                start: 2,
+                end:   5,
+                delta: 1,
+            })
+        );
+    }
+
+    /// An in-memory client driving a `Backend` through serialized JSON-RPC
+    /// messages, for testing requests end to end without stdio.
+    struct TestClient {
+        service:  LspService,
+        messages: mpsc::UnboundedReceiver<Value>,
+        next_id:  u64,
+        /// The handle the server sends through, for driving a second
+        /// `Backend` directly.
+        client:   Client,
+    }
+
+    impl TestClient {
+        /// Starts a server and initializes it with the client `capabilities`.
+        async fn start(capabilities: Value) -> Self {
+            Self::initialize(json!({ "capabilities": capabilities })).await
+        }
+
+        /// Starts a server and initializes it with the full `initialize`
+        /// params, e.g. to set the root or initialization options.
+        async fn initialize(params: Value) -> Self {
+            let mut handle = None;
+            let (service, stream) = LspService::new(|client| {
+                handle = Some(client.clone());
+                Backend::new(client)
+            });
+            let (sender, messages) = mpsc::unbounded_channel();
+            tokio::spawn(stream.for_each(move |message| {
+                sender.send(serde_json::to_value(message).unwrap()).unwrap();
+                async {}
+            }));
+
+            let mut client = Self {
+                service,
+                messages,
+                next_id: 0,
+                client: handle.unwrap(),
+            };
+            client.request("initialize", params).await;
+
+            client
+        }
+
+        async fn notify(&mut self, method: &str, params: Value) {
+            let message =
+                serde_json::from_value::<Incoming>(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+            self.service.call(message.unwrap()).await.unwrap();
+        }
+
+        /// Sends a notification in the background, for ones the server only
+        /// finishes handling once the client has answered its own requests.
+        fn spawn_notify(&mut self, method: &str, params: Value) -> impl Future<Output = ()> {
+            let message =
+                serde_json::from_value::<Incoming>(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+            let handle = tokio::spawn(self.service.call(message.unwrap()));
+
+            async move {
+                handle.await.unwrap().unwrap();
+            }
+        }
+
+        /// Sends a request and returns the `result` of the response, failing
+        /// the test if the server responded with an error.
+        async fn request(&mut self, method: &str, params: Value) -> Value {
+            self.next_id += 1;
+
+            let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": self.next_id });
+            let response = self.service.call(serde_json::from_value(request).unwrap()).await.unwrap();
+            let response = serde_json::to_value(response).unwrap();
+            assert!(response["error"].is_null(), "'{}' failed: {}", method, response["error"]);
+
+            response["result"].clone()
+        }
+
+        /// Answers the request with `id` the server sent to the client.
+        async fn respond(&mut self, id: &Value, result: Value) {
+            let response = json!({ "jsonrpc": "2.0", "result": result, "id": id });
+            self.service.call(serde_json::from_value(response).unwrap()).await.unwrap();
+        }
+
+        /// Waits for the next `publishDiagnostics` the server sends, skipping
+        /// log messages.
+        async fn next_publish(&mut self) -> Value {
+            loop {
+                let message = self.messages.recv().await.unwrap();
+                if message["method"] == "textDocument/publishDiagnostics" {
+                    return message["params"].clone();
+                }
+            }
+        }
+
+        /// Opens `uri` with `text`, returning the diagnostics published for it.
+        async fn open(&mut self, uri: &str, text: &str) -> Value {
+            self.notify(
+                "textDocument/didOpen",
+                json!({ "textDocument": { "uri": uri, "languageId": "smali", "version": 1, "text": text } }),
+            )
+            .await;
+
+            self.next_publish().await["diagnostics"].clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn save_revalidates() {
+        let mut client = TestClient::start(json!({})).await;
+
+        let uri = "file:///Test.smali";
+        let diagnostics = client.open(uri, ".class public LTest;\n.super Ljava/lang/Object;\n").await;
+        assert_eq!(diagnostics, json!([]));
+
+        // The file was changed on disk by another tool before being saved
+        let saved = ".class public abstract final LTest;\n.super Ljava/lang/Object;\n";
+        client.notify("textDocument/didSave", json!({ "textDocument": { "uri": uri }, "text": saved })).await;
+
+        let published = client.next_publish().await;
+        assert_eq!(published["uri"], uri);
+        // Saving doesn't assign a version, the last one from the client is kept
+        assert_eq!(published["version"], 1);
+        assert!(published["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|diag| diag["message"] == "Class cannot be defined as both abstract and final."));
+    }
+
+    #[tokio::test]
+    async fn unchanged_content_is_not_revalidated() {
+        let mut client = TestClient::start(json!({})).await;
+
+        let uri = "file:///Test.smali";
+        client.open(uri, ".class public LTest;\n.super Ljava/lang/Object;\n").await;
+
+        let change = |version: i32, text: &str| {
+            json!({
+                "textDocument": { "uri": uri, "version": version },
+                "contentChanges": [{
+                    "range": { "start": { "line": 0, "character": 7 }, "end": { "line": 0, "character": 13 } },
+                    "text": text,
+                }]
+            })
+        };
+
+        // Replacing `public` with itself leaves the content as it was
+        client.notify("textDocument/didChange", change(2, "public")).await;
+        client.notify("textDocument/didChange", change(3, "final ")).await;
+
+        assert_eq!(client.next_publish().await["version"], 3);
+    }
+
+    #[tokio::test]
+    async fn configuration_change_revalidates() {
+        let mut client = TestClient::start(json!({ "workspace": { "configuration": true } })).await;
+
+        let uri = "file:///Test.smali";
+        let text = ".class public LTest;\n.super Ljava/lang/Object;\n\n.method public test()V\n    .locals 0x0\n    \
+                    return-void\n.end method\n";
+        assert_eq!(client.open(uri, text).await[0]["severity"], 2);
+
+        let changed = client.spawn_notify("workspace/didChangeConfiguration", json!({ "settings": {} }));
+
+        // Act as the client, answering the pull with correctness diagnostics
+        // raised to errors
+        loop {
+            let message = client.messages.recv().await.unwrap();
+            if message["method"] == "workspace/configuration" {
+                assert_eq!(message["params"]["items"][0]["section"], "smali-lsp");
+
+                client.respond(&message["id"], json!([{ "categories": { "correctness": "error" } }])).await;
+                break;
+            }
+        }
+
+        let published = client.next_publish().await;
+        assert_eq!(published["uri"], uri);
+        assert_eq!(published["diagnostics"][0]["severity"], 1);
+        changed.await;
+    }
+
+    #[tokio::test]
+    async fn routine_validation_is_quiet() {
+        let mut client = TestClient::start(json!({})).await;
+
+        let text = ".class public LTest;\n.super Ljava/lang/Object;\n";
+        for uri in &["file:///First.smali", "file:///Second.smali"] {
+            client.notify(
+                "textDocument/didOpen",
+                json!({ "textDocument": { "uri": uri, "languageId": "smali", "version": 1, "text": text } }),
+            )
+            .await;
+        }
+
+        // Validating only logs at trace level, which is off by default, so
+        // nothing is sent besides the diagnostics
+        let mut messages = Vec::new();
+        loop {
+            let message = client.messages.recv().await.unwrap();
+            let done = message["params"]["uri"] == "file:///Second.smali";
+            messages.push(json!({ "method": message["method"], "params": message["params"] }));
+
+            if done {
+                break;
+            }
+        }
+
+        let publish = |uri: &str| {
+            json!({
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": uri, "diagnostics": [], "version": 1 },
+            })
+        };
+        assert_eq!(messages, vec![publish("file:///First.smali"), publish("file:///Second.smali")]);
+    }
+
+    #[tokio::test]
+    async fn navigate_published_diagnostics() {
+        let mut client = TestClient::start(json!({})).await;
+
+        let uri = "file:///Test.smali";
+        let text = ".class public LTest;\n.super Ljava/lang/Object;\n\n.field private 1first:I\n\n.field private 2second:I\n";
+        client.open(uri, text).await;
+
+        let navigate = |command: &str, line: u32| {
+            json!({
+                "command": command,
+                "arguments": [{ "textDocument": { "uri": uri }, "position": { "line": line, "character": 0 } }],
+            })
+        };
+
+        let position = client.request("workspace/executeCommand", navigate("smali-lsp.nextDiagnostic", 0)).await;
+        assert_eq!(position, json!({ "line": 3, "character": 15 }));
+
+        let position = client.request("workspace/executeCommand", navigate("smali-lsp.nextDiagnostic", 4)).await;
+        assert_eq!(position, json!({ "line": 5, "character": 15 }));
+
+        let position = client.request("workspace/executeCommand", navigate("smali-lsp.prevDiagnostic", 4)).await;
+        assert_eq!(position, json!({ "line": 3, "character": 15 }));
+    }
+
+    #[tokio::test]
+    async fn export_diagnostics_command() {
+        async fn export(client: &mut TestClient) -> Vec<Value> {
+            let output = std::env::temp_dir().join(format!("smali-lsp-export-command-{}.json", std::process::id()));
+            let params = json!({ "command": "smali-lsp.exportDiagnostics", "arguments": [output, "json"] });
+            assert_eq!(client.request("workspace/executeCommand", params).await, json!(output));
+
+            let report = serde_json::from_str::<Value>(&std::fs::read_to_string(&output).unwrap()).unwrap();
+            std::fs::remove_file(output).unwrap();
+
+            report.as_array().unwrap().clone()
+        }
+
+        let root = Url::from_directory_path(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/lint")).unwrap();
+        let valid = root.join("me/l3af/Valid.smali").unwrap();
+        let broken = root.join("me/l3af/util/Broken.smali").unwrap();
+        let text = ".class public Lme/l3af/util/Broken;\n.super Ljava/lang/Object;\n";
+
+        // The open document is fixed but not saved, the report uses the live
+        // content rather than the file on disk
+        let mut client = TestClient::initialize(json!({ "rootUri": root, "capabilities": {} })).await;
+        assert_eq!(client.open(broken.as_str(), text).await, json!([]));
+
+        let files = export(&mut client).await;
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0]["path"], json!(valid.to_file_path().unwrap()));
+        assert_eq!(files[1]["path"], json!(broken.to_file_path().unwrap()));
+        assert_eq!(files[1]["errors"], 0);
+        assert_eq!(files[1]["warnings"], 0);
+        assert_eq!(files[1]["diagnostics"], json!([]));
+
+        // Ignored files are left out, whether open or only on disk
+        let mut client = TestClient::initialize(json!({
+            "rootUri": root,
+            "initializationOptions": { "ignore": ["util/"] },
+            "capabilities": {},
+        }))
+        .await;
+        assert_eq!(client.open(broken.as_str(), text).await, json!([]));
+
+        let files = export(&mut client).await;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0]["path"], json!(valid.to_file_path().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn indexing_reports_progress() {
+        let root = Url::from_directory_path(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/lint")).unwrap();
+        let mut client = TestClient::initialize(json!({
+            "rootUri": root,
+            "capabilities": { "window": { "workDoneProgress": true } },
+        }))
+        .await;
+
+        let initialized = client.spawn_notify("initialized", json!({}));
+
+        // Act as the client, acknowledging requests and recording the
+        // progress notifications until indexing has finished.
+        let mut kinds = Vec::new();
+        while kinds.last().map(String::as_str) != Some("end") {
+            let message = client.messages.recv().await.unwrap();
+            if message["id"].is_number() {
+                client.respond(&message["id"], Value::Null).await;
+            }
+
+            if message["method"] == "$/progress" {
+                kinds.push(message["params"]["value"]["kind"].as_str().unwrap().to_string());
+            }
+        }
+
+        assert_eq!(kinds, vec!["begin", "report", "report", "end"]);
+
+        while let Some(message) = client.messages.recv().await {
+            if message["method"] == "client/registerCapability" {
+                client.respond(&message["id"], Value::Null).await;
+                break;
+            }
+        }
+        initialized.await;
+    }
+
+    #[tokio::test]
+    async fn semantic_tokens_delta() {
+        let mut client = TestClient::start(json!({})).await;
+
+        let uri = "file:///Test.smali";
+        let text = ".class public LTest;\n.super Ljava/lang/Object;\n\n.method public static test()V\n    .locals \
+                    1\n    const/4 v0, 0x1\n    return-void\n.end method\n";
+        client.open(uri, text).await;
+
+        let full = client.request("textDocument/semanticTokens/full", json!({ "textDocument": { "uri": uri } })).await;
+        let full_len = full["data"].as_array().unwrap().len();
+        let result_id = full["resultId"].clone();
+
+        client
+            .notify(
+                "textDocument/didChange",
+                json!({
+                    "textDocument": { "uri": uri, "version": 2 },
+                    "contentChanges": [{
+                        "range": { "start": { "line": 5, "character": 18 }, "end": { "line": 5, "character": 19 } },
+                        "text": "12",
+                    }]
+                }),
+            )
+            .await;
+
+        let delta = client
+            .request(
+                "textDocument/semanticTokens/full/delta",
+                json!({ "textDocument": { "uri": uri }, "previousResultId": result_id }),
+            )
+            .await;
+        let edits = delta["edits"].as_array().unwrap();
+
+        assert_ne!(delta["resultId"], result_id);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0]["deleteCount"], 5);
+        assert_eq!(edits[0]["data"], json!([0, 4, 4, 8, 0]));
+        assert!(edits[0]["start"].as_u64().unwrap() > 0);
+        assert!((edits[0]["start"].as_u64().unwrap() as usize) < full_len);
+    }
+
+    #[tokio::test]
+    async fn ignored_files() {
+        let mut client = TestClient::initialize(json!({
+            "rootUri": "file:///workspace/",
+            "initializationOptions": { "ignore": ["android/"] },
+            "capabilities": {},
+        }))
+        .await;
+
+        let ignored = "file:///workspace/smali/android/Target.smali";
+        let text = ".class public abstract final Landroid/Target;\n.super Ljava/lang/Object;\n";
+        assert_eq!(client.open(ignored, text).await, json!([]));
+
+        let uri = "file:///workspace/smali/Test.smali";
+        let text = ".class public LTest;\n.super Ljava/lang/Object;\n\n.method public static test()V\n    .locals \
+                    1\n    new-instance v0, Landroid/Target;\n    return-void\n.end method\n";
+        client.open(uri, text).await;
+
+        let params = json!({ "textDocument": { "uri": uri }, "position": { "line": 5, "character": 22 } });
+        assert_eq!(client.request("textDocument/typeDefinition", params).await, Value::Null);
+    }
+
+    #[tokio::test]
+    async fn resolve_unsaved_changes() {
+        let mut client = TestClient::start(json!({})).await;
+
+        let target = "file:///Target.smali";
+        client.open(target, ".class public Lme/l3af/Target;\n.super Ljava/lang/Object;\n").await;
+
+        let uri = "file:///Test.smali";
+        let text = ".class public LTest;\n.super Ljava/lang/Object;\n\n.method public static test()V\n    .locals \
+                    1\n    new-instance v0, Lme/l3af/Target;\n    return-void\n.end method\n";
+        client.open(uri, text).await;
+
+        // The edit is never saved, so only the open document knows about it
+        client
+            .notify(
+                "textDocument/didChange",
+                json!({
+                    "textDocument": { "uri": target, "version": 2 },
+                    "contentChanges": [{
+                        "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } },
+                        "text": "# Unsaved\n",
+                    }]
+                }),
+            )
+            .await;
+
+        let params = json!({ "textDocument": { "uri": uri }, "position": { "line": 5, "character": 18 } });
+        assert_eq!(
+            client.request("textDocument/typeDefinition", params.clone()).await,
+            json!({
+                "uri": target,
+                "range": { "start": { "line": 1, "character": 14 }, "end": { "line": 1, "character": 30 } },
+            })
+        );
+
+        // Once closed, the file is read from disk, where it doesn't exist
+        client.notify("textDocument/didClose", json!({ "textDocument": { "uri": target } })).await;
+        assert_eq!(client.request("textDocument/typeDefinition", params).await, Value::Null);
+    }
+
+    #[tokio::test]
+    async fn document_symbols_end_to_end() {
+        let mut client = TestClient::start(json!({})).await;
+
+        let uri = "file:///Test.smali";
+        let text = ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n.method public run()V\n    .locals \
+                    0\n    return-void\n.end method\n";
+        client.open(uri, text).await;
+
+        let symbols = client.request("textDocument/documentSymbol", json!({ "textDocument": { "uri": uri } })).await;
+        assert_eq!(symbols.as_array().unwrap().len(), 1);
+        assert_eq!(symbols[0]["name"], "Lme/l3af/Test;");
+        assert_eq!(symbols[0]["kind"], json!(SymbolKind::Class));
+
+        let children = symbols[0]["children"].as_array().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0]["name"], "run");
+        assert_eq!(children[0]["kind"], json!(SymbolKind::Method));
+        assert_eq!(children[0]["detail"], "()V · 1 instruction");
+    }
+
+    #[tokio::test]
+    async fn format_string_request() {
+        let mut client = TestClient::start(json!({})).await;
+
+        let text = "    const/4   v0,  0x1\n    return   v0\n";
+        let formatted = client.request("smali-lsp/formatString", json!({ "text": text })).await;
+        assert_eq!(formatted, json!({ "text": "    const/4 v0, 0x1\n    return v0\n", "changed": true }));
+
+        let formatted = client.request("smali-lsp/formatString", json!({ "text": formatted["text"] })).await;
+        assert_eq!(formatted["changed"], false);
+    }
+
+    #[tokio::test]
+    async fn nothing_published_after_shutdown() {
+        let mut client = TestClient::start(json!({})).await;
+
+        // The service stops passing on requests once it is shut down, so the
+        // backend is driven directly to shut it down halfway through
+        let backend = Backend::new(client.client.clone());
+        let uri = Url::parse("file:///Test.smali").unwrap();
+        let text = ".class public LTest;\n.super Ljava/lang/Object;\n";
+        backend.documents.did_open(&open_params(&uri, text, 1)).await;
+
+        // Validation has started and waits for indexing to finish
+        let index = backend.index.write().await;
+        let validation = backend.validate(uri);
+        futures::pin_mut!(validation);
+        assert!(futures::poll!(&mut validation).is_pending());
+
+        backend.shutdown().await.unwrap();
+        drop(index);
+        validation.await;
+
+        // Let the forwarding task pass on anything the server sent
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        while let Ok(message) = client.messages.try_recv() {
+            assert_ne!(message["method"], "textDocument/publishDiagnostics");
+        }
+    }
+}
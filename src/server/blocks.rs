@@ -0,0 +1,319 @@
+use lspower::lsp::Range;
+
+use super::{
+    helper::{split_lines, tokens_range},
+    lexer::{lex_str, Token, TokenType},
+};
+
+/// A directive block that can contain other lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Block {
+    Method,
+    /// A `.field` followed by annotations, closed by `.end field`.
+    Field,
+    Param,
+    Annotation,
+    SubAnnotation,
+}
+
+impl Block {
+    /// The directive name of the block, e.g. `method` for `.method`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Block::Method => "method",
+            Block::Field => "field",
+            Block::Param => "param",
+            Block::Annotation => "annotation",
+            Block::SubAnnotation => "subannotation",
+        }
+    }
+
+    /// The block the `.end` directive in `line` closes, if it is one.
+    pub fn closed_by(line: &[Token]) -> Option<Block> {
+        match (&line[0].token_type, line[0].content.as_str()) {
+            (TokenType::Method, ".end method") => Some(Block::Method),
+            (TokenType::Field, ".end field") => Some(Block::Field),
+            (TokenType::Param, ".end param") => Some(Block::Param),
+            (TokenType::Annotation, ".end annotation") => Some(Block::Annotation),
+            (TokenType::Annotation, ".end subannotation") => Some(Block::SubAnnotation),
+            _ => None,
+        }
+    }
+}
+
+/// A block along with the directive that opened it.
+#[derive(Debug)]
+struct OpenBlock {
+    block: Block,
+    /// The range of the line that opened the block.
+    range: Range,
+    /// The class of an `.annotation` or `.subannotation` block.
+    class: Option<String>,
+}
+
+impl OpenBlock {
+    fn new(block: Block, line: &[Token]) -> Self {
+        let class = match block {
+            Block::Annotation | Block::SubAnnotation => line
+                .iter()
+                .find(|token| token.token_type == TokenType::Class)
+                .map(|class| class.content.clone()),
+            _ => None,
+        };
+
+        Self {
+            block,
+            range: tokens_range(line),
+            class,
+        }
+    }
+}
+
+/// Tracks which directive blocks the current line is nested in.
+///
+/// `.field` and `.param` only open a block when an annotation directly
+/// follows them, so they are held as pending until the next line decides
+/// whether the block continues.
+#[derive(Debug, Default)]
+pub struct BlockTracker {
+    stack:   Vec<OpenBlock>,
+    pending: Option<OpenBlock>,
+}
+
+impl BlockTracker {
+    /// The innermost open block.
+    pub fn current(&self) -> Option<Block> {
+        self.stack.last().map(|open| open.block)
+    }
+
+    /// The range of the line that opened the innermost open block.
+    pub fn opener(&self) -> Option<Range> {
+        self.stack.last().map(|open| open.range)
+    }
+
+    /// The class of the innermost open block, if it is an annotation.
+    pub fn annotation_class(&self) -> Option<&str> {
+        self.stack.last().and_then(|open| open.class.as_deref())
+    }
+
+    /// The block enclosing the innermost open block.
+    pub fn parent(&self) -> Option<Block> {
+        self.stack.iter().rev().nth(1).map(|open| open.block)
+    }
+
+    /// The open blocks, outermost first.
+    pub fn blocks(&self) -> Vec<Block> {
+        self.stack.iter().map(|open| open.block).collect()
+    }
+
+    /// Whether the next line is at class level, outside every block.
+    pub fn is_top_level(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Applies the directive in `line` to the block stack, returning the
+    /// depth `line` itself is nested at.
+    pub fn update(&mut self, line: &[Token]) -> usize {
+        let directive = line[0].content.as_str();
+
+        // A blank line in between means the annotation belongs to the class
+        if let Some(pending) = self.pending.take() {
+            if line[0].token_type == TokenType::Annotation
+                && directive == ".annotation"
+                && line[0].range.start.line == pending.range.end.line + 1
+            {
+                self.stack.push(pending);
+            }
+        }
+
+        let depth = self.stack.len();
+
+        match (&line[0].token_type, directive) {
+            (TokenType::Method, ".method") => self.stack.push(OpenBlock::new(Block::Method, line)),
+            (TokenType::Field, ".field") => self.pending = Some(OpenBlock::new(Block::Field, line)),
+            (TokenType::Param, ".param") => self.pending = Some(OpenBlock::new(Block::Param, line)),
+            (TokenType::Annotation, ".annotation") => self.stack.push(OpenBlock::new(Block::Annotation, line)),
+            (TokenType::Annotation, ".subannotation") => self.stack.push(OpenBlock::new(Block::SubAnnotation, line)),
+            _ => {
+                if let Some(block) = Block::closed_by(line) {
+                    self.close(block);
+                }
+            },
+        }
+
+        depth
+    }
+
+    /// Pops up to and including the innermost `block`, unbalanced closers
+    /// are ignored.
+    fn close(&mut self, block: Block) {
+        if let Some(idx) = self.stack.iter().rposition(|open| open.block == block) {
+            self.stack.truncate(idx);
+        }
+    }
+}
+
+/// A change confined to the body of a single method.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MethodEdit {
+    /// The line of the `.method` directive.
+    pub start: u32,
+    /// The line of the `.end method` directive before the change.
+    pub end:   u32,
+    /// The number of lines the change added, negative when it removed lines.
+    pub delta: i64,
+}
+
+impl MethodEdit {
+    /// The line of the `.end method` directive after the change.
+    pub fn new_end(&self) -> u32 {
+        (self.end as i64 + self.delta) as u32
+    }
+}
+
+/// Finds the method whose body contains `range`, the range of `content`
+/// replaced by `text`.
+///
+/// Returns `None` when the change touches the `.method` or `.end method`
+/// lines, or could open or close a method itself.
+pub fn edited_method(content: &str, range: Range, text: &str) -> Option<MethodEdit> {
+    if text.contains(".method") || text.contains(".end method") {
+        return None;
+    }
+
+    let mut tracker = BlockTracker::default();
+    let mut start = None;
+
+    for line in split_lines(lex_str(content)) {
+        let depth = tracker.update(&line);
+        let line_number = line[0].range.start.line;
+
+        match (&line[0].token_type, line[0].content.as_ref()) {
+            (TokenType::Method, ".method") if depth == 0 => start = Some(line_number),
+            (TokenType::Method, ".end method") => {
+                let start = start.take()?;
+
+                if start < range.start.line && range.end.line < line_number {
+                    let removed = (range.end.line - range.start.line) as i64;
+                    let added = text.matches('\n').count() as i64;
+
+                    return Some(MethodEdit {
+                        start,
+                        end: line_number,
+                        delta: added - removed,
+                    });
+                }
+            },
+            _ => {},
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range};
+
+    use super::{edited_method, Block, BlockTracker, MethodEdit};
+    use crate::server::{helper::split_lines, lexer::lex_str};
+
+    fn track(content: &str) -> BlockTracker {
+        let mut tracker = BlockTracker::default();
+        for line in split_lines(lex_str(content)) {
+            tracker.update(&line);
+        }
+
+        tracker
+    }
+
+    #[test]
+    fn method_blocks() {
+        assert_eq!(track(".method public test()V").current(), Some(Block::Method));
+        assert!(track(".method public test()V\n.end method").is_top_level());
+
+        let tracker = track(".method public test()V\n.annotation runtime Lfoo;");
+        assert_eq!(tracker.current(), Some(Block::Annotation));
+        assert_eq!(tracker.parent(), Some(Block::Method));
+        assert_eq!(tracker.depth(), 2);
+    }
+
+    #[test]
+    fn openers() {
+        let tracker = track(".method public test()V\n.annotation runtime Lfoo;");
+        assert_eq!(tracker.opener(), Some(Range::new(Position::new(1, 0), Position::new(1, 25))));
+        assert_eq!(tracker.annotation_class(), Some("Lfoo;"));
+
+        let tracker = track(".method public test()V\n.annotation runtime Lfoo;\n.end annotation");
+        assert_eq!(tracker.opener(), Some(Range::new(Position::new(0, 0), Position::new(0, 22))));
+        assert_eq!(tracker.annotation_class(), None);
+    }
+
+    #[test]
+    fn field_blocks() {
+        assert!(track(".field public count:I\n.method public test()V\n.end method").is_top_level());
+
+        let tracker = track(".field public count:I\n.annotation runtime Lfoo;\n.end annotation");
+        assert_eq!(tracker.current(), Some(Block::Field));
+        assert!(track(".field public count:I\n.annotation runtime Lfoo;\n.end annotation\n.end field").is_top_level());
+
+        let tracker = track(".field public count:I\n\n.annotation system Lfoo;");
+        assert_eq!(tracker.current(), Some(Block::Annotation));
+        assert_eq!(tracker.depth(), 1);
+    }
+
+    #[test]
+    fn line_depth() {
+        let mut tracker = BlockTracker::default();
+        let depths = split_lines(lex_str(
+            ".annotation system Lfoo;\n.end annotation\n.field public count:I\n.annotation runtime Lfoo;\n.end \
+             annotation\n.end field",
+        ))
+        .iter()
+        .map(|line| tracker.update(line))
+        .collect::<Vec<_>>();
+
+        assert_eq!(depths, vec![0, 1, 0, 1, 2, 1]);
+    }
+
+    #[test]
+    fn param_blocks() {
+        let tracker = track(".method public test(I)V\n.param p1\n.annotation build Lfoo;");
+        assert_eq!(tracker.depth(), 3);
+
+        let tracker = track(".method public test(I)V\n.param p1\nreturn-void");
+        assert_eq!(tracker.current(), Some(Block::Method));
+    }
+
+    #[test]
+    fn unbalanced_close() {
+        assert!(track(".end method\n.end annotation").is_top_level());
+        assert!(track(".method public test()V\n.annotation runtime Lfoo;\n.end method").is_top_level());
+    }
+
+    #[test]
+    fn method_edits() {
+        let content = ".class public LTest;\n\n.method public a()V\n    return-void\n.end method\n\n.method public \
+                       b()V\n    nop\n    return-void\n.end method\n";
+        let range = |start: u32, end: u32| Range::new(Position::new(start, 4), Position::new(end, 4));
+
+        assert_eq!(
+            edited_method(content, range(7, 8), ""),
+            Some(MethodEdit {
+                start: 6,
+                end:   9,
+                delta: -1,
+            })
+        );
+        assert_eq!(edited_method(content, range(3, 3), "nop\n    ").unwrap().new_end(), 5);
+
+        assert_eq!(edited_method(content, range(2, 3), ""), None);
+        assert_eq!(edited_method(content, range(3, 7), ""), None);
+        assert_eq!(edited_method(content, range(3, 3), ".end method\n"), None);
+        assert_eq!(edited_method(content, range(0, 0), ""), None);
+    }
+}
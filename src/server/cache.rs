@@ -0,0 +1,165 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use lspower::lsp::Url;
+use serde::{Deserialize, Serialize};
+
+use super::index::{ClassEntry, ClassIndex};
+
+/// Name of the cache file written to the root of the workspace.
+pub const CACHE_FILE: &str = ".smali-lsp-cache.json";
+
+/// Bumped whenever the cached entries change shape or meaning, so caches
+/// written by older versions are discarded instead of misread.
+const CACHE_VERSION: u32 = 1;
+
+/// The classes indexed from each file, keyed by path and kept alongside the
+/// modification time they were read at.
+///
+/// Files that haven't been modified since are restored from the cache rather
+/// than lexed again, which makes warm starts on large projects much faster.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexCache {
+    version: u32,
+    files:   HashMap<PathBuf, CachedFile>,
+}
+
+impl Default for IndexCache {
+    fn default() -> Self {
+        Self {
+            version: CACHE_VERSION,
+            files:   HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedFile {
+    modified: SystemTime,
+    classes:  Vec<ClassEntry>,
+}
+
+impl IndexCache {
+    /// Reads the cache at `path`, starting empty if it is missing, can't be
+    /// parsed or was written by another version.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|cache| cache.version == CACHE_VERSION)
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let content = serde_json::to_string(self).map_err(|why| io::Error::new(io::ErrorKind::Other, why))?;
+
+        fs::write(path, content)
+    }
+
+    /// Indexes the `.smali` file at `path` into `index`, from the cache when
+    /// the file hasn't been modified since it was cached.
+    ///
+    /// Returns whether the cached classes were used.
+    pub fn index_file(&mut self, path: &Path, index: &mut ClassIndex) -> bool {
+        let uri = Url::from_file_path(path);
+        let modified = fs::metadata(path).and_then(|meta| meta.modified());
+
+        let (uri, modified) = match (uri, modified) {
+            (Ok(uri), Ok(modified)) => (uri, modified),
+            _ => {
+                self.files.remove(path);
+                index.index_file(path);
+
+                return false;
+            },
+        };
+
+        if let Some(cached) = self.files.get(path).filter(|cached| cached.modified == modified) {
+            index.remove_uri(&uri);
+            for class in &cached.classes {
+                index.insert(class.clone());
+            }
+
+            return true;
+        }
+
+        index.index_file(path);
+        self.files.insert(path.to_path_buf(), CachedFile {
+            modified,
+            classes: index.classes_from(&uri),
+        });
+
+        false
+    }
+
+    /// Drops every file that isn't in `files`, e.g. because it was deleted.
+    pub fn retain(&mut self, files: &HashSet<PathBuf>) {
+        self.files.retain(|path, _| files.contains(path));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, fs, time::SystemTime};
+
+    use super::IndexCache;
+    use crate::server::index::ClassIndex;
+
+    #[test]
+    fn reuse_unmodified_files() {
+        let dir = std::env::temp_dir().join(format!("smali-lsp-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let file = dir.join("Test.smali");
+        fs::write(&file, ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n").unwrap();
+
+        let cache_file = dir.join(super::CACHE_FILE);
+        let mut cache = IndexCache::default();
+        assert!(!cache.index_file(&file, &mut ClassIndex::default()));
+        cache.save(&cache_file).unwrap();
+
+        let mut cache = IndexCache::load(&cache_file);
+        let mut index = ClassIndex::default();
+        assert!(cache.index_file(&file, &mut index));
+        assert!(index.get("Lme/l3af/Test;").is_some());
+
+        // The file is newer than what was cached
+        cache.files.get_mut(&file).unwrap().modified = SystemTime::UNIX_EPOCH;
+        let mut index = ClassIndex::default();
+        assert!(!cache.index_file(&file, &mut index));
+        assert!(index.get("Lme/l3af/Test;").is_some());
+        assert!(cache.index_file(&file, &mut index));
+
+        cache.retain(&HashSet::new());
+        assert!(cache.files.is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn discard_other_versions() {
+        let dir = std::env::temp_dir().join(format!("smali-lsp-cache-version-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let file = dir.join("Test.smali");
+        fs::write(&file, ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n").unwrap();
+
+        let cache_file = dir.join(super::CACHE_FILE);
+        let mut cache = IndexCache::default();
+        cache.index_file(&file, &mut ClassIndex::default());
+        cache.version = 0;
+        cache.save(&cache_file).unwrap();
+        assert!(IndexCache::load(&cache_file).files.is_empty());
+
+        // Caches from before the version was recorded
+        fs::write(&cache_file, "{\"files\":{}}").unwrap();
+        assert_eq!(IndexCache::load(&cache_file).version, super::CACHE_VERSION);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+use super::lexer::{Token, TokenType};
+
+/// A `.catch` or `.catchall` directive, routing exceptions thrown between
+/// two labels to a handler.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatchDirective {
+    /// The caught exception type, `None` for `.catchall`.
+    pub exception: Option<Token>,
+    pub try_start: Token,
+    pub try_end:   Token,
+    pub handler:   Token,
+}
+
+/// Parses `.catch Lclass/Name; {:try_start .. :try_end} :handler`, or the
+/// `.catchall` form without an exception type.
+///
+/// Returns `None` when `line` isn't a catch directive or is malformed.
+pub fn parse_catch(line: &[Token]) -> Option<CatchDirective> {
+    if line[0].token_type != TokenType::Catch {
+        return None;
+    }
+
+    let operands = line[1..]
+        .iter()
+        .filter(|token| token.token_type != TokenType::Space)
+        .collect::<Vec<_>>();
+
+    let (exception, rest) = match (line[0].content.as_ref(), operands.as_slice()) {
+        (".catch", [exception, rest @ ..]) if exception.token_type == TokenType::Class => {
+            (Some((*exception).clone()), rest)
+        },
+        (".catchall", rest) => (None, rest),
+        _ => return None,
+    };
+
+    match rest {
+        [open, try_start, range, try_end, close, handler]
+            if open.content == "{"
+                && range.token_type == TokenType::RangeOp
+                && close.content == "}"
+                && [try_start, try_end, handler].iter().all(|label| label.token_type == TokenType::Label) =>
+        {
+            Some(CatchDirective {
+                exception,
+                try_start: (*try_start).clone(),
+                try_end: (*try_end).clone(),
+                handler: (*handler).clone(),
+            })
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_catch;
+    use crate::server::{helper::split_lines, lexer::lex_str};
+
+    fn parse(line: &str) -> Option<Vec<String>> {
+        let line = split_lines(lex_str(line)).remove(0);
+
+        parse_catch(&line).map(|catch| {
+            vec![
+                catch.exception.map(|exception| exception.content).unwrap_or_default(),
+                catch.try_start.content,
+                catch.try_end.content,
+                catch.handler.content,
+            ]
+        })
+    }
+
+    #[test]
+    fn catch_directives() {
+        assert_eq!(parse(".catch Ljava/lang/Exception; {:try_start_0 .. :try_end_0} :catch_0"), Some(vec![
+            "Ljava/lang/Exception;".to_string(),
+            ":try_start_0".to_string(),
+            ":try_end_0".to_string(),
+            ":catch_0".to_string(),
+        ]));
+        assert_eq!(
+            parse("    .catchall {:try_start_1 .. :try_end_1} :catchall_0").unwrap()[3],
+            ":catchall_0"
+        );
+    }
+
+    #[test]
+    fn malformed_catch() {
+        assert_eq!(parse(".catch {:try_start_0 .. :try_end_0} :catch_0"), None);
+        assert_eq!(parse(".catchall Ljava/lang/Exception; {:try_start_0 .. :try_end_0} :catch_0"), None);
+        assert_eq!(parse(".catch Ljava/lang/Exception; {:try_start_0} :catch_0"), None);
+        assert_eq!(parse("goto :catch_0"), None);
+    }
+}
@@ -0,0 +1,181 @@
+use std::convert::TryFrom;
+
+use lspower::lsp::{Color, ColorInformation, ColorPresentation, Range, TextEdit};
+
+use super::{
+    helper::{parse_count, split_lines},
+    lexer::{lex_str, TokenType},
+    settings::Settings,
+};
+
+/// Finds integer constants that look like ARGB colors when `showColors` is
+/// enabled.
+///
+/// Only hexadecimal literals are considered, either spelled out with all
+/// eight digits (`0xffff0000`) or negative as baksmali writes them (`-0x1`).
+pub fn document_colors(content: &str, settings: &Settings) -> Vec<ColorInformation> {
+    if !settings.show_colors {
+        return Vec::new();
+    }
+
+    split_lines(lex_str(content))
+        .into_iter()
+        .filter(|line| matches!(line[0].token_type, TokenType::Const | TokenType::ConstInt))
+        .filter(|line| line[0].content == "const" || line[0].content == "const/16")
+        .filter_map(|line| line.into_iter().find(|token| token.token_type == TokenType::Number))
+        .filter_map(|number| {
+            parse_argb(&number.content).map(|argb| ColorInformation {
+                range: number.range,
+                color: decode_color(argb),
+            })
+        })
+        .collect()
+}
+
+/// Offers the hex literal for `color` as a replacement for `range`, in the
+/// form the instruction on that line can hold.
+///
+/// `const/16` only takes a sign extended 16 bit literal, so colors it can't
+/// hold aren't offered at all.
+pub fn color_presentations(content: &str, color: Color, range: Range) -> Vec<ColorPresentation> {
+    let argb = encode_color(color);
+    let opcode = split_lines(lex_str(content))
+        .into_iter()
+        .find(|line| line[0].range.start.line == range.start.line)
+        .map(|line| line[0].content.clone());
+
+    let label = match opcode.as_deref() {
+        Some("const/16") => match i16::try_from(argb as i32) {
+            Ok(value) if value < 0 => format!("-0x{:x}", -i32::from(value)),
+            Ok(value) => format!("0x{:x}", value),
+            Err(_) => return Vec::new(),
+        },
+        _ => format!("0x{:08x}", argb),
+    };
+
+    vec![ColorPresentation {
+        text_edit: Some(TextEdit {
+            range,
+            new_text: label.clone(),
+        }),
+        label,
+        additional_text_edits: None,
+    }]
+}
+
+fn parse_argb(literal: &str) -> Option<u32> {
+    let (negative, literal) = match literal.strip_prefix('-') {
+        Some(literal) => (true, literal),
+        None => (false, literal),
+    };
+    let hex = literal.strip_prefix("0x")?;
+
+    if negative {
+        let value = i64::from(parse_count(literal)?);
+        if value == 0 || value > 0x8000_0000 {
+            return None;
+        }
+
+        Some((-value) as i32 as u32)
+    } else if hex.len() == 8 {
+        parse_count(literal)
+    } else {
+        None
+    }
+}
+
+fn decode_color(argb: u32) -> Color {
+    let channel = |shift: u32| ((argb >> shift) & 0xff) as f32 / 255.0;
+
+    Color {
+        red:   channel(16),
+        green: channel(8),
+        blue:  channel(0),
+        alpha: channel(24),
+    }
+}
+
+fn encode_color(color: Color) -> u32 {
+    let channel = |value: f32, shift: u32| ((value.clamp(0.0, 1.0) * 255.0).round() as u32) << shift;
+
+    channel(color.alpha, 24) | channel(color.red, 16) | channel(color.green, 8) | channel(color.blue, 0)
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Color, Position, Range};
+
+    use super::{color_presentations, document_colors};
+    use crate::server::settings::Settings;
+
+    fn enabled() -> Settings {
+        Settings {
+            show_colors: true,
+            ..Settings::default()
+        }
+    }
+
+    const RED: Color = Color {
+        red:   1.0,
+        green: 0.0,
+        blue:  0.0,
+        alpha: 1.0,
+    };
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(document_colors("const v0, 0xffff0000\n", &Settings::default()).is_empty());
+    }
+
+    #[test]
+    fn opaque_red() {
+        let colors = document_colors("const v0, 0xffff0000\n", &enabled());
+
+        assert_eq!(colors.len(), 1);
+        assert_eq!(colors[0].range, Range::new(Position::new(0, 10), Position::new(0, 20)));
+        assert_eq!(colors[0].color, RED);
+    }
+
+    #[test]
+    fn negative_literal() {
+        let colors = document_colors("const v0, -0x10000\nconst v1, -0x1\n", &enabled());
+
+        assert_eq!(colors.iter().map(|info| info.color.clone()).collect::<Vec<_>>(), vec![RED, Color {
+            red:   1.0,
+            green: 1.0,
+            blue:  1.0,
+            alpha: 1.0,
+        }]);
+    }
+
+    #[test]
+    fn ignores_other_literals() {
+        assert!(document_colors("const/4 v0, 0x1\nconst v0, 0x10\nconst v0, 16711680\n", &enabled()).is_empty());
+    }
+
+    #[test]
+    fn presentation() {
+        let range = Range::new(Position::new(0, 10), Position::new(0, 20));
+        let presentations = color_presentations("const v0, 0xffff0000\n", RED, range);
+
+        assert_eq!(presentations[0].label, "0xffff0000");
+        assert_eq!(presentations[0].text_edit.as_ref().map(|edit| edit.range), Some(range));
+    }
+
+    #[test]
+    fn narrow_presentation() {
+        let range = Range::new(Position::new(0, 13), Position::new(0, 17));
+        let white = Color {
+            red:   1.0,
+            green: 1.0,
+            blue:  1.0,
+            alpha: 1.0,
+        };
+
+        let presentations = color_presentations("const/16 v0, -0x1\n", white, range);
+        assert_eq!(presentations[0].label, "-0x1");
+
+        // Opaque red is out of the range of a 16 bit literal
+        assert!(color_presentations("const/16 v0, -0x1\n", RED, range).is_empty());
+    }
+}
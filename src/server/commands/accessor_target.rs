@@ -0,0 +1,113 @@
+use lspower::lsp::{Location, Position, Url};
+
+use crate::server::{
+    descriptor::{parameter_types, return_type},
+    helper::{method_lines_at, split_lines},
+    index::ClassIndex,
+    lexer::{lex_str, TokenType},
+};
+
+/// Resolves the field or method that the synthetic `access$NNN` accessor at
+/// `position` forwards to.
+///
+/// Accessors are generated so nested classes can reach private members, the
+/// first field access or invoke in the body is the member being accessed.
+/// The class in `content` is used when the target isn't in `index`.
+pub fn accessor_target(uri: &Url, content: &str, position: Position, index: &ClassIndex) -> Option<Location> {
+    let lines = split_lines(lex_str(content));
+    let method = method_lines_at(&lines, position.line)?;
+
+    let is_accessor = method[0]
+        .iter()
+        .any(|token| token.token_type == TokenType::MethodName && token.content.starts_with("access$"));
+    if !is_accessor {
+        return None;
+    }
+
+    let line = method[1..].iter().find(|line| {
+        matches!(
+            line[0].token_type,
+            TokenType::IGet | TokenType::SGet | TokenType::IPut | TokenType::SPut | TokenType::Invoke
+        )
+    })?;
+
+    let idx = line
+        .iter()
+        .position(|token| matches!(token.token_type, TokenType::FieldAccess | TokenType::MethodCall))?;
+    let class = line.get(idx.checked_sub(1)?).filter(|token| token.token_type == TokenType::Class)?;
+
+    let mut current = ClassIndex::default();
+    current.index_content(uri, content);
+    let entry = index.get(&class.content).or_else(|| current.get(&class.content))?;
+
+    let member = line[idx].content.trim_start_matches("->");
+    let range = if line[idx].token_type == TokenType::FieldAccess {
+        entry.field(member.trim_end_matches(':'))?.range
+    } else {
+        let descriptor = format!("({}){}", parameter_types(line)?.concat(), return_type(line)?);
+        entry.method(member.trim_end_matches('('), &descriptor)?.range
+    };
+
+    Some(Location::new(entry.uri.clone(), range))
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Location, Position, Range, Url};
+
+    use super::accessor_target;
+    use crate::server::index::ClassIndex;
+
+    const CONTENT: &str = ".class public Lme/l3af/Outer;
+.super Ljava/lang/Object;
+
+.field private count:I
+
+.method private reset()V
+    .locals 0
+    return-void
+.end method
+
+.method static synthetic access$000(Lme/l3af/Outer;)I
+    .locals 1
+    iget v0, p0, Lme/l3af/Outer;->count:I
+    return v0
+.end method
+
+.method static synthetic access$100(Lme/l3af/Outer;)V
+    .locals 0
+    invoke-direct {p0}, Lme/l3af/Outer;->reset()V
+    return-void
+.end method
+";
+
+    fn target(line: u32) -> Option<Location> {
+        let uri = Url::parse("file:///Outer.smali").unwrap();
+        accessor_target(&uri, CONTENT, Position::new(line, 4), &ClassIndex::default())
+    }
+
+    #[test]
+    fn field_accessor() {
+        let expected = Location::new(
+            Url::parse("file:///Outer.smali").unwrap(),
+            Range::new(Position::new(3, 15), Position::new(3, 21)),
+        );
+
+        assert_eq!(target(10), Some(expected.clone()));
+        assert_eq!(target(12), Some(expected));
+    }
+
+    #[test]
+    fn method_accessor() {
+        assert_eq!(
+            target(18).map(|location| location.range),
+            Some(Range::new(Position::new(5, 16), Position::new(5, 21)))
+        );
+    }
+
+    #[test]
+    fn not_an_accessor() {
+        assert_eq!(target(7), None);
+        assert_eq!(target(3), None);
+    }
+}
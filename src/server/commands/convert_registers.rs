@@ -0,0 +1,82 @@
+use lspower::lsp::{Position, Range, TextEdit};
+
+use crate::server::{
+    descriptor::parameter_register_count,
+    helper::{method_lines_at, parse_count, split_lines},
+    lexer::{lex_str, TokenType},
+};
+
+/// Computes the edit rewriting the `.locals` directive of the method at
+/// `position` to the equivalent `.registers`, or the other way around.
+///
+/// `.registers` also counts the parameter registers, including `this`, so
+/// the two differ by the parameter register count of the descriptor. The
+/// `vN` and `pN` names keep referring to the same registers either way.
+pub fn convert_register_directive(content: &str, position: Position) -> Option<Vec<TextEdit>> {
+    let lines = split_lines(lex_str(content));
+    let method = method_lines_at(&lines, position.line)?;
+    let parameters = parameter_register_count(&method[0])?;
+
+    let line = method[1..].iter().find(|line| {
+        line[0].token_type == TokenType::Directive && matches!(line[0].content.as_str(), ".locals" | ".registers")
+    })?;
+    let number = line.iter().find(|token| token.token_type == TokenType::Number)?;
+    let count = parse_count(&number.content)?;
+
+    // Too few registers for the parameters is reported by the validator,
+    // there is no `.locals` to convert it to
+    let new_text = if line[0].content == ".locals" {
+        format!(".registers {}", count + parameters)
+    } else {
+        format!(".locals {}", count.checked_sub(parameters)?)
+    };
+
+    Some(vec![TextEdit {
+        range: Range::new(line[0].range.start, number.range.end),
+        new_text,
+    }])
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range, TextEdit};
+
+    use super::convert_register_directive;
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.method public test(ILjava/lang/String;)V
+    .locals 2
+
+    const/4 v1, 0x0
+
+    return-void
+.end method
+";
+
+    #[test]
+    fn locals_to_registers() {
+        assert_eq!(convert_register_directive(CONTENT, Position::new(6, 4)), Some(vec![TextEdit {
+            range:    Range::new(Position::new(4, 4), Position::new(4, 13)),
+            new_text: ".registers 5".to_string(),
+        }]));
+    }
+
+    #[test]
+    fn registers_to_locals() {
+        let content = CONTENT.replace(".locals 2", ".registers 5");
+        assert_eq!(convert_register_directive(&content, Position::new(4, 4)), Some(vec![TextEdit {
+            range:    Range::new(Position::new(4, 4), Position::new(4, 16)),
+            new_text: ".locals 2".to_string(),
+        }]));
+
+        let content = CONTENT.replace(".locals 2", ".registers 2");
+        assert_eq!(convert_register_directive(&content, Position::new(4, 4)), None);
+    }
+
+    #[test]
+    fn outside_method() {
+        assert_eq!(convert_register_directive(CONTENT, Position::new(1, 0)), None);
+    }
+}
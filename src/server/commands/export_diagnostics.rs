@@ -0,0 +1,180 @@
+use std::{fs, io, path::Path};
+
+use lspower::lsp::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use serde_json::{json, Value};
+
+use crate::server::lint::FileReport;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Rule id used in SARIF output for diagnostics without a code.
+const DEFAULT_RULE: &str = "smali-lsp";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Json,
+    Sarif,
+}
+
+impl ReportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(ReportFormat::Json),
+            "sarif" => Some(ReportFormat::Sarif),
+            _ => None,
+        }
+    }
+}
+
+/// Renders `reports` in the given format.
+pub fn render_report(reports: &[FileReport], format: ReportFormat) -> Value {
+    match format {
+        ReportFormat::Json => serde_json::to_value(reports).unwrap_or(Value::Null),
+        ReportFormat::Sarif => render_sarif(reports),
+    }
+}
+
+/// Renders `reports` and writes them to `output`, creating any missing
+/// parent directories.
+pub fn export_diagnostics(reports: &[FileReport], format: ReportFormat, output: &Path) -> io::Result<()> {
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let report = serde_json::to_string_pretty(&render_report(reports, format))?;
+    fs::write(output, report)
+}
+
+fn render_sarif(reports: &[FileReport]) -> Value {
+    let results = reports
+        .iter()
+        .flat_map(|report| {
+            report
+                .diagnostics
+                .iter()
+                .map(move |diag| sarif_result(&report.path.to_string_lossy(), diag))
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "smali-lsp",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn sarif_result(path: &str, diag: &Diagnostic) -> Value {
+    let rule = match &diag.code {
+        Some(NumberOrString::String(code)) => code.clone(),
+        Some(NumberOrString::Number(code)) => code.to_string(),
+        None => DEFAULT_RULE.to_string(),
+    };
+
+    let level = match diag.severity {
+        Some(DiagnosticSeverity::Error) => "error",
+        Some(DiagnosticSeverity::Warning) => "warning",
+        Some(DiagnosticSeverity::Information) | Some(DiagnosticSeverity::Hint) => "note",
+        None => "none",
+    };
+
+    // SARIF lines and columns are 1 based
+    json!({
+        "ruleId": rule,
+        "level": level,
+        "message": { "text": diag.message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": path },
+                "region": {
+                    "startLine": diag.range.start.line + 1,
+                    "startColumn": diag.range.start.character + 1,
+                    "endLine": diag.range.end.line + 1,
+                    "endColumn": diag.range.end.character + 1,
+                },
+            },
+        }],
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::PathBuf};
+
+    use lspower::lsp::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+    use serde_json::Value;
+
+    use super::{export_diagnostics, ReportFormat};
+    use crate::server::lint::FileReport;
+
+    fn reports() -> Vec<FileReport> {
+        let diag = Diagnostic {
+            range: Range::new(Position::new(2, 4), Position::new(2, 9)),
+            severity: Some(DiagnosticSeverity::Warning),
+            code: Some(NumberOrString::String("correctness".to_string())),
+            message: "Field 'foo' already declared with type 'I'.".to_string(),
+            ..Diagnostic::default()
+        };
+
+        vec![
+            FileReport::new(PathBuf::from("smali/me/l3af/Test.smali"), vec![diag]),
+            FileReport::new(PathBuf::from("smali/me/l3af/Clean.smali"), Vec::new()),
+        ]
+    }
+
+    fn export(format: ReportFormat, name: &str) -> Value {
+        let output = std::env::temp_dir()
+            .join(format!("smali-lsp-export-{}", std::process::id()))
+            .join(name);
+        export_diagnostics(&reports(), format, &output).unwrap();
+
+        let report = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        fs::remove_file(output).unwrap();
+
+        report
+    }
+
+    #[test]
+    fn export_json() {
+        let report = export(ReportFormat::Json, "report.json");
+        let files = report.as_array().unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0]["path"], "smali/me/l3af/Test.smali");
+        assert_eq!(files[0]["warnings"], 1);
+        assert_eq!(files[0]["diagnostics"][0]["range"]["start"]["line"], 2);
+        assert_eq!(files[1]["diagnostics"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn export_sarif() {
+        let report = export(ReportFormat::Sarif, "report.sarif");
+
+        assert_eq!(report["version"], "2.1.0");
+        assert_eq!(report["runs"][0]["tool"]["driver"]["name"], "smali-lsp");
+
+        let results = report["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "correctness");
+        assert_eq!(results[0]["level"], "warning");
+
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "smali/me/l3af/Test.smali");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(location["region"]["startColumn"], 5);
+    }
+
+    #[test]
+    fn format_names() {
+        assert_eq!(ReportFormat::from_name("sarif"), Some(ReportFormat::Sarif));
+        assert_eq!(ReportFormat::from_name("json"), Some(ReportFormat::Json));
+        assert_eq!(ReportFormat::from_name("xml"), None);
+    }
+}
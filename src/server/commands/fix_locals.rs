@@ -0,0 +1,111 @@
+use lspower::lsp::{Position, Range, TextEdit};
+
+use crate::server::{
+    helper::{method_lines_at, split_lines},
+    lexer::{lex_str, TokenType},
+};
+
+/// Computes the edit setting `.locals` of the method at `position` to the
+/// highest used `vN` register plus one.
+///
+/// Parameter registers are addressed as `pN` when using `.locals` so they
+/// don't count towards the total. Methods using `.registers` are left alone.
+pub fn fix_locals(content: &str, position: Position) -> Option<Vec<TextEdit>> {
+    let lines = split_lines(lex_str(content));
+    let method = method_lines_at(&lines, position.line)?;
+
+    let mut max_register: Option<u32> = None;
+    let mut locals_line = None;
+
+    for line in &method[1..] {
+        match (&line[0].token_type, line[0].content.as_ref()) {
+            (TokenType::Directive, ".registers") => return None,
+            (TokenType::Directive, ".locals") => locals_line = Some(line),
+            _ => {},
+        }
+
+        for token in line.iter().filter(|token| token.token_type == TokenType::Register) {
+            if let Some(Ok(idx)) = token.content.strip_prefix('v').map(str::parse::<u32>) {
+                max_register = Some(max_register.map_or(idx, |max| max.max(idx)));
+            }
+        }
+    }
+
+    let locals = max_register.map_or(0, |max| max + 1);
+
+    match locals_line {
+        Some(line) => {
+            let range = match line.iter().find(|token| token.token_type == TokenType::Number) {
+                Some(number) if number.content == locals.to_string() => return Some(Vec::new()),
+                Some(number) => number.range,
+                None => Range::new(line[0].range.end, line.last().unwrap().range.end),
+            };
+
+            Some(vec![TextEdit {
+                range,
+                new_text: if range.start == line[0].range.end {
+                    format!(" {}", locals)
+                } else {
+                    locals.to_string()
+                },
+            }])
+        },
+        None => {
+            let insert_at = Position::new(method[0][0].range.start.line + 1, 0);
+
+            Some(vec![TextEdit {
+                range:    Range::new(insert_at, insert_at),
+                new_text: format!("    .locals {}\n", locals),
+            }])
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range, TextEdit};
+
+    use super::fix_locals;
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.method public test(I)V
+    .locals 3
+
+    const/4 v5, 0x0
+
+    add-int v0, v5, p1
+
+    return-void
+.end method
+";
+
+    #[test]
+    fn bump_locals() {
+        assert_eq!(fix_locals(CONTENT, Position::new(6, 4)), Some(vec![TextEdit {
+            range:    Range::new(Position::new(4, 12), Position::new(4, 13)),
+            new_text: "6".to_string(),
+        }]));
+    }
+
+    #[test]
+    fn insert_locals() {
+        let content = CONTENT.replace("    .locals 3\n", "");
+        assert_eq!(fix_locals(&content, Position::new(3, 0)), Some(vec![TextEdit {
+            range:    Range::new(Position::new(4, 0), Position::new(4, 0)),
+            new_text: "    .locals 6\n".to_string(),
+        }]));
+    }
+
+    #[test]
+    fn already_correct() {
+        let content = CONTENT.replace(".locals 3", ".locals 6");
+        assert_eq!(fix_locals(&content, Position::new(6, 4)), Some(Vec::new()));
+    }
+
+    #[test]
+    fn outside_method() {
+        assert_eq!(fix_locals(CONTENT, Position::new(1, 0)), None);
+    }
+}
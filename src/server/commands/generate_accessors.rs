@@ -0,0 +1,143 @@
+use lspower::lsp::{Position, Range, TextEdit, Url};
+
+use crate::server::{
+    descriptor::{expected_suffix, is_reference, is_wide},
+    helper::split_lines,
+    index::ClassIndex,
+    lexer::{lex_str, TokenType},
+};
+
+/// Generates a `get<Name>()` and `set<Name>(type)V` method for the field
+/// declared on the line at `position`, inserted after the last method.
+///
+/// Static fields get static accessors using `sget` and `sput`.
+pub fn generate_accessors(uri: &Url, content: &str, position: Position) -> Option<Vec<TextEdit>> {
+    let mut index = ClassIndex::default();
+    index.index_content(uri, content);
+
+    let class = index.classes_from(uri).into_iter().next()?;
+    let field = class.fields.iter().find(|field| field.range.start.line == position.line)?;
+
+    let mut chars = field.name.chars();
+    let name = chars.next()?.to_uppercase().chain(chars).collect::<String>();
+    let target = format!("{}->{}:{}", class.name, field.name, field.field_type);
+    let suffix = expected_suffix(&field.field_type);
+
+    let (modifiers, getter_access, setter_access) = if field.is_static {
+        (
+            "public static",
+            format!("sget{} v0, {}", suffix, target),
+            format!("sput{} p0, {}", suffix, target),
+        )
+    } else {
+        (
+            "public",
+            format!("iget{} v0, p0, {}", suffix, target),
+            format!("iput{} p1, p0, {}", suffix, target),
+        )
+    };
+
+    let (locals, return_op) = if is_wide(&field.field_type) {
+        (2, "return-wide")
+    } else if is_reference(&field.field_type) {
+        (1, "return-object")
+    } else {
+        (1, "return")
+    };
+
+    // Insert after the last `.end method`, or the last line when there are no methods
+    let lines = split_lines(lex_str(content));
+    let insert_at = lines
+        .iter()
+        .rev()
+        .find(|line| line[0].token_type == TokenType::Method && line[0].content == ".end method")
+        .or_else(|| lines.last())?
+        .last()?
+        .range
+        .end;
+
+    Some(vec![TextEdit {
+        range:    Range::new(insert_at, insert_at),
+        new_text: format!(
+            "\n\n.method {modifiers} get{name}(){ty}\n    .locals {locals}\n\n    {getter}\n\n    {ret} v0\n.end \
+             method\n\n.method {modifiers} set{name}({ty})V\n    .locals 0\n\n    {setter}\n\n    return-void\n.end \
+             method",
+            modifiers = modifiers,
+            name = name,
+            ty = field.field_type,
+            locals = locals,
+            getter = getter_access,
+            ret = return_op,
+            setter = setter_access,
+        ),
+    }])
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range, TextEdit, Url};
+
+    use super::generate_accessors;
+
+    const CONTENT: &str = ".class public Lme/l3af/Person;
+.super Ljava/lang/Object;
+
+.field private name:Ljava/lang/String;
+
+.field private static count:J
+
+.method public constructor <init>()V
+    .locals 0
+
+    invoke-direct {p0}, Ljava/lang/Object;-><init>()V
+
+    return-void
+.end method
+";
+
+    #[test]
+    fn object_field() {
+        let uri = Url::parse("file:///Person.smali").unwrap();
+        let insert_at = Position::new(13, 11);
+
+        assert_eq!(generate_accessors(&uri, CONTENT, Position::new(3, 20)), Some(vec![TextEdit {
+            range:    Range::new(insert_at, insert_at),
+            new_text: "
+
+.method public getName()Ljava/lang/String;
+    .locals 1
+
+    iget-object v0, p0, Lme/l3af/Person;->name:Ljava/lang/String;
+
+    return-object v0
+.end method
+
+.method public setName(Ljava/lang/String;)V
+    .locals 0
+
+    iput-object p1, p0, Lme/l3af/Person;->name:Ljava/lang/String;
+
+    return-void
+.end method"
+                .to_string(),
+        }]));
+    }
+
+    #[test]
+    fn static_wide_field() {
+        let uri = Url::parse("file:///Person.smali").unwrap();
+        let edits = generate_accessors(&uri, CONTENT, Position::new(5, 0)).unwrap();
+
+        assert!(edits[0].new_text.contains(".method public static getCount()J\n    .locals 2\n"));
+        assert!(edits[0].new_text.contains("sget-wide v0, Lme/l3af/Person;->count:J"));
+        assert!(edits[0].new_text.contains("return-wide v0"));
+        assert!(edits[0].new_text.contains("sput-wide p0, Lme/l3af/Person;->count:J"));
+    }
+
+    #[test]
+    fn not_a_field() {
+        let uri = Url::parse("file:///Person.smali").unwrap();
+
+        assert_eq!(generate_accessors(&uri, CONTENT, Position::new(0, 0)), None);
+    }
+}
@@ -0,0 +1,45 @@
+mod accessor_target;
+mod convert_registers;
+mod export_diagnostics;
+mod fix_locals;
+mod generate_accessors;
+mod navigate_diagnostics;
+mod strip_comments;
+mod try_range;
+
+pub use self::{
+    accessor_target::accessor_target,
+    convert_registers::convert_register_directive,
+    export_diagnostics::{export_diagnostics, render_report, ReportFormat},
+    fix_locals::fix_locals,
+    generate_accessors::generate_accessors,
+    navigate_diagnostics::{next_diagnostic, previous_diagnostic},
+    strip_comments::strip_comments,
+    try_range::try_ranges,
+};
+
+pub const FORMAT: &str = "smali-lsp.format";
+pub const FIX_LOCALS: &str = "smali-lsp.fixLocals";
+pub const EXPORT_DIAGNOSTICS: &str = "smali-lsp.exportDiagnostics";
+pub const NEXT_DIAGNOSTIC: &str = "smali-lsp.nextDiagnostic";
+pub const PREV_DIAGNOSTIC: &str = "smali-lsp.prevDiagnostic";
+pub const GOTO_ACCESSOR_TARGET: &str = "smali-lsp.gotoAccessorTarget";
+pub const GENERATE_ACCESSORS: &str = "smali-lsp.generateAccessors";
+pub const HIGHLIGHT_TRY_RANGE: &str = "smali-lsp.highlightTryRange";
+pub const CONVERT_REGISTER_DIRECTIVE: &str = "smali-lsp.convertRegisterDirective";
+pub const STRIP_COMMENTS: &str = "smali-lsp.stripComments";
+
+pub fn command_list() -> Vec<String> {
+    vec![
+        FORMAT.to_string(),
+        FIX_LOCALS.to_string(),
+        EXPORT_DIAGNOSTICS.to_string(),
+        NEXT_DIAGNOSTIC.to_string(),
+        PREV_DIAGNOSTIC.to_string(),
+        GOTO_ACCESSOR_TARGET.to_string(),
+        GENERATE_ACCESSORS.to_string(),
+        HIGHLIGHT_TRY_RANGE.to_string(),
+        CONVERT_REGISTER_DIRECTIVE.to_string(),
+        STRIP_COMMENTS.to_string(),
+    ]
+}
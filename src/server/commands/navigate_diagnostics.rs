@@ -0,0 +1,72 @@
+use lspower::lsp::{Diagnostic, Position};
+
+/// Returns the start of the first diagnostic after `position`, wrapping
+/// around to the first diagnostic in the file.
+pub fn next_diagnostic(diagnostics: &[Diagnostic], position: Position) -> Option<Position> {
+    let starts = sorted_starts(diagnostics);
+
+    starts.iter().find(|start| **start > position).or_else(|| starts.first()).copied()
+}
+
+/// Returns the start of the last diagnostic before `position`, wrapping
+/// around to the last diagnostic in the file.
+pub fn previous_diagnostic(diagnostics: &[Diagnostic], position: Position) -> Option<Position> {
+    let starts = sorted_starts(diagnostics);
+
+    starts.iter().rev().find(|start| **start < position).or_else(|| starts.last()).copied()
+}
+
+fn sorted_starts(diagnostics: &[Diagnostic]) -> Vec<Position> {
+    let mut starts = diagnostics.iter().map(|diag| diag.range.start).collect::<Vec<_>>();
+    starts.sort();
+    starts.dedup();
+
+    starts
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::Position;
+
+    use super::{next_diagnostic, previous_diagnostic};
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::{validate, ValidationContext},
+    };
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.field private 1first:I
+
+.field private 2second:I
+";
+
+    #[test]
+    fn navigation_order() {
+        let diags = validate(CONTENT.to_string(), &ValidationContext {
+            index:    &ClassIndex::default(),
+            settings: &Settings::default(),
+        })
+        .unwrap();
+        assert_eq!(diags.len(), 2);
+
+        let first = Position::new(3, 15);
+        let second = Position::new(5, 15);
+
+        assert_eq!(next_diagnostic(&diags, Position::new(0, 0)), Some(first));
+        assert_eq!(next_diagnostic(&diags, first), Some(second));
+        assert_eq!(next_diagnostic(&diags, second), Some(first));
+
+        assert_eq!(previous_diagnostic(&diags, Position::new(6, 0)), Some(second));
+        assert_eq!(previous_diagnostic(&diags, second), Some(first));
+        assert_eq!(previous_diagnostic(&diags, first), Some(second));
+    }
+
+    #[test]
+    fn no_diagnostics() {
+        assert_eq!(next_diagnostic(&[], Position::new(0, 0)), None);
+        assert_eq!(previous_diagnostic(&[], Position::new(0, 0)), None);
+    }
+}
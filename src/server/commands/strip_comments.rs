@@ -0,0 +1,111 @@
+use lspower::lsp::{Range, TextEdit};
+
+use crate::server::lexer::{lex_str, TokenType};
+
+/// Computes the edits removing every `#` comment from `content`, along with
+/// the whitespace before it.
+///
+/// Lines holding nothing but a comment are removed entirely. A `#` inside a
+/// string literal is part of the `String` token, so it is left alone.
+pub fn strip_comments(content: &str) -> Vec<TextEdit> {
+    let tokens = lex_str(content);
+    let mut edits = Vec::new();
+
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.token_type != TokenType::Comment {
+            continue;
+        }
+
+        let line_start = tokens[..idx]
+            .iter()
+            .rposition(|token| token.token_type == TokenType::NewLine)
+            .map_or(0, |newline| newline + 1);
+        let before = &tokens[line_start..idx];
+
+        let range = if before.iter().all(|token| token.token_type == TokenType::Space) {
+            // The line goes along with its newline, if it has one
+            let end = match tokens.get(idx + 1) {
+                Some(newline) if newline.token_type == TokenType::NewLine => newline.range.end,
+                _ => token.range.end,
+            };
+            let start = before.first().unwrap_or(token).range.start;
+
+            Range::new(start, end)
+        } else {
+            let start = match before.last() {
+                Some(space) if space.token_type == TokenType::Space => space.range.start,
+                _ => token.range.start,
+            };
+
+            Range::new(start, token.range.end)
+        };
+
+        edits.push(TextEdit {
+            range,
+            new_text: String::new(),
+        });
+    }
+
+    edits
+}
+
+#[cfg(test)]
+mod test {
+    use super::strip_comments;
+    use crate::server::helper::lsp_range_to_range;
+
+    fn strip(content: &str) -> String {
+        let mut content = content.to_string();
+        for edit in strip_comments(&content).into_iter().rev() {
+            let range = lsp_range_to_range(edit.range, &content);
+            content.replace_range(range, &edit.new_text);
+        }
+
+        content
+    }
+
+    #[test]
+    fn inline_and_full_line_comments() {
+        let content = "# Header comment
+.class public Lme/l3af/Test;
+.super Ljava/lang/Object; # Inline comment
+.source \"Test.java\"
+
+.method public static test()V
+    .locals 1
+
+    # Full line comment
+    const-string v0, \"# Not a comment\"   # Trailing comment
+
+    return-void
+.end method
+";
+
+        assert_eq!(
+            strip(content),
+            ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+.source \"Test.java\"
+
+.method public static test()V
+    .locals 1
+
+    const-string v0, \"# Not a comment\"
+
+    return-void
+.end method
+"
+        );
+    }
+
+    #[test]
+    fn escaped_quote() {
+        assert_eq!(strip("    const-string v0, \"a \\\" # b\" # c\n"), "    const-string v0, \"a \\\" # b\"\n");
+    }
+
+    #[test]
+    fn final_line_comment() {
+        assert_eq!(strip(".class public Lme/l3af/Test;\n# No newline"), ".class public Lme/l3af/Test;\n");
+        assert!(strip_comments(".class public Lme/l3af/Test;\n").is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+use lspower::lsp::{Position, Range};
+
+use crate::server::{
+    catch::{parse_catch, CatchDirective},
+    helper::{method_lines_at, split_lines, token_at},
+    lexer::{lex_str, Token, TokenType},
+};
+
+/// Returns the ranges covering the try body and the handler of every catch
+/// directive involving the `.catch` line or try label at `position`.
+///
+/// The try body runs from the start label to the end label, the handler from
+/// its label to the first instruction that doesn't fall through.
+pub fn try_ranges(content: &str, position: Position) -> Option<Vec<Range>> {
+    let tokens = lex_str(content);
+    let lines = split_lines(tokens.clone());
+    let method = method_lines_at(&lines, position.line)?;
+
+    let catches = method.iter().filter_map(|line| parse_catch(line)).collect::<Vec<_>>();
+    let label = token_at(&tokens, position).filter(|token| token.token_type == TokenType::Label);
+
+    let involves = |catch: &CatchDirective| match label {
+        Some(label) => [&catch.try_start, &catch.try_end, &catch.handler]
+            .iter()
+            .any(|token| token.content == label.content),
+        // Anywhere else on the `.catch` line itself
+        None => catch.handler.range.start.line == position.line,
+    };
+
+    let mut ranges = Vec::new();
+    for catch in catches.iter().filter(|catch| involves(catch)) {
+        let try_start = definition(method, &catch.try_start)?;
+        let try_end = definition(method, &catch.try_end)?;
+        let handler_start = definition(method, &catch.handler)?;
+
+        let handler_end = method[handler_start..]
+            .iter()
+            .find(|line| line[0].token_type.is_terminal())
+            .unwrap_or(&method[method.len() - 1]);
+
+        let try_body = Range::new(method[try_start][0].range.start, method[try_end][0].range.end);
+        let handler = Range::new(method[handler_start][0].range.start, handler_end.last()?.range.end);
+
+        for range in [try_body, handler].iter() {
+            if !ranges.contains(range) {
+                ranges.push(*range);
+            }
+        }
+    }
+
+    if ranges.is_empty() {
+        None
+    } else {
+        Some(ranges)
+    }
+}
+
+/// The index of the line in `method` defining `label`.
+fn definition(method: &[Vec<Token>], label: &Token) -> Option<usize> {
+    method
+        .iter()
+        .position(|line| line[0].token_type == TokenType::Label && line[0].content == label.content)
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range};
+
+    use super::try_ranges;
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.method public static test()V
+    .locals 1
+
+    :try_start_0
+    invoke-static {}, Lfoo;->bar()V
+    :try_end_0
+    .catch Ljava/lang/Exception; {:try_start_0 .. :try_end_0} :catch_0
+
+    return-void
+
+    :catch_0
+    move-exception v0
+    throw v0
+.end method
+";
+
+    #[test]
+    fn try_and_handler() {
+        let expected = vec![
+            Range::new(Position::new(6, 4), Position::new(8, 14)),
+            Range::new(Position::new(13, 4), Position::new(15, 12)),
+        ];
+
+        assert_eq!(try_ranges(CONTENT, Position::new(9, 8)), Some(expected.clone()));
+        assert_eq!(try_ranges(CONTENT, Position::new(6, 6)), Some(expected.clone()));
+        assert_eq!(try_ranges(CONTENT, Position::new(13, 6)), Some(expected));
+    }
+
+    #[test]
+    fn outside_try() {
+        assert_eq!(try_ranges(CONTENT, Position::new(11, 6)), None);
+        assert_eq!(try_ranges(CONTENT, Position::new(1, 0)), None);
+    }
+}
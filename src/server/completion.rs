@@ -0,0 +1,122 @@
+use lspower::lsp::{CompletionItem, CompletionItemKind, CompletionTextEdit, Position, Range, TextEdit};
+
+use super::{descriptor::expected_suffix, index::ClassIndex};
+
+/// Produces the completions for `position`, currently the fields of the
+/// class in a field instruction's `Lclass/Name;->` operand.
+pub fn completions(content: &str, position: Position, index: &ClassIndex) -> Vec<CompletionItem> {
+    field_completions(content, position, index).unwrap_or_default()
+}
+
+/// Offers the fields of the class before `->` that the opcode starting the
+/// line can access, i.e. static fields for `sget`/`sput` and instance fields
+/// for `iget`/`iput`, of the type its suffix expects.
+///
+/// Each completion replaces the part of the name typed so far with
+/// `name:Type`.
+fn field_completions(content: &str, position: Position, index: &ClassIndex) -> Option<Vec<CompletionItem>> {
+    let line = content.lines().nth(position.line as usize)?;
+    let prefix = line.get(..position.character as usize)?;
+
+    // Method references follow `->` too, the opcode tells the two apart
+    let opcode = prefix.split_whitespace().next()?;
+    let is_static = match opcode.get(..4)? {
+        "iget" | "iput" => false,
+        "sget" | "sput" => true,
+        _ => return None,
+    };
+    let suffix = &opcode[4..];
+
+    let arrow = prefix.rfind("->")?;
+    let typed = &prefix[arrow + 2..];
+    if !typed.chars().all(|c| c.is_ascii_alphanumeric() || c == '$' || c == '_') {
+        return None;
+    }
+
+    let class = prefix[..arrow].rsplit(|c: char| c.is_whitespace() || c == ',').next()?;
+    let class = index.get(class)?;
+    let range = Range::new(Position::new(position.line, position.character - typed.len() as u32), position);
+
+    let items = class
+        .fields
+        .iter()
+        .filter(|field| field.is_static == is_static && expected_suffix(&field.field_type) == suffix)
+        .map(|field| CompletionItem {
+            label: field.name.clone(),
+            kind: Some(CompletionItemKind::Field),
+            detail: Some(field.field_type.clone()),
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+                range,
+                new_text: format!("{}:{}", field.name, field.field_type),
+            })),
+            ..CompletionItem::default()
+        })
+        .collect();
+
+    Some(items)
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{CompletionTextEdit, Position, Range, Url};
+
+    use super::completions;
+    use crate::server::index::ClassIndex;
+
+    fn index() -> ClassIndex {
+        let mut index = ClassIndex::default();
+        index.index_content(
+            &Url::parse("file:///Target.smali").unwrap(),
+            ".class public Lme/l3af/Target;\n.super Ljava/lang/Object;\n\n.field private static \
+             INSTANCE:Lme/l3af/Target;\n\n.field private name:Ljava/lang/String;\n\n.field private count:I\n",
+        );
+
+        index
+    }
+
+    /// The label and inserted text of each completion at the end of `line`.
+    fn complete(line: &str) -> Vec<(String, String)> {
+        let content = format!(".method public test()V\n{}\n.end method\n", line);
+
+        completions(&content, Position::new(1, line.len() as u32), &index())
+            .into_iter()
+            .map(|item| match item.text_edit {
+                Some(CompletionTextEdit::Edit(edit)) => (item.label, edit.new_text),
+                edit => panic!("Unexpected completion edit: {:?}", edit),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn object_fields() {
+        assert_eq!(complete("    iget-object v0, p0, Lme/l3af/Target;->"), vec![(
+            "name".to_string(),
+            "name:Ljava/lang/String;".to_string()
+        )]);
+        assert_eq!(complete("    sget-object v0, Lme/l3af/Target;->IN"), vec![(
+            "INSTANCE".to_string(),
+            "INSTANCE:Lme/l3af/Target;".to_string()
+        )]);
+        assert_eq!(complete("    iput v0, p0, Lme/l3af/Target;->"), vec![(
+            "count".to_string(),
+            "count:I".to_string()
+        )]);
+    }
+
+    #[test]
+    fn replaces_typed_name() {
+        let content = "    iget-object v0, p0, Lme/l3af/Target;->na";
+        let edit = match &completions(content, Position::new(0, 44), &index())[0].text_edit {
+            Some(CompletionTextEdit::Edit(edit)) => edit.range,
+            edit => panic!("Unexpected completion edit: {:?}", edit),
+        };
+
+        assert_eq!(edit, Range::new(Position::new(0, 42), Position::new(0, 44)));
+    }
+
+    #[test]
+    fn other_contexts() {
+        assert!(complete("    invoke-virtual {p0}, Lme/l3af/Target;->").is_empty());
+        assert!(complete("    iget-object v0, p0, Lme/l3af/Other;->").is_empty());
+    }
+}
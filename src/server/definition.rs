@@ -0,0 +1,230 @@
+use lspower::lsp::{Location, Position};
+
+use super::{
+    descriptor::{parameter_types, return_type},
+    helper::{method_lines_at, split_lines, token_at},
+    index::ClassIndex,
+    lexer::{lex_str, TokenType},
+    registers::{RegisterTracker, RegisterType},
+};
+
+/// How many superclasses to walk up when looking for an inherited method.
+const MAX_SUPER_DEPTH: usize = 32;
+
+/// Resolves the class, method or field referenced under `position` to the
+/// name in its declaration, so the editor lands on the identifier rather
+/// than the `.class`, `.method` or `.field` directive.
+pub fn definition(content: &str, position: Position, index: &ClassIndex) -> Option<Location> {
+    let tokens = lex_str(content);
+    let token = token_at(&tokens, position)?;
+
+    if token.token_type == TokenType::Class {
+        let class = index.get(token.content.trim_start_matches('['))?;
+
+        return Some(Location::new(class.uri.clone(), class.range));
+    }
+
+    if token.token_type != TokenType::MethodCall && token.token_type != TokenType::FieldAccess {
+        return None;
+    }
+
+    let lines = split_lines(tokens.clone());
+    let line = lines.iter().find(|line| line[0].range.start.line == position.line)?;
+
+    let idx = line.iter().position(|call| call.range == token.range)?;
+    let owner = line.get(idx.checked_sub(1)?).filter(|owner| owner.token_type == TokenType::Class)?;
+    let mut class = index.get(owner.content.trim_start_matches('['))?;
+
+    // Field references, including the `.enum` values of annotations
+    if token.token_type == TokenType::FieldAccess {
+        let field = class.field(token.content.trim_start_matches("->").trim_end_matches(':'))?;
+
+        return Some(Location::new(class.uri.clone(), field.range));
+    }
+
+    let name = token.content.trim_start_matches("->").trim_end_matches('(');
+    let descriptor = format!("({}){}", parameter_types(line)?.concat(), return_type(line)?);
+
+    // Inherited methods resolve to the ancestor declaring them, the walk
+    // ends at the first class that isn't indexed, such as framework classes
+    for _ in 0..MAX_SUPER_DEPTH {
+        if let Some(method) = class.method(name, &descriptor) {
+            return Some(Location::new(class.uri.clone(), method.range));
+        }
+
+        class = index.get(class.super_class.as_deref()?)?;
+    }
+
+    None
+}
+
+/// Resolves the register under `position` to the `.class` declaration of
+/// the type most recently written to it within the enclosing method.
+pub fn type_definition(content: &str, position: Position, index: &ClassIndex) -> Option<Location> {
+    let tokens = lex_str(content);
+    let register = token_at(&tokens, position).filter(|token| token.token_type == TokenType::Register)?;
+
+    let lines = split_lines(tokens.clone());
+    let method = method_lines_at(&lines, position.line)?;
+
+    let mut tracker = RegisterTracker::default();
+    for line in method.iter().take_while(|line| line[0].range.start.line <= position.line) {
+        tracker.update(line);
+    }
+
+    let descriptor = match tracker.get(&register.content)? {
+        RegisterType::Reference(descriptor) | RegisterType::Uninitialized(descriptor) => descriptor,
+        _ => return None,
+    };
+
+    let class = index.get(descriptor.trim_start_matches('['))?;
+
+    Some(Location::new(class.uri.clone(), class.range))
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Location, Position, Range, Url};
+
+    use super::{definition, type_definition};
+    use crate::server::index::ClassIndex;
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.method public static test()V
+    .locals 2
+
+    new-instance v0, Lme/l3af/Target;
+
+    invoke-direct {v0}, Lme/l3af/Target;-><init>()V
+
+    const/4 v1, 0x1
+
+    return-void
+.end method
+";
+
+    fn index() -> ClassIndex {
+        let mut index = ClassIndex::default();
+        index.index_content(
+            &Url::parse("file:///Target.smali").unwrap(),
+            ".class public Lme/l3af/Target;\n.super Ljava/lang/Object;\n\n.method public constructor <init>()V\n    \
+             .locals 0\n    return-void\n.end method\n",
+        );
+
+        index
+    }
+
+    #[test]
+    fn register_type_definition() {
+        let expected = Some(Location::new(
+            Url::parse("file:///Target.smali").unwrap(),
+            Range::new(Position::new(0, 14), Position::new(0, 30)),
+        ));
+
+        assert_eq!(type_definition(CONTENT, Position::new(8, 20), &index()), expected);
+        assert_eq!(type_definition(CONTENT, Position::new(6, 18), &index()), expected);
+    }
+
+    #[test]
+    fn primitive_or_unresolved() {
+        assert_eq!(type_definition(CONTENT, Position::new(10, 12), &index()), None);
+        assert_eq!(type_definition(CONTENT, Position::new(8, 20), &ClassIndex::default()), None);
+        assert_eq!(type_definition(CONTENT, Position::new(0, 2), &index()), None);
+    }
+
+    #[test]
+    fn lands_on_name_token() {
+        let target = Url::parse("file:///Target.smali").unwrap();
+
+        // `<init>` on the `.method` line, not the directive at column zero
+        assert_eq!(
+            definition(CONTENT, Position::new(8, 44), &index()),
+            Some(Location::new(target.clone(), Range::new(Position::new(3, 27), Position::new(3, 33))))
+        );
+        assert_eq!(
+            definition(CONTENT, Position::new(6, 25), &index()),
+            Some(Location::new(target, Range::new(Position::new(0, 14), Position::new(0, 30))))
+        );
+    }
+
+    #[test]
+    fn unresolved_definition() {
+        let content = CONTENT.replace("-><init>()V", "-><init>(I)V");
+
+        assert_eq!(definition(&content, Position::new(8, 44), &index()), None);
+        assert_eq!(definition(CONTENT, Position::new(8, 44), &ClassIndex::default()), None);
+        assert_eq!(definition(CONTENT, Position::new(8, 20), &index()), None);
+    }
+
+    #[test]
+    fn inherited_method() {
+        let mut index = ClassIndex::default();
+        index.index_content(
+            &Url::parse("file:///Base.smali").unwrap(),
+            ".class public Lme/l3af/Base;\n.super Ljava/lang/Object;\n\n.method public run()V\n    .locals 0\n    \
+             return-void\n.end method\n",
+        );
+        index.index_content(
+            &Url::parse("file:///Middle.smali").unwrap(),
+            ".class public Lme/l3af/Middle;\n.super Lme/l3af/Base;\n",
+        );
+        index.index_content(
+            &Url::parse("file:///Target.smali").unwrap(),
+            ".class public Lme/l3af/Target;\n.super Lme/l3af/Middle;\n",
+        );
+
+        let content = CONTENT.replace("-><init>()V", "->run()V");
+        assert_eq!(
+            definition(&content, Position::new(8, 44), &index),
+            Some(Location::new(
+                Url::parse("file:///Base.smali").unwrap(),
+                Range::new(Position::new(3, 15), Position::new(3, 18))
+            ))
+        );
+
+        // The chain is broken by an unindexed class
+        index.remove_uri(&Url::parse("file:///Middle.smali").unwrap());
+        assert_eq!(definition(&content, Position::new(8, 44), &index), None);
+    }
+
+    #[test]
+    fn enum_value() {
+        let mut index = ClassIndex::default();
+        index.index_content(
+            &Url::parse("file:///Kind.smali").unwrap(),
+            ".class public final enum Lme/l3af/Kind;\n.super Ljava/lang/Enum;\n\n.field public static final enum \
+             FIRST:Lme/l3af/Kind;\n",
+        );
+
+        let content = ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n.annotation runtime \
+                       Lme/l3af/Marker;\n    value = .enum Lme/l3af/Kind;->FIRST:Lme/l3af/Kind;\n.end annotation\n";
+        assert_eq!(
+            definition(content, Position::new(4, 35), &index),
+            Some(Location::new(
+                Url::parse("file:///Kind.smali").unwrap(),
+                Range::new(Position::new(3, 32), Position::new(3, 38))
+            ))
+        );
+        assert_eq!(definition(&content.replace("->FIRST:", "->SECOND:"), Position::new(4, 35), &index), None);
+    }
+
+    #[test]
+    fn implemented_interface() {
+        let mut index = ClassIndex::default();
+        index.index_content(
+            &Url::parse("file:///Runnable.smali").unwrap(),
+            ".class public interface abstract Lme/l3af/Runnable;\n.super Ljava/lang/Object;\n",
+        );
+
+        let content = ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n.implements Lme/l3af/Runnable;\n";
+        assert_eq!(
+            definition(content, Position::new(2, 16), &index),
+            Some(Location::new(
+                Url::parse("file:///Runnable.smali").unwrap(),
+                Range::new(Position::new(0, 33), Position::new(0, 51))
+            ))
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use super::lexer::{Token, TokenType};
+
+/// Reads a single type descriptor (e.g. `I`, `Lfoo/Bar;`, `[[J`) from the
+/// start of `tokens`, returning it alongside the number of tokens consumed.
+pub fn read_type(tokens: &[Token]) -> Option<(String, usize)> {
+    let mut descriptor = String::new();
+
+    for (idx, token) in tokens.iter().enumerate() {
+        match token.token_type {
+            TokenType::ArrayOp => descriptor.push('['),
+            TokenType::BuiltinType | TokenType::Class => {
+                descriptor.push_str(&token.content);
+                return Some((descriptor, idx + 1));
+            },
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Returns the return type of the method declaration or reference in `line`,
+/// read from the tokens following the closing `)`.
+pub fn return_type(line: &[Token]) -> Option<String> {
+    let idx = line.iter().position(|token| token.content == ")")?;
+
+    read_type(&line[idx + 1..]).map(|(descriptor, _)| descriptor)
+}
+
+/// Returns the parameter types of the method declaration or reference in
+/// `line`, read from the tokens between the method name and the closing `)`.
+pub fn parameter_types(line: &[Token]) -> Option<Vec<String>> {
+    let start = line
+        .iter()
+        .position(|token| token.token_type == TokenType::MethodName || token.token_type == TokenType::MethodCall)?;
+
+    let mut params = Vec::new();
+    let mut idx = start + 1;
+    while line.get(idx)?.content != ")" {
+        let (descriptor, consumed) = read_type(&line[idx..])?;
+        params.push(descriptor);
+        idx += consumed;
+    }
+
+    Some(params)
+}
+
+/// Returns the number of parameter registers the method declared in `line`
+/// takes, including the implicit `this` of non-static methods.
+pub fn parameter_register_count(line: &[Token]) -> Option<u32> {
+    let is_static = line
+        .iter()
+        .any(|token| token.token_type == TokenType::Modifier && token.content == "static");
+
+    let registers = parameter_types(line)?
+        .iter()
+        .map(|param| if is_wide(param) { 2 } else { 1 })
+        .sum::<u32>();
+
+    Some(if is_static { registers } else { registers + 1 })
+}
+
+/// Converts a type descriptor to its Java source name, e.g. `[Ljava/lang/String;`
+/// becomes `java.lang.String[]`.
+pub fn java_name(descriptor: &str) -> String {
+    if let Some(element) = descriptor.strip_prefix('[') {
+        return format!("{}[]", java_name(element));
+    }
+
+    match descriptor {
+        "V" => "void".to_string(),
+        "Z" => "boolean".to_string(),
+        "B" => "byte".to_string(),
+        "S" => "short".to_string(),
+        "C" => "char".to_string(),
+        "I" => "int".to_string(),
+        "J" => "long".to_string(),
+        "F" => "float".to_string(),
+        "D" => "double".to_string(),
+        _ => descriptor.trim_start_matches('L').trim_end_matches(';').replace('/', "."),
+    }
+}
+
+pub fn is_reference(descriptor: &str) -> bool {
+    descriptor.starts_with('L') || descriptor.starts_with('[')
+}
+
+pub fn is_wide(descriptor: &str) -> bool {
+    descriptor == "J" || descriptor == "D"
+}
+
+/// Returns the opcode suffix for accessing a field or array element of type
+/// `descriptor`, e.g. `-wide` for `J`.
+pub fn expected_suffix(descriptor: &str) -> &'static str {
+    match descriptor {
+        "J" | "D" => "-wide",
+        "Z" => "-boolean",
+        "B" => "-byte",
+        "C" => "-char",
+        "S" => "-short",
+        "I" | "F" => "",
+        _ => "-object",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{java_name, parameter_register_count, parameter_types, read_type, return_type};
+    use crate::server::lexer::lex_str;
+
+    #[test]
+    fn read_types() {
+        assert_eq!(read_type(&lex_str("I")), Some(("I".to_string(), 1)));
+        assert_eq!(read_type(&lex_str("[[Lfoo/Bar;")), Some(("[[Lfoo/Bar;".to_string(), 3)));
+        assert_eq!(read_type(&lex_str("[")), None);
+        assert_eq!(read_type(&lex_str(")")), None);
+    }
+
+    #[test]
+    fn return_types() {
+        assert_eq!(
+            return_type(&lex_str("invoke-static {}, Lfoo;->bar(I)[Ljava/lang/String;")),
+            Some("[Ljava/lang/String;".to_string())
+        );
+        assert_eq!(return_type(&lex_str(".method public bar(I)V")), Some("V".to_string()));
+        assert_eq!(return_type(&lex_str("invoke-static {}, Lfoo;->bar(I)")), None);
+    }
+
+    #[test]
+    fn parameters() {
+        let line = lex_str(".method public bar(IJ[Lfoo;)V");
+        assert_eq!(
+            parameter_types(&line),
+            Some(vec!["I".to_string(), "J".to_string(), "[Lfoo;".to_string()])
+        );
+        assert_eq!(parameter_register_count(&line), Some(5));
+
+        let line = lex_str(".method public static bar()V");
+        assert_eq!(parameter_types(&line), Some(Vec::new()));
+        assert_eq!(parameter_register_count(&line), Some(0));
+
+        assert_eq!(parameter_types(&lex_str(".method public bar(I")), None);
+    }
+
+    #[test]
+    fn java_names() {
+        assert_eq!(java_name("Lcom/foo/Bar$Inner;"), "com.foo.Bar$Inner");
+        assert_eq!(java_name("[[I"), "int[][]");
+        assert_eq!(java_name("[Ljava/lang/String;"), "java.lang.String[]");
+        assert_eq!(java_name("V"), "void");
+    }
+}
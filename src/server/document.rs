@@ -0,0 +1,176 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use lspower::lsp::{Position, Range, Url};
+use tokio::sync::RwLock;
+
+use super::{
+    blocks::MethodEdit,
+    error::CacheError,
+    helper::{lsp_pos_to_pos, lsp_range_to_range, pos_to_lsp_pos},
+};
+
+#[derive(Debug)]
+pub struct Document {
+    pub uri:            Url,
+    pub content:        RwLock<String>,
+    /// The version the client last assigned, published with diagnostics.
+    pub version:        RwLock<i32>,
+    /// Bumped by the server on every change to `content`, including the text
+    /// replaced on save which comes without a new `version`.
+    pub revision:       RwLock<u64>,
+    /// Hash of `content`, kept in step with every update.
+    pub hash:           RwLock<u64>,
+    /// Hash of the content the last published diagnostics were computed for.
+    pub validated_hash: RwLock<Option<u64>>,
+    /// The method the last change was confined to, when the diagnostics
+    /// published before it can be updated for just that method.
+    pub method_edit:    RwLock<Option<MethodEdit>>,
+}
+
+impl Document {
+    pub fn new(uri: Url, content: String, version: i32) -> Self {
+        Self {
+            uri,
+            hash: RwLock::new(content_hash(&content)),
+            content: RwLock::new(content),
+            version: RwLock::new(version),
+            revision: RwLock::new(0),
+            validated_hash: RwLock::new(None),
+            method_edit: RwLock::new(None),
+        }
+    }
+
+    pub async fn update(&self, range: Range, content: String) -> Result<(), CacheError> {
+        let mut current = self.content.write().await;
+
+        if !contains_range(&current, range) {
+            return Err(CacheError::InvalidRange(range));
+        }
+
+        let range = lsp_range_to_range(range, &current);
+        current.replace_range(range, &content);
+        *self.hash.write().await = content_hash(&current);
+        *self.revision.write().await += 1;
+
+        Ok(())
+    }
+
+    /// Replaces the whole content, e.g. with the text sent on save.
+    ///
+    /// Only the revision is bumped, the client's version is left alone as it
+    /// didn't assign one to the saved text.
+    pub async fn replace(&self, content: String) {
+        let mut current = self.content.write().await;
+
+        if *current != content {
+            *self.hash.write().await = content_hash(&content);
+            *self.revision.write().await += 1;
+            *current = content;
+        }
+    }
+}
+
+pub fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+/// Whether both ends of `range` are positions in `content`, measured the same
+/// way `lsp_range_to_range` converts them. Positions it would have to clamp
+/// don't survive the round trip back.
+fn contains_range(content: &str, range: Range) -> bool {
+    let line_count = content.split('\n').count() as u32;
+    let in_bounds = |position: Position| {
+        position.line < line_count && pos_to_lsp_pos(lsp_pos_to_pos(position, content), content) == position
+    };
+
+    range.start <= range.end && in_bounds(range.start) && in_bounds(range.end)
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range, Url};
+
+    use super::{content_hash, Document};
+    use crate::server::error::CacheError;
+
+    fn document(content: &str) -> Document {
+        Document::new(Url::parse("file:///Test.smali").unwrap(), content.to_string(), 0)
+    }
+
+    #[tokio::test]
+    async fn update_in_range() {
+        let doc = document(".class public LTest;\n");
+        let range = Range::new(Position::new(0, 7), Position::new(0, 13));
+
+        assert_eq!(doc.update(range, "final".to_string()).await, Ok(()));
+        assert_eq!(*doc.content.read().await, ".class final LTest;\n");
+
+        let end = Position::new(1, 0);
+        assert_eq!(doc.update(Range::new(end, end), ".super".to_string()).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn update_multibyte() {
+        let doc = document("const-string v0, \"\u{e9}\"\n");
+        let inside = Range::new(Position::new(0, 19), Position::new(0, 19));
+
+        assert_eq!(doc.update(inside, String::new()).await, Err(CacheError::InvalidRange(inside)));
+
+        let range = Range::new(Position::new(0, 18), Position::new(0, 20));
+        assert_eq!(doc.update(range, "e".to_string()).await, Ok(()));
+        assert_eq!(*doc.content.read().await, "const-string v0, \"e\"\n");
+    }
+
+    #[tokio::test]
+    async fn update_out_of_range() {
+        let doc = document(".class public LTest;\n");
+
+        for range in &[
+            Range::new(Position::new(0, 7), Position::new(0, 40)),
+            Range::new(Position::new(3, 0), Position::new(3, 1)),
+            Range::new(Position::new(0, 9), Position::new(0, 2)),
+        ] {
+            assert_eq!(
+                doc.update(*range, String::new()).await,
+                Err(CacheError::InvalidRange(*range))
+            );
+        }
+
+        assert_eq!(*doc.content.read().await, ".class public LTest;\n");
+    }
+
+    #[tokio::test]
+    async fn hash_follows_content() {
+        let doc = document(".class public LTest;\n");
+        let original = *doc.hash.read().await;
+        let range = Range::new(Position::new(0, 7), Position::new(0, 13));
+
+        doc.update(range, "public".to_string()).await.unwrap();
+        assert_eq!(*doc.hash.read().await, original);
+
+        doc.update(range, "final ".to_string()).await.unwrap();
+        assert_eq!(*doc.hash.read().await, content_hash(".class final  LTest;\n"));
+        assert_ne!(*doc.hash.read().await, original);
+
+        doc.replace(".class public LTest;\n".to_string()).await;
+        assert_eq!(*doc.hash.read().await, original);
+    }
+
+    #[tokio::test]
+    async fn replace_bumps_revision() {
+        let doc = document(".class public LTest;\n");
+
+        doc.replace(".class public LTest;\n".to_string()).await;
+        assert_eq!(*doc.revision.read().await, 0);
+
+        doc.replace(".class final LTest;\n".to_string()).await;
+        assert_eq!(*doc.revision.read().await, 1);
+        assert_eq!(*doc.version.read().await, 0);
+    }
+}
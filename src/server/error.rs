@@ -0,0 +1,53 @@
+use std::{error::Error, fmt};
+
+use lspower::lsp::{Range, Url};
+
+/// Failures while reading or editing the open document cache.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheError {
+    /// No document with this uri is open.
+    DocumentNotFound(Url),
+    /// A change was sent without a range, full document syncs aren't supported.
+    MissingRange,
+    /// A change range lies outside the current document content.
+    InvalidRange(Range),
+}
+
+/// Failures while producing diagnostics for a document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnalysisError {
+    /// No document with this uri is open.
+    DocumentNotFound(Url),
+    /// The document changed while it was being validated, the results are
+    /// for `version` and must not be published.
+    Stale { version: i32 },
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::DocumentNotFound(uri) => write!(f, "Unable to find document '{}'", uri),
+            CacheError::MissingRange => write!(f, "Unable to get range to update"),
+            CacheError::InvalidRange(range) => write!(
+                f,
+                "Range {}:{}-{}:{} is outside of the document",
+                range.start.line, range.start.character, range.end.line, range.end.character
+            ),
+        }
+    }
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::DocumentNotFound(uri) => write!(f, "Unable to get document '{}' for validation", uri),
+            AnalysisError::Stale {
+                version,
+            } => write!(f, "Results for version {} are stale", version),
+        }
+    }
+}
+
+impl Error for CacheError {}
+
+impl Error for AnalysisError {}
@@ -0,0 +1,102 @@
+use lspower::lsp::{FoldingRange, FoldingRangeKind};
+
+use super::{
+    helper::split_lines,
+    lexer::{lex_str, TokenType},
+};
+
+/// Returns an imports-like fold over the class-level `.implements` and
+/// `.annotation` lines before the first member.
+pub fn folding_ranges(content: &str) -> Vec<FoldingRange> {
+    let mut header: Option<(u32, u32)> = None;
+
+    for line in split_lines(lex_str(content)) {
+        let number = line[0].range.start.line;
+
+        if matches!(line[0].token_type, TokenType::Field | TokenType::Method) {
+            break;
+        }
+
+        if line[0].token_type == TokenType::Comment {
+            continue;
+        }
+
+        let starts_header = matches!(
+            (&line[0].token_type, line[0].content.as_str()),
+            (TokenType::Directive, ".implements") | (TokenType::Annotation, ".annotation")
+        );
+
+        // Everything after the first `.implements` or `.annotation` belongs
+        // to the header, including the contents of the annotations
+        match &mut header {
+            Some((_, end)) => *end = number,
+            None if starts_header => header = Some((number, number)),
+            None => {},
+        }
+    }
+
+    header
+        .filter(|(start, end)| start < end)
+        .map(|(start, end)| fold(start, end, FoldingRangeKind::Imports))
+        .into_iter()
+        .collect()
+}
+
+fn fold(start_line: u32, end_line: u32, kind: FoldingRangeKind) -> FoldingRange {
+    FoldingRange {
+        start_line,
+        start_character: None,
+        end_line,
+        end_character: None,
+        kind: Some(kind),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::FoldingRangeKind;
+
+    use super::folding_ranges;
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+.source \"Test.java\"
+
+# interfaces
+.implements Ljava/lang/Runnable;
+.implements Ljava/io/Serializable;
+
+# annotations
+.annotation system Ldalvik/annotation/MemberClasses;
+    value = {
+        Lme/l3af/Test$Inner;
+    }
+.end annotation
+
+# instance fields
+.field private count:I
+
+.method public run()V
+    .locals 0
+
+    return-void
+.end method
+";
+
+    #[test]
+    fn header() {
+        let ranges = folding_ranges(CONTENT)
+            .into_iter()
+            .map(|range| (range.start_line, range.end_line, range.kind.unwrap()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(ranges, vec![(5, 13, FoldingRangeKind::Imports)]);
+    }
+
+    #[test]
+    fn no_header() {
+        let ranges = folding_ranges(".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n.implements Lfoo;\n");
+
+        assert!(ranges.is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+use lspower::lsp::TextEdit;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    helper::{lsp_range_to_range, split_lines},
+    lexer::{lex_str, Token, TokenType},
+    settings::{LineEnding, Settings},
+};
+
+/// Formats text sent with the request rather than an open document.
+pub const FORMAT_STRING: &str = "smali-lsp/formatString";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FormatStringParams {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FormatStringResult {
+    pub text:    String,
+    pub changed: bool,
+}
+
+/// Computes the edits formatting `content` according to `settings`.
+pub fn format_document(content: &str, settings: &Settings) -> Vec<TextEdit> {
+    let mut edits = Vec::new();
+    let tokens = lex_str(content);
+
+    if settings.normalize_operand_spacing {
+        for line in split_lines(tokens.clone()) {
+            edits.append(&mut normalize_operand_spacing(&line));
+        }
+    }
+
+    edits.append(&mut normalize_line_endings(&tokens, settings.line_ending));
+
+    edits
+}
+
+/// Formats `content` on its own, returning the formatted text.
+pub fn format_string(content: &str, settings: &Settings) -> FormatStringResult {
+    let mut edits = format_document(content, settings)
+        .into_iter()
+        .map(|edit| (lsp_range_to_range(edit.range, content), edit.new_text))
+        .collect::<Vec<_>>();
+    edits.sort_by_key(|(range, _)| range.start);
+
+    // Last edit first, so the offsets of earlier ones stay valid
+    let mut text = content.to_string();
+    for (range, new_text) in edits.into_iter().rev() {
+        text.replace_range(range, &new_text);
+    }
+
+    FormatStringResult {
+        changed: text != content,
+        text,
+    }
+}
+
+/// Replaces every line ending that differs from `line_ending`, or from the
+/// first line ending in the file when it is `auto`.
+fn normalize_line_endings(tokens: &[Token], line_ending: LineEnding) -> Vec<TextEdit> {
+    let mut newlines = tokens.iter().filter(|token| token.token_type == TokenType::NewLine);
+
+    let expected = match line_ending.as_str() {
+        Some(expected) => expected,
+        None => match newlines.next() {
+            Some(first) => first.content.as_str(),
+            None => return Vec::new(),
+        },
+    };
+
+    newlines
+        .filter(|newline| newline.content != expected)
+        .map(|newline| TextEdit {
+            range:    newline.range,
+            new_text: expected.to_string(),
+        })
+        .collect()
+}
+
+/// Collapses the whitespace between the tokens of an instruction to a single
+/// space, leaving the indent, trailing whitespace and comments alone.
+///
+/// Lines that don't lex cleanly are skipped, an escaped quote can end a
+/// `String` token early and leave part of the string looking like spaces.
+fn normalize_operand_spacing(line: &[Token]) -> Vec<TextEdit> {
+    let mut edits = Vec::new();
+
+    let start = match line.iter().position(|token| token.token_type != TokenType::Space) {
+        Some(start) if line[start].token_type.is_opcode() => start,
+        _ => return edits,
+    };
+
+    let is_clean = line.iter().all(|token| {
+        token.token_type != TokenType::Error
+            && !(token.token_type == TokenType::String && token.content.ends_with("\\\""))
+    });
+    if !is_clean {
+        return edits;
+    }
+
+    for (idx, token) in line.iter().enumerate().skip(start + 1) {
+        let next = match line.get(idx + 1) {
+            Some(next) if next.token_type != TokenType::Comment => next,
+            _ => break,
+        };
+
+        if token.token_type == TokenType::Space && token.content != " " && next.token_type != TokenType::Space {
+            edits.push(TextEdit {
+                range:    token.range,
+                new_text: " ".to_string(),
+            });
+        }
+    }
+
+    edits
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range};
+
+    use super::{format_document, format_string};
+    use crate::server::settings::{LineEnding, Settings};
+
+    /// Applies edits to single line content, last edit first so earlier
+    /// ranges stay valid.
+    fn apply(content: &str, settings: &Settings) -> String {
+        let mut output = content.to_string();
+        let mut edits = format_document(content, settings);
+        edits.sort_by_key(|edit| edit.range.start);
+
+        for edit in edits.into_iter().rev() {
+            let range = edit.range.start.character as usize..edit.range.end.character as usize;
+            output.replace_range(range, &edit.new_text);
+        }
+
+        output
+    }
+
+    #[test]
+    fn collapse_operand_spaces() {
+        assert_eq!(
+            apply("    invoke-virtual   {v0},    Lfoo;->bar()V", &Settings::default()),
+            "    invoke-virtual {v0}, Lfoo;->bar()V"
+        );
+        assert_eq!(apply("\tconst/4\tv0,  0x1", &Settings::default()), "\tconst/4 v0, 0x1");
+    }
+
+    #[test]
+    fn strings_and_comments_untouched() {
+        let content = "    const-string v0,   \"a    b\"    # spaced    comment";
+        assert_eq!(apply(content, &Settings::default()), "    const-string v0, \"a    b\"    # spaced    comment");
+
+        let content = "    const-string v0,  \"a\\\"   b\"";
+        assert_eq!(apply(content, &Settings::default()), content);
+    }
+
+    #[test]
+    fn directives_untouched() {
+        let content = "    .locals   2";
+        assert_eq!(apply(content, &Settings::default()), content);
+    }
+
+    #[test]
+    fn disabled() {
+        let settings = Settings {
+            normalize_operand_spacing: false,
+            ..Settings::default()
+        };
+
+        assert!(format_document("    invoke-virtual   {v0},    Lfoo;->bar()V", &settings).is_empty());
+    }
+
+    #[test]
+    fn line_endings() {
+        let content = "    nop\r\n    nop\n";
+        let edits = |line_ending| {
+            format_document(content, &Settings {
+                line_ending,
+                ..Settings::default()
+            })
+            .into_iter()
+            .map(|edit| (edit.range, edit.new_text))
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(edits(LineEnding::Lf), vec![(
+            Range::new(Position::new(0, 7), Position::new(1, 0)),
+            "\n".to_string()
+        )]);
+        assert_eq!(edits(LineEnding::Crlf), vec![(
+            Range::new(Position::new(1, 7), Position::new(2, 0)),
+            "\r\n".to_string()
+        )]);
+        assert_eq!(edits(LineEnding::Auto), edits(LineEnding::Crlf));
+    }
+
+    #[test]
+    fn format_snippet() {
+        let result = format_string("    const/4   v0,  0x1\r\n    return   v0\n", &Settings {
+            line_ending: LineEnding::Lf,
+            ..Settings::default()
+        });
+        assert_eq!(result.text, "    const/4 v0, 0x1\n    return v0\n");
+        assert!(result.changed);
+
+        assert!(!format_string(&result.text, &Settings::default()).changed);
+    }
+}
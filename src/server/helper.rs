@@ -4,12 +4,17 @@ use lspower::lsp::{Diagnostic, DiagnosticSeverity, Position, Range as LspRange};
 
 use super::lexer::{Token, TokenType};
 
+/// Trims leading and trailing spaces from a line along with any trailing
+/// comment, so validators only see the structural tokens.
 pub fn trim_space_tokens(tokens: Vec<Token>) -> Vec<Token> {
     let mut output = Vec::new();
     let mut space_buffer = Vec::new();
 
     for token in tokens {
-        if token.token_type == TokenType::Space {
+        if token.token_type == TokenType::Comment {
+            // Comments run until the end of the line
+            break;
+        } else if token.token_type == TokenType::Space {
             // Ignore spaces at the start
             if !output.is_empty() {
                 space_buffer.push(token);
@@ -25,18 +30,72 @@ pub fn trim_space_tokens(tokens: Vec<Token>) -> Vec<Token> {
     output
 }
 
+/// Splits `tokens` into lines with surrounding spaces trimmed, dropping
+/// empty lines.
+pub fn split_lines(tokens: Vec<Token>) -> Vec<Vec<Token>> {
+    tokens
+        .split(|token| token.token_type == TokenType::NewLine)
+        .map(|line| trim_space_tokens(line.to_vec()))
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Returns the lines of the method block containing `line`, from the
+/// `.method` line up to and including the `.end method` line.
+pub fn method_lines_at(lines: &[Vec<Token>], line: u32) -> Option<&[Vec<Token>]> {
+    let start = lines.iter().rposition(|tokens| {
+        tokens[0].range.start.line <= line && tokens[0].token_type == TokenType::Method && tokens[0].content == ".method"
+    })?;
+
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|tokens| tokens[0].token_type == TokenType::Method && tokens[0].content == ".end method")?;
+
+    if lines[end][0].range.start.line < line {
+        return None;
+    }
+
+    Some(&lines[start..=end])
+}
+
+/// Parses an unsigned number literal written in decimal or as `0x`
+/// hexadecimal, such as the count of a `.locals` directive.
+pub fn parse_count(number: &str) -> Option<u32> {
+    match number.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => number.parse().ok(),
+    }
+}
+
+/// Returns the token under `position`, ignoring spaces and new lines.
+pub fn token_at(tokens: &[Token], position: Position) -> Option<&Token> {
+    tokens.iter().find(|token| {
+        token.token_type != TokenType::Space
+            && token.token_type != TokenType::NewLine
+            && token.range.start <= position
+            && position <= token.range.end
+    })
+}
+
+/// The range spanning from the first to the last of `tokens`.
+pub fn tokens_range(tokens: &[Token]) -> LspRange {
+    match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last)) => LspRange {
+            start: first.range.start,
+            end:   last.range.end,
+        },
+        _ => LspRange::default(),
+    }
+}
+
 pub fn tokens_to_diagnostic(
     tokens: &[Token],
     message: impl ToString,
     severity: Option<DiagnosticSeverity>,
 ) -> Diagnostic {
-    let range = LspRange {
-        start: tokens.first().unwrap().range.start,
-        end:   tokens.last().unwrap().range.end,
-    };
-
     Diagnostic {
-        range,
+        range: tokens_range(tokens),
         severity,
         message: message.to_string(),
         code: None,
@@ -67,11 +126,17 @@ pub fn lsp_pos_to_pos(input: Position, content: &str) -> usize {
         },
     };
 
+    // Positions past the end of the line or inside a character are clamped
+    let mut character = (input.character as usize).min(line.len());
+    while !line.is_char_boundary(character) {
+        character -= 1;
+    }
+
     let up_to = format!(
         "{}{}{}",
         lines.split_at(input.line as usize).0.join("\n"),
         if input.line > 0 { "\n" } else { "" },
-        line.split_at(input.character as usize).0
+        line.split_at(character).0
     );
 
     up_to.len()
@@ -94,7 +159,16 @@ mod test {
 
     use crate::server::{helper::trim_space_tokens, lexer::{TokenType, lex_str}};
 
-    use super::{lsp_pos_to_pos, lsp_range_to_range, pos_to_lsp_pos, range_to_lsp_range};
+    use super::{
+        lsp_pos_to_pos,
+        lsp_range_to_range,
+        method_lines_at,
+        parse_count,
+        pos_to_lsp_pos,
+        range_to_lsp_range,
+        split_lines,
+        token_at,
+    };
 
     #[test]
     fn pos_to_lsp_pos_single_line() {
@@ -175,6 +249,15 @@ mod test {
         assert_eq!(expected, pos_to_lsp_pos(pos, input));
     }
 
+    #[test]
+    fn lsp_pos_to_pos_out_of_bounds() {
+        assert_eq!(lsp_pos_to_pos(Position::new(0, 40), "test\nstring"), 4);
+        assert_eq!(lsp_pos_to_pos(Position::new(1, 40), "test\nstring"), 11);
+        assert_eq!(lsp_pos_to_pos(Position::new(9, 0), "test\nstring"), 11);
+        // Inside the two byte 'é'
+        assert_eq!(lsp_pos_to_pos(Position::new(0, 2), "aé"), 1);
+    }
+
     #[test]
     fn range_to_lsp_range_single_line() {
         let input = "test";
@@ -405,4 +488,47 @@ mod test {
         assert_eq!(token.token_type, TokenType::Number);
         assert_eq!(token.content, "1");
     }
+
+    #[test]
+    fn method_lines() {
+        let lines = split_lines(lex_str(
+            ".class public LTest;\n\n.method public a()V\n    return-void\n.end method\n\n.method public b()V\n    \
+             return-void\n.end method\n",
+        ));
+
+        let method = method_lines_at(&lines, 7).unwrap();
+        assert_eq!(method.len(), 3);
+        assert_eq!(method[0][4].content, "b(");
+
+        assert_eq!(method_lines_at(&lines, 3).unwrap()[0][4].content, "a(");
+        assert!(method_lines_at(&lines, 5).is_none());
+        assert!(method_lines_at(&lines, 0).is_none());
+    }
+
+    #[test]
+    fn trim_comments() {
+        let tokens = trim_space_tokens(lex_str("    .locals 2    # two temps"));
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens.last().unwrap().content, "2");
+
+        assert!(trim_space_tokens(lex_str("    # only a comment")).is_empty());
+    }
+
+    #[test]
+    fn token_at_position() {
+        let tokens = lex_str("    move v0, v1\n");
+
+        assert_eq!(token_at(&tokens, Position::new(0, 9)).unwrap().content, "v0");
+        assert_eq!(token_at(&tokens, Position::new(0, 15)).unwrap().content, "v1");
+        assert!(token_at(&tokens, Position::new(0, 2)).is_none());
+        assert!(token_at(&tokens, Position::new(1, 0)).is_none());
+    }
+
+    #[test]
+    fn counts() {
+        assert_eq!(parse_count("16"), Some(16));
+        assert_eq!(parse_count("0x10"), Some(16));
+        assert_eq!(parse_count("-0x1"), None);
+        assert_eq!(parse_count("0x"), None);
+    }
 }
@@ -0,0 +1,334 @@
+use lspower::lsp::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Range};
+
+use super::{
+    descriptor::{is_wide, java_name, parameter_types},
+    helper::{method_lines_at, parse_count, split_lines, token_at},
+    index::ClassIndex,
+    lexer::{lex_str, Token, TokenType},
+    registers::{register_accesses, Access, RegisterTracker, RegisterType},
+};
+
+/// Describes the lifetime of the register under `position` within the
+/// enclosing method: where it is first written, where it is last read and the
+/// type it holds at that point.
+///
+/// Registers holding a parameter on entry also say which one, `this` being
+/// the enclosing class in instance methods.
+///
+/// Returns `None` when the register isn't used by any other instruction and
+/// doesn't hold a parameter.
+pub fn register_hover(content: &str, position: Position) -> Option<Hover> {
+    let tokens = lex_str(content);
+    let register = token_at(&tokens, position).filter(|token| token.token_type == TokenType::Register)?;
+
+    let lines = split_lines(tokens.clone());
+    let method = method_lines_at(&lines, position.line)?;
+    let class = lines
+        .iter()
+        .find(|line| line[0].content == ".class")
+        .and_then(|line| line.iter().find(|token| token.token_type == TokenType::Class));
+    let parameter = parameter(method, class.map(|class| class.content.as_str()), &register.content);
+
+    let mut tracker = RegisterTracker::default();
+    let mut first_write = None;
+    let mut last_read = None;
+    let mut uses = 0;
+
+    for line in method {
+        let accesses = register_accesses(line)
+            .into_iter()
+            .filter(|(token, _)| token.content == register.content)
+            .map(|(_, access)| access)
+            .collect::<Vec<_>>();
+
+        // Line numbers are shown 1-based, as editors display them
+        let line_number = line[0].range.start.line + 1;
+        if !accesses.is_empty() {
+            uses += 1;
+        }
+
+        for access in accesses {
+            if access != Access::Read && first_write.is_none() {
+                first_write = Some(line_number);
+            }
+            if access != Access::Write {
+                last_read = Some(line_number);
+            }
+        }
+
+        if line[0].range.start.line <= position.line {
+            tracker.update(line);
+        }
+    }
+
+    if uses <= 1 && parameter.is_none() {
+        return None;
+    }
+
+    let first_write = match first_write {
+        Some(line) => format!("line {}", line),
+        None if register.content.starts_with('p') || parameter.is_some() => "parameter".to_string(),
+        None => "never".to_string(),
+    };
+    let last_read = last_read.map_or_else(|| "never".to_string(), |line| format!("line {}", line));
+    let register_type = match tracker.get(&register.content) {
+        Some(RegisterType::Primitive(desc)) | Some(RegisterType::Reference(desc)) => format!("`{}`", desc),
+        Some(RegisterType::Uninitialized(desc)) => format!("`{}` (uninitialized)", desc),
+        Some(RegisterType::Null) => "`null` or zero".to_string(),
+        None => match &parameter {
+            Some((_, descriptor)) => format!("`{}`", descriptor),
+            None => "unknown".to_string(),
+        },
+    };
+    let parameter = parameter.map_or_else(String::new, |(name, _)| format!("\n- Parameter: {}", name));
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind:  MarkupKind::Markdown,
+            value: format!(
+                "**{}**\n{}\n- First written: {}\n- Last read: {}\n- Type: {}",
+                register.content, parameter, first_write, last_read, register_type
+            ),
+        }),
+        range:    Some(register.range),
+    })
+}
+
+/// Describes the `.enum` value of an annotation under `position` by its
+/// Java name, e.g. `me.l3af.Kind.FIRST`.
+pub fn enum_hover(content: &str, position: Position) -> Option<Hover> {
+    let lines = split_lines(lex_str(content));
+    let line = lines.iter().find(|line| line[0].range.start.line == position.line)?;
+
+    let start = line.iter().position(|token| token.token_type == TokenType::Directive && token.content == ".enum")?;
+    let reference = line[start + 1..]
+        .iter()
+        .skip_while(|token| token.token_type == TokenType::Space)
+        .take_while(|token| token.token_type != TokenType::Space && token.token_type != TokenType::Comment)
+        .collect::<Vec<_>>();
+
+    let (owner, field) = match reference.as_slice() {
+        [owner, field, ..] if owner.token_type == TokenType::Class && field.token_type == TokenType::FieldAccess => {
+            (owner, field)
+        },
+        _ => return None,
+    };
+    let range = Range::new(owner.range.start, reference[reference.len() - 1].range.end);
+    if position < range.start || range.end < position {
+        return None;
+    }
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind:  MarkupKind::Markdown,
+            value: format!(
+                "**{}.{}**\n- Enum: `{}`",
+                java_name(&owner.content),
+                field.content.trim_start_matches("->").trim_end_matches(':'),
+                owner.content
+            ),
+        }),
+        range:    Some(range),
+    })
+}
+
+/// Describes the indexed class referenced under `position`, e.g. an
+/// `.implements` operand, saying whether it is an interface.
+pub fn class_hover(content: &str, position: Position, index: &ClassIndex) -> Option<Hover> {
+    let tokens = lex_str(content);
+    let token = token_at(&tokens, position).filter(|token| token.token_type == TokenType::Class)?;
+    let class = index.get(token.content.trim_start_matches('['))?;
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind:  MarkupKind::Markdown,
+            value: format!(
+                "**{}**\n- {}: `{}`",
+                java_name(&class.name),
+                if class.is_interface { "Interface" } else { "Class" },
+                class.name
+            ),
+        }),
+        range:    Some(token.range),
+    })
+}
+
+/// Works out which parameter of `method` the register named `register`
+/// holds on entry, returning its name and descriptor.
+///
+/// `vN` registers only hold parameters in methods declaring `.registers`,
+/// where the parameters take up the last registers.
+fn parameter(method: &[Vec<Token>], class: Option<&str>, register: &str) -> Option<(String, String)> {
+    let declaration = &method[0];
+    let is_static = declaration
+        .iter()
+        .any(|token| token.token_type == TokenType::Modifier && token.content == "static");
+
+    let mut parameters = parameter_types(declaration)?;
+    if !is_static {
+        parameters.insert(0, class?.to_string());
+    }
+    let width = |descriptor: &String| if is_wide(descriptor) { 2 } else { 1 };
+    let slots = parameters.iter().map(width).sum::<u32>();
+
+    let index = register[1..].parse::<u32>().ok()?;
+    let slot = if register.starts_with('p') {
+        index
+    } else {
+        let count = method
+            .iter()
+            .find(|line| line[0].content == ".registers")?
+            .iter()
+            .find(|token| token.token_type == TokenType::Number)?;
+        let count = parse_count(&count.content)?;
+
+        index.checked_sub(count.checked_sub(slots)?)?
+    };
+
+    // The second half of a wide parameter isn't a parameter of its own
+    let mut start = 0;
+    for (idx, descriptor) in parameters.iter().enumerate() {
+        if start == slot {
+            let name = match idx {
+                0 if !is_static => "`this`".to_string(),
+                _ if is_static => format!("#{}", idx + 1),
+                _ => format!("#{}", idx),
+            };
+
+            return Some((name, descriptor.clone()));
+        }
+
+        start += width(descriptor);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{HoverContents, Position, Url};
+
+    use super::{class_hover, enum_hover, register_hover};
+    use crate::server::index::ClassIndex;
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.method public static test()V
+    .locals 1
+
+    const/4 v0, 0x1
+
+    invoke-static {v0}, Lfoo;->bar(I)V
+
+    invoke-static {v0}, Lfoo;->baz(I)V
+
+    return-void
+.end method
+";
+
+    #[test]
+    fn register_lifetime() {
+        let hover = register_hover(CONTENT, Position::new(8, 19)).unwrap();
+
+        match hover.contents {
+            HoverContents::Markup(markup) => assert_eq!(
+                markup.value,
+                "**v0**\n\n- First written: line 7\n- Last read: line 11\n- Type: `I`"
+            ),
+            contents => panic!("Unexpected hover contents: {:?}", contents),
+        }
+    }
+
+    #[test]
+    fn unused_register() {
+        let content = CONTENT.replace("invoke-static {v0}", "invoke-static {}");
+
+        assert!(register_hover(&content, Position::new(6, 13)).is_none());
+        assert!(register_hover(CONTENT, Position::new(6, 6)).is_none());
+    }
+
+    const PARAMETERS: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.method public run(J)V
+    .registers 4
+    invoke-virtual {p0}, Lme/l3af/Test;->hashCode()I
+    invoke-virtual {v1}, Lme/l3af/Test;->toString()Ljava/lang/String;
+    return-void
+.end method
+
+.method public static of(I)V
+    .locals 0
+    invoke-static {p0}, Lfoo;->bar(I)V
+    return-void
+.end method
+";
+
+    fn hover_value(position: Position) -> String {
+        match register_hover(PARAMETERS, position).unwrap().contents {
+            HoverContents::Markup(markup) => markup.value,
+            contents => panic!("Unexpected hover contents: {:?}", contents),
+        }
+    }
+
+    #[test]
+    fn instance_this() {
+        assert_eq!(
+            hover_value(Position::new(5, 21)),
+            "**p0**\n\n- Parameter: `this`\n- First written: parameter\n- Last read: line 6\n- Type: `Lme/l3af/Test;`"
+        );
+        assert_eq!(
+            hover_value(Position::new(6, 21)),
+            "**v1**\n\n- Parameter: `this`\n- First written: parameter\n- Last read: line 7\n- Type: `Lme/l3af/Test;`"
+        );
+    }
+
+    #[test]
+    fn static_first_parameter() {
+        assert_eq!(
+            hover_value(Position::new(12, 20)),
+            "**p0**\n\n- Parameter: #1\n- First written: parameter\n- Last read: line 13\n- Type: `I`"
+        );
+    }
+
+    #[test]
+    fn enum_value() {
+        let content = ".annotation runtime Lme/l3af/Marker;
+    value = .enum Lme/l3af/Kind;->FIRST:Lme/l3af/Kind;
+.end annotation
+";
+        let hover = enum_hover(content, Position::new(1, 35)).unwrap();
+
+        match hover.contents {
+            HoverContents::Markup(markup) => {
+                assert_eq!(markup.value, "**me.l3af.Kind.FIRST**\n- Enum: `Lme/l3af/Kind;`");
+            },
+            contents => panic!("Unexpected hover contents: {:?}", contents),
+        }
+        assert_eq!(enum_hover(content, Position::new(1, 6)), None);
+    }
+
+    #[test]
+    fn implemented_interface() {
+        let mut index = ClassIndex::default();
+        index.index_content(
+            &Url::parse("file:///Runnable.smali").unwrap(),
+            ".class public interface abstract Lme/l3af/Runnable;\n.super Ljava/lang/Object;\n",
+        );
+        index.index_content(
+            &Url::parse("file:///Base.smali").unwrap(),
+            ".class public Lme/l3af/Base;\n.super Ljava/lang/Object;\n",
+        );
+
+        let content = ".class public Lme/l3af/Test;\n.super Lme/l3af/Base;\n.implements Lme/l3af/Runnable;\n";
+        let value = |position: Position| match class_hover(content, position, &index).unwrap().contents {
+            HoverContents::Markup(markup) => markup.value,
+            contents => panic!("Unexpected hover contents: {:?}", contents),
+        };
+
+        assert_eq!(value(Position::new(2, 16)), "**me.l3af.Runnable**\n- Interface: `Lme/l3af/Runnable;`");
+        assert_eq!(value(Position::new(1, 10)), "**me.l3af.Base**\n- Class: `Lme/l3af/Base;`");
+        assert_eq!(class_hover(content, Position::new(0, 20), &index), None);
+    }
+}
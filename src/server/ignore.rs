@@ -0,0 +1,129 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Whether `path` is excluded by the gitignore-style `patterns`, matched
+/// relative to whichever of `roots` contains it.
+///
+/// Patterns support `*`, `**` and `?`. Like `.gitignore`, a pattern without
+/// a `/` matches a name at any depth, a trailing `/` only matches directories
+/// and a leading `!` re-includes paths excluded by an earlier pattern. Paths
+/// below an excluded directory can't be re-included.
+pub fn is_ignored(path: &Path, roots: &[PathBuf], patterns: &[String]) -> bool {
+    let relative = roots.iter().find_map(|root| path.strip_prefix(root).ok()).unwrap_or(path);
+    let components = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Each directory is checked on its way down, the last component is the
+    // file itself
+    (1..=components.len()).any(|len| excludes(patterns, &components[..len], len < components.len()))
+}
+
+/// Whether `patterns` exclude the path made of `components`, the last
+/// matching pattern decides.
+fn excludes(patterns: &[String], components: &[&str], is_dir: bool) -> bool {
+    let mut ignored = false;
+    for pattern in patterns.iter().map(|pattern| pattern.trim()) {
+        if pattern.is_empty() || pattern.starts_with('#') {
+            continue;
+        }
+
+        match pattern.strip_prefix('!') {
+            Some(pattern) if ignored => ignored = !pattern_matches(pattern, components, is_dir),
+            Some(_) => {},
+            None if !ignored => ignored = pattern_matches(pattern, components, is_dir),
+            None => {},
+        }
+    }
+
+    ignored
+}
+
+fn pattern_matches(pattern: &str, components: &[&str], is_dir: bool) -> bool {
+    let dir_only = pattern.ends_with('/');
+    let pattern = pattern.trim_end_matches('/');
+    let anchored = pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+
+    if dir_only && !is_dir {
+        return false;
+    }
+
+    if anchored {
+        glob_match(pattern.as_bytes(), components.join("/").as_bytes())
+    } else {
+        matches!(components.last(), Some(name) if glob_match(pattern.as_bytes(), name.as_bytes()))
+    }
+}
+
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        // Zero or more whole directories
+        [b'*', b'*', b'/', rest @ ..] => {
+            glob_match(rest, text)
+                || text
+                    .iter()
+                    .enumerate()
+                    .any(|(idx, c)| *c == b'/' && glob_match(rest, &text[idx + 1..]))
+        },
+        [b'*', b'*', rest @ ..] => (0..=text.len()).any(|idx| glob_match(rest, &text[idx..])),
+        [b'*', rest @ ..] => (0..=text.len())
+            .take_while(|idx| *idx == 0 || text[idx - 1] != b'/')
+            .any(|idx| glob_match(rest, &text[idx..])),
+        [b'?', rest @ ..] => matches!(text.split_first(), Some((c, text)) if *c != b'/' && glob_match(rest, text)),
+        [expected, rest @ ..] => {
+            matches!(text.split_first(), Some((c, text)) if c == expected && glob_match(rest, text))
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::is_ignored;
+
+    fn ignored(path: &str, patterns: &[&str]) -> bool {
+        let patterns = patterns.iter().map(|pattern| pattern.to_string()).collect::<Vec<_>>();
+
+        is_ignored(Path::new(path), &[PathBuf::from("/workspace")], &patterns)
+    }
+
+    #[test]
+    fn unanchored_patterns() {
+        assert!(ignored("/workspace/smali_classes2/android/os/Build.smali", &["android/"]));
+        assert!(ignored("/workspace/smali/R$string.smali", &["R$*.smali"]));
+        assert!(!ignored("/workspace/smali/me/l3af/Main.smali", &["android/"]));
+        // Only directories match a trailing `/`
+        assert!(!ignored("/workspace/smali/android", &["android/"]));
+    }
+
+    #[test]
+    fn anchored_patterns() {
+        assert!(ignored("/workspace/smali_classes2/android/os/Build.smali", &["smali_classes*/android"]));
+        assert!(ignored("/workspace/smali_classes2/android/os/Build.smali", &["**/os/*.smali"]));
+        assert!(!ignored("/workspace/smali/smali_classes2/android/Build.smali", &["/smali_classes2/"]));
+        assert!(!ignored("/workspace/smali/me/Main.smali", &["smali/*.smali"]));
+    }
+
+    #[test]
+    fn negated_patterns() {
+        let patterns = &["*.smali", "!Main.smali"];
+
+        assert!(ignored("/workspace/smali/me/l3af/Other.smali", patterns));
+        assert!(!ignored("/workspace/smali/me/l3af/Main.smali", patterns));
+    }
+
+    #[test]
+    fn excluded_directory_stays_excluded() {
+        let patterns = &["android/", "!support/", "!Fragment.smali"];
+
+        assert!(ignored("/workspace/smali/android/os/Build.smali", patterns));
+        assert!(ignored("/workspace/smali/android/support/Fragment.smali", patterns));
+        assert!(!ignored("/workspace/smali/me/l3af/Main.smali", patterns));
+    }
+}
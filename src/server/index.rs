@@ -0,0 +1,298 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use lspower::lsp::{Range, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    descriptor::{parameter_types, return_type},
+    helper::split_lines,
+    lexer::{lex_str, Token, TokenType},
+};
+
+#[derive(Debug, Default)]
+pub struct ClassIndex {
+    classes: HashMap<String, ClassEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassEntry {
+    pub uri:          Url,
+    pub name:         String,
+    pub range:        Range,
+    pub super_class:  Option<String>,
+    /// Caches written before this was recorded read back as classes.
+    #[serde(default)]
+    pub is_interface: bool,
+    pub fields:       Vec<FieldEntry>,
+    pub methods:      Vec<MethodEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldEntry {
+    pub name:       String,
+    pub field_type: String,
+    pub is_static:  bool,
+    pub range:      Range,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MethodEntry {
+    pub name:       String,
+    /// The parameter and return types, e.g. `(ILjava/lang/String;)V`.
+    pub descriptor: String,
+    pub is_static:  bool,
+    /// The range of the name alone, without the opening `(`.
+    pub range:      Range,
+}
+
+impl ClassEntry {
+    pub fn field(&self, name: &str) -> Option<&FieldEntry> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    pub fn method(&self, name: &str, descriptor: &str) -> Option<&MethodEntry> {
+        self.methods
+            .iter()
+            .find(|method| method.name == name && method.descriptor == descriptor)
+    }
+}
+
+impl ClassIndex {
+    pub fn get(&self, class: &str) -> Option<&ClassEntry> {
+        self.classes.get(class)
+    }
+
+    pub fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// Removes every class that was indexed from `uri`.
+    pub fn remove_uri(&mut self, uri: &Url) {
+        self.classes.retain(|_, class| &class.uri != uri);
+    }
+
+    /// Every class that was indexed from `uri`.
+    pub fn classes_from(&self, uri: &Url) -> Vec<ClassEntry> {
+        self.classes.values().filter(|class| &class.uri == uri).cloned().collect()
+    }
+
+    /// Adds a class parsed elsewhere, such as one read back from a cache.
+    pub fn insert(&mut self, class: ClassEntry) {
+        self.classes.insert(class.name.clone(), class);
+    }
+
+    /// Indexes the class declared in `content`, replacing anything
+    /// previously indexed from the same `uri`.
+    pub fn index_content(&mut self, uri: &Url, content: &str) {
+        self.remove_uri(uri);
+
+        if let Some(class) = parse_class(uri, content) {
+            self.classes.insert(class.name.clone(), class);
+        }
+    }
+
+    /// Indexes the `.smali` file at `path`, skipping it if it can't be read.
+    pub fn index_file(&mut self, path: &Path) {
+        if let (Ok(uri), Ok(content)) = (Url::from_file_path(path), fs::read_to_string(path)) {
+            self.index_content(&uri, &content);
+        }
+    }
+
+    /// Recursively indexes every `.smali` file under `path`.
+    pub fn index_directory(&mut self, path: &Path) {
+        for path in smali_files(path) {
+            self.index_file(&path);
+        }
+    }
+}
+
+/// Recursively collects every `.smali` file under `path`, sorted by path.
+pub fn smali_files(path: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_smali_files(path, &mut files);
+    files.sort();
+
+    files
+}
+
+fn collect_smali_files(path: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_smali_files(&path, files);
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("smali") {
+            files.push(path);
+        }
+    }
+}
+
+fn parse_class(uri: &Url, content: &str) -> Option<ClassEntry> {
+    let mut class: Option<ClassEntry> = None;
+    let mut super_class = None;
+    let mut fields = Vec::new();
+    let mut methods = Vec::new();
+
+    for line in split_lines(lex_str(content)) {
+        match (&line[0].token_type, line[0].content.as_ref()) {
+            (TokenType::Directive, ".class") => {
+                if let Some(token) = line.iter().find(|token| token.token_type == TokenType::Class) {
+                    let is_interface = line
+                        .iter()
+                        .any(|token| token.token_type == TokenType::Modifier && token.content == "interface");
+
+                    class = Some(ClassEntry {
+                        uri: uri.clone(),
+                        name: token.content.clone(),
+                        range: token.range,
+                        super_class: None,
+                        is_interface,
+                        fields: Vec::new(),
+                        methods: Vec::new(),
+                    });
+                }
+            },
+            (TokenType::Directive, ".super") => {
+                super_class = line
+                    .iter()
+                    .find(|token| token.token_type == TokenType::Class)
+                    .map(|token| token.content.clone());
+            },
+            (TokenType::Field, ".field") => {
+                if let Some(field) = parse_field(&line) {
+                    fields.push(field);
+                }
+            },
+            (TokenType::Method, ".method") => {
+                if let Some(method) = parse_method(&line) {
+                    methods.push(method);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    class.map(|class| ClassEntry {
+        super_class,
+        fields,
+        methods,
+        ..class
+    })
+}
+
+fn parse_field(line: &[Token]) -> Option<FieldEntry> {
+    let is_static = line
+        .iter()
+        .any(|token| token.token_type == TokenType::Modifier && token.content == "static");
+
+    let name_idx = line.iter().position(|token| token.token_type == TokenType::FieldName)?;
+    let name = &line[name_idx];
+
+    let field_type = line[name_idx + 1..]
+        .iter()
+        .take_while(|token| token.token_type != TokenType::Space)
+        .map(|token| token.content.as_str())
+        .collect::<String>();
+
+    Some(FieldEntry {
+        name: name.content.trim_end_matches(':').to_string(),
+        field_type,
+        is_static,
+        range: name.range,
+    })
+}
+
+fn parse_method(line: &[Token]) -> Option<MethodEntry> {
+    let name = line.iter().find(|token| token.token_type == TokenType::MethodName)?;
+    let is_static = line
+        .iter()
+        .any(|token| token.token_type == TokenType::Modifier && token.content == "static");
+
+    let mut range = name.range;
+    range.end.character -= 1;
+
+    Some(MethodEntry {
+        name: name.content.trim_end_matches('(').to_string(),
+        descriptor: format!("({}){}", parameter_types(line)?.concat(), return_type(line)?),
+        is_static,
+        range,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range, Url};
+
+    use super::ClassIndex;
+
+    const FIXTURE: &str = ".class public Lme/l3af/Fixture;
+.super Ljava/lang/Object;
+
+.field private static final TAG:Ljava/lang/String; = \"Fixture\"
+
+.field private count:I
+
+.method public static getTag(I)Ljava/lang/String;
+    .locals 0
+.end method
+";
+
+    #[test]
+    fn index_fields() {
+        let uri = Url::parse("file:///Fixture.smali").unwrap();
+        let mut index = ClassIndex::default();
+        index.index_content(&uri, FIXTURE);
+
+        let class = index.get("Lme/l3af/Fixture;").unwrap();
+        assert_eq!(class.uri, uri);
+        assert_eq!(class.super_class.as_deref(), Some("Ljava/lang/Object;"));
+        assert!(!class.is_interface);
+
+        let tag = class.field("TAG").unwrap();
+        assert_eq!(tag.field_type, "Ljava/lang/String;");
+        assert!(tag.is_static);
+
+        let count = class.field("count").unwrap();
+        assert_eq!(count.field_type, "I");
+        assert!(!count.is_static);
+    }
+
+    #[test]
+    fn index_methods() {
+        let uri = Url::parse("file:///Fixture.smali").unwrap();
+        let mut index = ClassIndex::default();
+        index.index_content(&uri, FIXTURE);
+
+        let class = index.get("Lme/l3af/Fixture;").unwrap();
+        let method = class.method("getTag", "(I)Ljava/lang/String;").unwrap();
+        assert!(method.is_static);
+        assert_eq!(method.range, Range::new(Position::new(7, 22), Position::new(7, 28)));
+
+        assert!(class.method("getTag", "()Ljava/lang/String;").is_none());
+    }
+
+    #[test]
+    fn reindex_replaces_uri() {
+        let uri = Url::parse("file:///Fixture.smali").unwrap();
+        let mut index = ClassIndex::default();
+        index.index_content(&uri, FIXTURE);
+        index.index_content(&uri, &FIXTURE.replace("Fixture;", "Renamed;"));
+
+        assert!(index.get("Lme/l3af/Fixture;").is_none());
+        assert!(index.get("Lme/l3af/Renamed;").is_some());
+        assert_eq!(index.len(), 1);
+    }
+}
@@ -0,0 +1,96 @@
+use lspower::lsp::{Position, Range, TextDocumentIdentifier};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    descriptor::java_name,
+    lexer::{lex_str, TokenType},
+    settings::Settings,
+};
+
+pub const METHOD: &str = "textDocument/inlayHint";
+
+/// `InlayHintKind::Type` from the LSP specification.
+const KIND_TYPE: u8 = 1;
+
+// Inlay hints aren't part of the protocol version lspower implements, so the
+// request and response types are declared here.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range:         Range,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHint {
+    pub position:     Position,
+    pub label:        String,
+    pub kind:         u8,
+    pub padding_left: bool,
+}
+
+/// Produces a hint with the Java name after every class descriptor in
+/// `range` when `decodeClassNames` is enabled.
+pub fn inlay_hints(content: &str, range: Range, settings: &Settings) -> Vec<InlayHint> {
+    if !settings.decode_class_names {
+        return Vec::new();
+    }
+
+    lex_str(content)
+        .into_iter()
+        .filter(|token| token.token_type == TokenType::Class)
+        .filter(|token| token.range.start >= range.start && token.range.end <= range.end)
+        .map(|token| InlayHint {
+            position:     token.range.end,
+            label:        java_name(&token.content),
+            kind:         KIND_TYPE,
+            padding_left: true,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range};
+
+    use super::{inlay_hints, InlayHint};
+    use crate::server::settings::Settings;
+
+    const CONTENT: &str = ".class public La/b/C;\n.super Ljava/lang/Object;\n";
+
+    fn full_range() -> Range {
+        Range::new(Position::new(0, 0), Position::new(2, 0))
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(inlay_hints(CONTENT, full_range(), &Settings::default()).is_empty());
+    }
+
+    #[test]
+    fn decode_class_names() {
+        let settings = Settings {
+            decode_class_names: true,
+            ..Settings::default()
+        };
+
+        assert_eq!(inlay_hints(CONTENT, full_range(), &settings), vec![
+            InlayHint {
+                position:     Position::new(0, 21),
+                label:        "a.b.C".to_string(),
+                kind:         1,
+                padding_left: true,
+            },
+            InlayHint {
+                position:     Position::new(1, 25),
+                label:        "java.lang.Object".to_string(),
+                kind:         1,
+                padding_left: true,
+            },
+        ]);
+
+        let first_line = Range::new(Position::new(0, 0), Position::new(1, 0));
+        assert_eq!(inlay_hints(CONTENT, first_line, &settings).len(), 1);
+    }
+}
@@ -5,16 +5,16 @@ use super::helper::range_to_lsp_range;
 
 #[derive(Logos, Debug, Clone, PartialEq)]
 pub enum TokenType {
-    #[token("\n")]
+    #[regex(r"\r?\n")]
     NewLine,
 
-    #[regex(r"#.*")]
+    #[regex(r"#[^\r\n]*")]
     Comment,
 
     #[regex(r"public|private|protected")]
     Visibility,
 
-    #[regex(r"static|constructor|final|synthetic")]
+    #[regex(r"static|constructor|final|synthetic|abstract|interface|annotation|enum")]
     Modifier,
 
     #[regex(r"( |\t)+")]
@@ -32,10 +32,16 @@ pub enum TokenType {
     #[regex(r"\.(field|end field)")]
     Field,
 
-    #[regex(r":(goto|cond)_\d+")]
+    #[regex(r"\.(param|end param)")]
+    Param,
+
+    #[regex(r"\.(annotation|end annotation|subannotation|end subannotation)")]
+    Annotation,
+
+    #[regex(r":[a-zA-Z0-9_]+")]
     Label,
 
-    #[regex(r"\.(class|source|super|implements|locals|local|registers|line|prologue|goto)")]
+    #[regex(r"\.(class|source|super|implements|locals|local|registers|line|prologue|enum)")]
     Directive,
 
     #[regex(r"invoke-(direct|static|virtual|interface)(/range)?")]
@@ -47,40 +53,81 @@ pub enum TokenType {
     #[token("new-instance")]
     NewInstance,
 
+    #[token("new-array")]
+    NewArray,
+
+    #[token("instance-of")]
+    InstanceOf,
+
     #[regex(r"const-string(/jumbo|)")]
     ConstString,
 
-    #[regex(r"const/(4|16)")]
+    #[regex(r"const/(4|16|high16)")]
     ConstInt,
 
+    #[regex(r"const-wide(/16|/32|/high16|)")]
+    ConstWide,
+
     #[regex(r"const(-(class|class)|)")]
     Const,
 
-    #[regex(r"if-(lt|le|gt|ge|eq|eq|ne|ne)(z|)")]
+    #[regex(r"if-(eq|ne|lt|ge|gt|le)(z|)")]
     If,
 
-    #[regex(r"iget(-(object|string|wide)|)")]
+    #[regex(r"iget(-(object|string|wide|boolean|byte|char|short)|)")]
     IGet,
 
-    #[regex(r"sget(-(object|string|wide)|)")]
+    #[regex(r"sget(-(object|string|wide|boolean|byte|char|short)|)")]
     SGet,
 
-    #[regex(r"iput(-(object|string|wide)|)")]
+    #[regex(r"iput(-(object|string|wide|boolean|byte|char|short)|)")]
     IPut,
 
-    #[regex(r"sput(-(object|string|wide)|)")]
+    #[regex(r"sput(-(object|string|wide|boolean|byte|char|short)|)")]
     SPut,
 
-    #[regex(r"move(-(result(-object|)|)|)")]
+    #[regex(r"aget(-(object|wide|boolean|byte|char|short)|)")]
+    AGet,
+
+    #[regex(r"aput(-(object|wide|boolean|byte|char|short)|)")]
+    APut,
+
+    #[regex(r"filled-new-array(/range|)")]
+    FilledNewArray,
+
+    #[regex(r"(add|sub|mul|div|rem|and|or|xor|shl|shr|ushr)-(int|long)(/2addr|)")]
+    #[regex(r"(add|sub|mul|div|rem)-(float|double)(/2addr|)")]
+    #[regex(r"(add|mul|div|rem|and|or|xor)-int/lit16|rsub-int")]
+    #[regex(r"(add|rsub|mul|div|rem|and|or|xor|shl|shr|ushr)-int/lit8")]
+    #[regex(r"(neg|not)-(int|long)|neg-(float|double)")]
+    Arithmetic,
+
+    #[regex(r"move(-(result(-object|-wide|)|object|wide|exception)|)(/from16|/16|)")]
     Move,
 
     #[regex(r"return(-(void|object|wide)|)")]
     Return,
 
-    #[regex("\"[^\"]*\"")]
+    #[token("throw")]
+    Throw,
+
+    #[regex(r"goto(/16|/32|)")]
+    Goto,
+
+    #[regex(r"(packed|sparse)-switch")]
+    Switch,
+
+    /// The payload of a switch instruction, as opposed to the instruction.
+    #[regex(r"\.(packed-switch|end packed-switch|sparse-switch|end sparse-switch)")]
+    SwitchPayload,
+
+    #[regex(r"\.catch(all|)")]
+    Catch,
+
+    #[regex(r#""([^"\\\n]|\\.)*""#)]
     String,
 
-    #[regex(r"(-|)(0x|)\d+")]
+    #[regex(r"(-|)(0x[0-9a-fA-F]+|\d+)")]
     Number,
 
     #[regex(r"\{\{[a-z/a-zA-Z0-9_]*\}\}")]
@@ -101,7 +148,10 @@ pub enum TokenType {
     #[regex(r"[a-zA-Z0-9\$<>]+\(")]
     MethodName,
 
-    #[regex(r"[a-zA-Z0-9\$]+:")]
+    #[regex(r"->[a-zA-Z0-9\$_]+:")]
+    FieldAccess,
+
+    #[regex(r"[a-zA-Z0-9\$_]+:")]
     FieldName,
 
     #[token("[")]
@@ -124,6 +174,48 @@ pub struct Token {
     pub token_type: TokenType,
 }
 
+impl TokenType {
+    /// Whether the token is an instruction mnemonic.
+    pub fn is_opcode(&self) -> bool {
+        matches!(
+            self,
+            TokenType::Invoke
+                | TokenType::CheckCast
+                | TokenType::NewInstance
+                | TokenType::NewArray
+                | TokenType::InstanceOf
+                | TokenType::ConstString
+                | TokenType::ConstInt
+                | TokenType::ConstWide
+                | TokenType::Const
+                | TokenType::If
+                | TokenType::IGet
+                | TokenType::SGet
+                | TokenType::IPut
+                | TokenType::SPut
+                | TokenType::AGet
+                | TokenType::APut
+                | TokenType::FilledNewArray
+                | TokenType::Arithmetic
+                | TokenType::Move
+                | TokenType::Return
+                | TokenType::Throw
+                | TokenType::Goto
+                | TokenType::Switch
+        )
+    }
+
+    /// Whether the instruction can transfer control to a label.
+    pub fn is_branch(&self) -> bool {
+        matches!(self, TokenType::If | TokenType::Goto)
+    }
+
+    /// Whether execution never falls through to the next instruction.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, TokenType::Return | TokenType::Throw | TokenType::Goto)
+    }
+}
+
 impl Token {
     pub fn to_diagnostic(
         &self,
@@ -145,15 +237,44 @@ impl Token {
 }
 
 pub fn lex_str(content: &str) -> Vec<Token> {
-    let mut lex = TokenType::lexer(content);
     let mut output = Vec::new();
+    let mut offset = 0;
+
+    'lex: while offset < content.len() {
+        let mut lex = TokenType::lexer(&content[offset..]);
+
+        while let Some(token_type) = lex.next() {
+            let span = lex.span().start + offset..lex.span().end + offset;
+
+            // Logos doesn't backtrack to the shorter match when a longer pattern
+            // such as `MethodName` fails, so `ILjava/lang/String;` comes back as a
+            // single `BuiltinType` and `v0x1` as a single `Register`. Keep the part
+            // that actually matches and lex the rest again.
+            let valid_len = match token_type {
+                TokenType::BuiltinType => 1,
+                TokenType::Register => 1 + lex.slice()[1..].chars().take_while(char::is_ascii_digit).count(),
+                _ => span.len(),
+            };
+
+            if valid_len < span.len() {
+                output.push(Token {
+                    token_type,
+                    content: content[span.start..span.start + valid_len].to_string(),
+                    range: range_to_lsp_range(span.start..span.start + valid_len, content),
+                });
+
+                offset = span.start + valid_len;
+                continue 'lex;
+            }
+
+            output.push(Token {
+                token_type,
+                content: lex.slice().to_string(),
+                range: range_to_lsp_range(span, content),
+            });
+        }
 
-    while let Some(token_type) = lex.next() {
-        output.push(Token {
-            token_type,
-            content: lex.slice().to_string(),
-            range: range_to_lsp_range(lex.span(), content),
-        });
+        break;
     }
 
     output
@@ -212,6 +333,53 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_lex_str_builtin_types() {
+        let content = "test(IZLjava/lang/String;)V";
+        let tokens = lex_str(content)
+            .into_iter()
+            .map(|token| (token.token_type, token.content))
+            .collect::<Vec<_>>();
+
+        assert_eq!(tokens, vec![
+            (TokenType::MethodName, "test(".to_string()),
+            (TokenType::BuiltinType, "I".to_string()),
+            (TokenType::BuiltinType, "Z".to_string()),
+            (TokenType::Class, "Ljava/lang/String;".to_string()),
+            (TokenType::Paren, ")".to_string()),
+            (TokenType::BuiltinType, "V".to_string()),
+        ]);
+
+        let token = lex_str(content).into_iter().nth(3).unwrap();
+        assert_eq!(token.range, range_to_lsp_range(7..25, content));
+    }
+
+    #[test]
+    fn test_lex_str_builtin_types_across_lines() {
+        let content = "a(JLfoo;)V\nb(ZLbar;)I";
+        let tokens = lex_str(content)
+            .into_iter()
+            .map(|token| (token.token_type, token.content))
+            .collect::<Vec<_>>();
+
+        assert_eq!(tokens, vec![
+            (TokenType::MethodName, "a(".to_string()),
+            (TokenType::BuiltinType, "J".to_string()),
+            (TokenType::Class, "Lfoo;".to_string()),
+            (TokenType::Paren, ")".to_string()),
+            (TokenType::BuiltinType, "V".to_string()),
+            (TokenType::NewLine, "\n".to_string()),
+            (TokenType::MethodName, "b(".to_string()),
+            (TokenType::BuiltinType, "Z".to_string()),
+            (TokenType::Class, "Lbar;".to_string()),
+            (TokenType::Paren, ")".to_string()),
+            (TokenType::BuiltinType, "I".to_string()),
+        ]);
+
+        let token = lex_str(content).into_iter().nth(8).unwrap();
+        assert_eq!(token.range, range_to_lsp_range(14..19, content));
+    }
+
     #[test]
     fn test_comment() {
         let mut lex = TokenType::lexer("# Test");
@@ -275,6 +443,123 @@ mod test {
         assert_eq!(lex.next(), Some(TokenType::BuiltinType));
         assert_eq!(lex.slice(), "Z");
     }
+
+    #[test]
+    fn test_hex_register() {
+        let tokens = lex_str("v0x1");
+
+        assert_eq!(tokens[0].token_type, TokenType::Register);
+        assert_eq!(tokens[0].content, "v0");
+        assert!(tokens[1..].iter().all(|token| token.token_type != TokenType::Register));
+    }
+
+    #[test]
+    fn test_const_widths() {
+        let tokens = lex_str("const/high16 v0, 0x7f01\nconst-wide/16 v1, 0x0\nconst-wide v1, 0x1");
+        let opcodes = tokens
+            .iter()
+            .filter(|token| token.token_type.is_opcode())
+            .map(|token| (token.token_type.clone(), token.content.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(opcodes, vec![
+            (TokenType::ConstInt, "const/high16"),
+            (TokenType::ConstWide, "const-wide/16"),
+            (TokenType::ConstWide, "const-wide"),
+        ]);
+    }
+
+    #[test]
+    fn test_type_operand_opcodes() {
+        let tokens = lex_str("instance-of v0, v1, Lfoo;\nnew-array v0, v1, [I\nnew-instance v0, Lfoo;");
+        let opcodes = tokens
+            .iter()
+            .filter(|token| token.token_type.is_opcode())
+            .map(|token| token.token_type.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(opcodes, vec![TokenType::InstanceOf, TokenType::NewArray, TokenType::NewInstance]);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let tokens = lex_str("add-int v0, v1, v2\nmul-double/2addr v0, v2\nrsub-int/lit8 v0, v1, 0x2\nneg-long v0, v2");
+        let opcodes = tokens
+            .iter()
+            .filter(|token| token.token_type == TokenType::Arithmetic)
+            .map(|token| token.content.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(opcodes, vec!["add-int", "mul-double/2addr", "rsub-int/lit8", "neg-long"]);
+
+        // Bitwise operations only exist for integers
+        for opcode in &["and-float", "shl-double/2addr", "not-float", "xor-double"] {
+            let tokens = lex_str(&format!("{} v0, v1", opcode));
+            assert_ne!(tokens[0].token_type, TokenType::Arithmetic, "{}", opcode);
+        }
+    }
+
+    #[test]
+    fn test_filled_new_array() {
+        let tokens = lex_str("filled-new-array {v0, v1}, [I\nfilled-new-array/range {v0 .. v1}, [Lfoo;");
+        let opcodes = tokens
+            .iter()
+            .filter(|token| token.token_type == TokenType::FilledNewArray)
+            .map(|token| token.content.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(opcodes, vec!["filled-new-array", "filled-new-array/range"]);
+    }
+
+    #[test]
+    fn test_string_stops_at_newline() {
+        let tokens = lex_str("const-string v0, \"open\nconst-string v1, \"closed\"");
+
+        assert_eq!(tokens.iter().filter(|token| token.token_type == TokenType::NewLine).count(), 1);
+        assert!(tokens
+            .iter()
+            .any(|token| token.token_type == TokenType::String && token.content == "\"closed\""));
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let tokens = lex_str(r#"const-string v0, "say \"hi\" # not a comment\\" # comment"#);
+
+        let strings = tokens.iter().filter(|token| token.token_type == TokenType::String).collect::<Vec<_>>();
+        assert_eq!(strings.len(), 1);
+        assert_eq!(strings[0].content, r#""say \"hi\" # not a comment\\""#);
+        assert_eq!(tokens.iter().filter(|token| token.token_type == TokenType::Comment).count(), 1);
+    }
+
+    #[test]
+    fn test_hex_number() {
+        let tokens = lex_str("const v0, 0xffff0000\nconst v1, -0x1");
+        let numbers = tokens
+            .iter()
+            .filter(|token| token.token_type == TokenType::Number)
+            .map(|token| token.content.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(numbers, vec!["0xffff0000", "-0x1"]);
+    }
+
+    #[test]
+    fn test_crlf() {
+        let tokens = lex_str("# Test\r\n.super Ljava/lang/Object;\r\n");
+        let types = tokens
+            .iter()
+            .map(|token| (token.token_type.clone(), token.content.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(types, vec![
+            (TokenType::Comment, "# Test"),
+            (TokenType::NewLine, "\r\n"),
+            (TokenType::Directive, ".super"),
+            (TokenType::Space, " "),
+            (TokenType::Class, "Ljava/lang/Object;"),
+            (TokenType::NewLine, "\r\n"),
+        ]);
+    }
 }
 
 #[cfg(test)]
@@ -305,6 +590,26 @@ mod test_instructions {
         assert_eq!(lex.next(), Some(TokenType::BuiltinType));
         assert_eq!(lex.slice(), "V");
     }
+
+    #[test]
+    fn test_field_access() {
+        let mut lex = TokenType::lexer("    sget-boolean v0, Lme/l3af/Test;->enabled:Z");
+
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::SGet));
+        assert_eq!(lex.slice(), "sget-boolean");
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Register));
+        assert_eq!(lex.slice(), "v0");
+        assert_eq!(lex.next(), Some(TokenType::CommaOp));
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Class));
+        assert_eq!(lex.slice(), "Lme/l3af/Test;");
+        assert_eq!(lex.next(), Some(TokenType::FieldAccess));
+        assert_eq!(lex.slice(), "->enabled:");
+        assert_eq!(lex.next(), Some(TokenType::BuiltinType));
+        assert_eq!(lex.slice(), "Z");
+    }
 }
 
 #[cfg(test)]
@@ -341,6 +646,19 @@ mod test_directives {
         assert_eq!(lex.slice(), "\"TreecordCommands.smali\"");
     }
 
+    #[test]
+    fn test_enum() {
+        let mut lex = TokenType::lexer(".enum Lme/l3af/Kind;->VALUE:Lme/l3af/Kind;");
+
+        assert_eq!(lex.next(), Some(TokenType::Directive));
+        assert_eq!(lex.slice(), ".enum");
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Class));
+        assert_eq!(lex.next(), Some(TokenType::FieldAccess));
+        assert_eq!(lex.slice(), "->VALUE:");
+        assert_eq!(lex.next(), Some(TokenType::Class));
+    }
+
     #[test]
     fn test_super() {
         let mut lex = TokenType::lexer(".super Ljava/lang/Object;");
@@ -405,14 +723,175 @@ mod test_directives {
         assert_eq!(lex.slice(), ".end method");
     }
 
+    #[test]
+    fn test_param() {
+        let mut lex = TokenType::lexer(".param p1, \"name\"\n.end param");
+
+        assert_eq!(lex.next(), Some(TokenType::Param));
+        assert_eq!(lex.slice(), ".param");
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Register));
+        assert_eq!(lex.slice(), "p1");
+        assert_eq!(lex.next(), Some(TokenType::CommaOp));
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::String));
+        assert_eq!(lex.slice(), "\"name\"");
+        assert_eq!(lex.next(), Some(TokenType::NewLine));
+        assert_eq!(lex.next(), Some(TokenType::Param));
+        assert_eq!(lex.slice(), ".end param");
+    }
+
+    #[test]
+    fn test_annotation() {
+        let mut lex = TokenType::lexer(".annotation runtime Lfoo/Bar;\n.end annotation");
+
+        assert_eq!(lex.next(), Some(TokenType::Annotation));
+        assert_eq!(lex.slice(), ".annotation");
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        lex.next();
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Class));
+        assert_eq!(lex.next(), Some(TokenType::NewLine));
+        assert_eq!(lex.next(), Some(TokenType::Annotation));
+        assert_eq!(lex.slice(), ".end annotation");
+    }
+
     #[test]
     fn test_goto() {
-        let mut lex = TokenType::lexer(".goto :goto_12");
+        let mut lex = TokenType::lexer("goto :goto_12");
 
-        assert_eq!(lex.next(), Some(TokenType::Directive));
-        assert_eq!(lex.slice(), ".goto");
+        assert_eq!(lex.next(), Some(TokenType::Goto));
+        assert_eq!(lex.slice(), "goto");
         assert_eq!(lex.next(), Some(TokenType::Space));
         assert_eq!(lex.next(), Some(TokenType::Label));
         assert_eq!(lex.slice(), ":goto_12");
     }
+
+    #[test]
+    fn test_catch() {
+        let mut lex = TokenType::lexer(":catchall_1f\nmove-exception v0\n:try_start_0");
+
+        assert_eq!(lex.next(), Some(TokenType::Label));
+        assert_eq!(lex.slice(), ":catchall_1f");
+        assert_eq!(lex.next(), Some(TokenType::NewLine));
+        assert_eq!(lex.next(), Some(TokenType::Move));
+        assert_eq!(lex.slice(), "move-exception");
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Register));
+        assert_eq!(lex.next(), Some(TokenType::NewLine));
+        assert_eq!(lex.next(), Some(TokenType::Label));
+        assert_eq!(lex.slice(), ":try_start_0");
+    }
+
+    #[test]
+    fn opcode_classification() {
+        for token_type in &[
+            TokenType::Invoke,
+            TokenType::Move,
+            TokenType::IGet,
+            TokenType::AGet,
+            TokenType::ConstString,
+        ] {
+            assert!(token_type.is_opcode());
+            assert!(!token_type.is_branch());
+            assert!(!token_type.is_terminal());
+        }
+
+        assert!(TokenType::If.is_opcode());
+        assert!(TokenType::If.is_branch());
+        assert!(!TokenType::If.is_terminal());
+
+        assert!(TokenType::Goto.is_branch());
+        assert!(TokenType::Goto.is_terminal());
+
+        for token_type in &[TokenType::Return, TokenType::Throw] {
+            assert!(token_type.is_opcode());
+            assert!(!token_type.is_branch());
+            assert!(token_type.is_terminal());
+        }
+
+        for token_type in &[TokenType::Directive, TokenType::Label, TokenType::Register, TokenType::Comment] {
+            assert!(!token_type.is_opcode());
+            assert!(!token_type.is_branch());
+            assert!(!token_type.is_terminal());
+        }
+    }
+
+    #[test]
+    fn test_switch() {
+        let mut lex = TokenType::lexer("packed-switch v0, :pswitch_data_0\n.packed-switch 0x1\n.end sparse-switch");
+
+        assert_eq!(lex.next(), Some(TokenType::Switch));
+        assert_eq!(lex.slice(), "packed-switch");
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Register));
+        assert_eq!(lex.next(), Some(TokenType::CommaOp));
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Label));
+        assert_eq!(lex.next(), Some(TokenType::NewLine));
+        assert_eq!(lex.next(), Some(TokenType::SwitchPayload));
+        assert_eq!(lex.slice(), ".packed-switch");
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Number));
+        assert_eq!(lex.next(), Some(TokenType::NewLine));
+        assert_eq!(lex.next(), Some(TokenType::SwitchPayload));
+        assert_eq!(lex.slice(), ".end sparse-switch");
+    }
+
+    #[test]
+    fn test_catch_directive() {
+        let mut lex = TokenType::lexer(".catch Ljava/lang/Exception; {:try_start_0 .. :try_end_0} :catch_0\n.catchall");
+
+        assert_eq!(lex.next(), Some(TokenType::Catch));
+        assert_eq!(lex.slice(), ".catch");
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Class));
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Brace));
+        assert_eq!(lex.next(), Some(TokenType::Label));
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::RangeOp));
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Label));
+        assert_eq!(lex.next(), Some(TokenType::Brace));
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Label));
+        assert_eq!(lex.slice(), ":catch_0");
+        assert_eq!(lex.next(), Some(TokenType::NewLine));
+        assert_eq!(lex.next(), Some(TokenType::Catch));
+        assert_eq!(lex.slice(), ".catchall");
+    }
+
+    #[test]
+    fn test_throw_goto() {
+        let mut lex = TokenType::lexer("throw v0\ngoto/16 :goto_1");
+
+        assert_eq!(lex.next(), Some(TokenType::Throw));
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Register));
+        assert_eq!(lex.next(), Some(TokenType::NewLine));
+        assert_eq!(lex.next(), Some(TokenType::Goto));
+        assert_eq!(lex.slice(), "goto/16");
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Label));
+    }
+
+    #[test]
+    fn test_array_access() {
+        let mut lex = TokenType::lexer("aget-object v0, v1, v2\naput v0, v1, v2");
+
+        assert_eq!(lex.next(), Some(TokenType::AGet));
+        assert_eq!(lex.slice(), "aget-object");
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Register));
+        assert_eq!(lex.next(), Some(TokenType::CommaOp));
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Register));
+        assert_eq!(lex.next(), Some(TokenType::CommaOp));
+        assert_eq!(lex.next(), Some(TokenType::Space));
+        assert_eq!(lex.next(), Some(TokenType::Register));
+        assert_eq!(lex.next(), Some(TokenType::NewLine));
+        assert_eq!(lex.next(), Some(TokenType::APut));
+        assert_eq!(lex.slice(), "aput");
+    }
 }
@@ -0,0 +1,84 @@
+use lspower::lsp::{LinkedEditingRanges, Position, Range};
+
+use super::{
+    helper::{method_lines_at, split_lines, token_at},
+    lexer::{lex_str, TokenType},
+};
+
+/// Returns the name of every occurrence of the label under `position` within
+/// the enclosing method, the definition and each reference, so they can be
+/// edited together.
+pub fn linked_editing_ranges(content: &str, position: Position) -> Option<LinkedEditingRanges> {
+    let tokens = lex_str(content);
+    let label = token_at(&tokens, position).filter(|token| token.token_type == TokenType::Label)?;
+
+    let lines = split_lines(tokens.clone());
+    let method = method_lines_at(&lines, position.line)?;
+
+    // The leading `:` isn't part of the name
+    let ranges = method
+        .iter()
+        .flatten()
+        .filter(|token| token.token_type == TokenType::Label && token.content == label.content)
+        .map(|token| {
+            Range::new(
+                Position::new(token.range.start.line, token.range.start.character + 1),
+                token.range.end,
+            )
+        })
+        .collect();
+
+    Some(LinkedEditingRanges {
+        ranges,
+        word_pattern: Some("[a-zA-Z0-9_]+".to_string()),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range};
+
+    use super::linked_editing_ranges;
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.method public static test(I)V
+    .locals 0
+
+    if-eqz p0, :cond_0
+
+    if-ltz p0, :cond_0
+
+    :cond_0
+    return-void
+.end method
+
+.method public static other()V
+    .locals 0
+
+    :cond_0
+    return-void
+.end method
+";
+
+    #[test]
+    fn label_ranges() {
+        let expected = vec![
+            Range::new(Position::new(6, 16), Position::new(6, 22)),
+            Range::new(Position::new(8, 16), Position::new(8, 22)),
+            Range::new(Position::new(10, 5), Position::new(10, 11)),
+        ];
+
+        for position in [Position::new(6, 18), Position::new(10, 4)] {
+            let ranges = linked_editing_ranges(CONTENT, position).unwrap();
+            assert_eq!(ranges.ranges, expected);
+        }
+    }
+
+    #[test]
+    fn not_a_label() {
+        assert!(linked_editing_ranges(CONTENT, Position::new(6, 12)).is_none());
+        assert!(linked_editing_ranges(CONTENT, Position::new(0, 20)).is_none());
+    }
+}
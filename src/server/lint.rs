@@ -0,0 +1,117 @@
+use std::{
+    env,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use lspower::lsp::{Diagnostic, DiagnosticSeverity, Url};
+use rayon::{prelude::*, ThreadPoolBuilder};
+use serde::Serialize;
+
+use super::{
+    ignore,
+    index::{smali_files, ClassIndex},
+    settings::Settings,
+    validation::{validate, ValidationContext},
+};
+
+/// Diagnostics produced for a single file by a lint run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileReport {
+    pub path:        PathBuf,
+    pub errors:      usize,
+    pub warnings:    usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl FileReport {
+    pub fn new(path: PathBuf, diagnostics: Vec<Diagnostic>) -> Self {
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|diag| diag.severity == Some(severity))
+                .count()
+        };
+
+        Self {
+            errors: count(DiagnosticSeverity::Error),
+            warnings: count(DiagnosticSeverity::Warning),
+            path,
+            diagnostics,
+        }
+    }
+}
+
+/// Expands `paths` into every `.smali` file they contain, directories are
+/// walked recursively and files are kept as is.
+pub fn collect_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .flat_map(|path| if path.is_dir() { smali_files(path) } else { vec![path.clone()] })
+        .collect()
+}
+
+/// Validates every `.smali` file under `paths` across `jobs` threads,
+/// skipping those matched by the `ignore` patterns.
+///
+/// All files are indexed before validation, so cross-file checks see the
+/// whole tree. Reports are returned in path order.
+pub fn lint_paths(paths: &[PathBuf], jobs: usize, settings: &Settings) -> Result<Vec<FileReport>, String> {
+    let files = collect_files(paths)
+        .into_iter()
+        .filter(|path| !ignore::is_ignored(path, paths, &settings.ignore))
+        .collect::<Vec<_>>();
+
+    let mut index = ClassIndex::default();
+    for path in &files {
+        if let (Ok(uri), Ok(content)) = (file_uri(path), fs::read_to_string(path)) {
+            index.index_content(&uri, &content);
+        }
+    }
+
+    let context = ValidationContext {
+        index: &index,
+        settings,
+    };
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(|why| why.to_string())?;
+
+    pool.install(|| {
+        files
+            .par_iter()
+            .map(|path| {
+                let content = fs::read_to_string(path).map_err(|why| format!("{}: {}", path.display(), why))?;
+                let diags = validate(content, &context).map_err(|why| format!("{}: {}", path.display(), why))?;
+
+                Ok(FileReport::new(path.clone(), diags))
+            })
+            .collect()
+    })
+}
+
+/// Validates `content` read from somewhere other than the file system, such
+/// as stdin, reporting it under `path`.
+pub fn lint_content(path: &Path, content: String, settings: &Settings) -> Result<FileReport, String> {
+    // The file may not exist, so it can't be canonicalized
+    let absolute = env::current_dir().map(|dir| dir.join(path)).unwrap_or_else(|_| path.to_path_buf());
+
+    let mut index = ClassIndex::default();
+    if let Ok(uri) = Url::from_file_path(absolute) {
+        index.index_content(&uri, &content);
+    }
+
+    let context = ValidationContext {
+        index: &index,
+        settings,
+    };
+    let diags = validate(content, &context).map_err(|why| format!("{}: {}", path.display(), why))?;
+
+    Ok(FileReport::new(path.to_path_buf(), diags))
+}
+
+fn file_uri(path: &Path) -> Result<Url, ()> {
+    Url::from_file_path(fs::canonicalize(path).map_err(|_| ())?)
+}
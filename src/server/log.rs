@@ -0,0 +1,41 @@
+use lspower::lsp::MessageType;
+use serde::Deserialize;
+
+/// How much the server writes to the client's output channel, messages below
+/// the configured level are dropped.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+    /// Routine per-document activity, such as each validation.
+    Trace,
+    Debug,
+    Info,
+}
+
+impl LogLevel {
+    pub fn enabled(self, threshold: LogLevel) -> bool {
+        self >= threshold
+    }
+
+    /// `window/logMessage` has no trace or debug types, those are sent as
+    /// plain logs.
+    pub fn message_type(self) -> MessageType {
+        match self {
+            LogLevel::Trace | LogLevel::Debug => MessageType::Log,
+            LogLevel::Info => MessageType::Info,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LogLevel;
+
+    #[test]
+    fn levels() {
+        assert!(LogLevel::Info.enabled(LogLevel::Info));
+        assert!(LogLevel::Info.enabled(LogLevel::Trace));
+        assert!(!LogLevel::Trace.enabled(LogLevel::Info));
+        assert!(!LogLevel::Debug.enabled(LogLevel::Info));
+    }
+}
@@ -1,4 +1,29 @@
+pub mod blocks;
+pub mod cache;
+pub mod catch;
+pub mod colors;
+pub mod commands;
+pub mod completion;
+pub mod definition;
+pub mod descriptor;
+pub mod document;
+pub mod error;
+pub mod folding;
+pub mod format;
 pub mod lexer;
 pub mod helper;
+pub mod hover;
+pub mod ignore;
+pub mod index;
+pub mod inlay_hint;
+pub mod linked_editing;
+pub mod lint;
+pub mod log;
+pub mod opcodes;
+pub mod registers;
+pub mod rename;
+pub mod semantic_tokens;
+pub mod settings;
+pub mod symbols;
 pub mod validation;
 
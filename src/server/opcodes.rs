@@ -0,0 +1,443 @@
+use super::{
+    descriptor::read_type,
+    lexer::{Token, TokenType},
+};
+
+/// The kind of operand an instruction takes at a given position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operand {
+    /// A single `vN` or `pN` register.
+    Register,
+    /// A `{vA, vB}` register list or `{vA .. vB}` register range.
+    RegisterList,
+    /// An integer literal, e.g. `0x1` or `-5`.
+    Literal,
+    /// A string literal.
+    String,
+    /// A class or array type descriptor.
+    Type,
+    /// A field reference, e.g. `Lfoo;->bar:I`.
+    FieldRef,
+    /// A method reference, e.g. `Lfoo;->bar(I)V`.
+    MethodRef,
+    /// A `:label` in the same method.
+    Label,
+}
+
+impl Operand {
+    /// Describes the operand for diagnostics, e.g. `a register`.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Operand::Register => "a register",
+            Operand::RegisterList => "a register list",
+            Operand::Literal => "a literal",
+            Operand::String => "a string",
+            Operand::Type => "a type",
+            Operand::FieldRef => "a field reference",
+            Operand::MethodRef => "a method reference",
+            Operand::Label => "a label",
+        }
+    }
+}
+
+/// An instruction mnemonic and the operands it takes, in order.
+#[derive(Debug, PartialEq)]
+pub struct Opcode {
+    pub name:     &'static str,
+    pub operands: &'static [Operand],
+}
+
+impl Opcode {
+    /// Renders the instruction with placeholder operands, e.g.
+    /// `move vA, vB`.
+    pub fn usage(&self) -> String {
+        let mut registers = (b'A'..=b'Z').map(char::from);
+        let operands = self
+            .operands
+            .iter()
+            .map(|operand| match operand {
+                Operand::Register => format!("v{}", registers.next().unwrap_or('N')),
+                Operand::RegisterList => "{vC, vD, ...}".to_string(),
+                Operand::Literal => "lit".to_string(),
+                Operand::String => "\"value\"".to_string(),
+                Operand::Type => "Lclass/Name;".to_string(),
+                Operand::FieldRef => "Lclass/Name;->field:Type".to_string(),
+                Operand::MethodRef => "Lclass/Name;->method()V".to_string(),
+                Operand::Label => ":label".to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        if operands.is_empty() {
+            self.name.to_string()
+        } else {
+            format!("{} {}", self.name, operands.join(", "))
+        }
+    }
+}
+
+const NONE: &[Operand] = &[];
+const REG: &[Operand] = &[Operand::Register];
+const REG_REG: &[Operand] = &[Operand::Register, Operand::Register];
+const REG_REG_REG: &[Operand] = &[Operand::Register, Operand::Register, Operand::Register];
+const REG_LIT: &[Operand] = &[Operand::Register, Operand::Literal];
+const REG_REG_LIT: &[Operand] = &[Operand::Register, Operand::Register, Operand::Literal];
+const REG_STRING: &[Operand] = &[Operand::Register, Operand::String];
+const REG_TYPE: &[Operand] = &[Operand::Register, Operand::Type];
+const REG_REG_TYPE: &[Operand] = &[Operand::Register, Operand::Register, Operand::Type];
+const REG_FIELD: &[Operand] = &[Operand::Register, Operand::FieldRef];
+const REG_REG_FIELD: &[Operand] = &[Operand::Register, Operand::Register, Operand::FieldRef];
+const REG_LABEL: &[Operand] = &[Operand::Register, Operand::Label];
+const REG_REG_LABEL: &[Operand] = &[Operand::Register, Operand::Register, Operand::Label];
+const INVOKE: &[Operand] = &[Operand::RegisterList, Operand::MethodRef];
+const LIST_TYPE: &[Operand] = &[Operand::RegisterList, Operand::Type];
+const LABEL: &[Operand] = &[Operand::Label];
+
+macro_rules! opcodes {
+    ($($operands:expr => [$($name:literal),+ $(,)?]),* $(,)?) => {
+        &[$($(Opcode { name: $name, operands: $operands },)+)*]
+    };
+}
+
+/// Every instruction the lexer recognises, with its operand shape.
+pub const OPCODES: &[Opcode] = opcodes![
+    NONE => ["return-void"],
+    REG => [
+        "move-result",
+        "move-result-object",
+        "move-result-wide",
+        "move-exception",
+        "return",
+        "return-object",
+        "return-wide",
+        "throw",
+    ],
+    REG_REG => [
+        "move",
+        "move/from16",
+        "move/16",
+        "move-object",
+        "move-object/from16",
+        "move-object/16",
+        "move-wide",
+        "move-wide/from16",
+        "move-wide/16",
+    ],
+    REG_LIT => [
+        "const",
+        "const/4",
+        "const/16",
+        "const/high16",
+        "const-wide",
+        "const-wide/16",
+        "const-wide/32",
+        "const-wide/high16",
+    ],
+    REG_STRING => ["const-string", "const-string/jumbo"],
+    REG_TYPE => ["const-class", "check-cast", "new-instance"],
+    REG_REG_TYPE => ["instance-of", "new-array"],
+    REG_LABEL => ["if-eqz", "if-nez", "if-ltz", "if-gez", "if-gtz", "if-lez", "packed-switch", "sparse-switch"],
+    REG_REG_LABEL => ["if-eq", "if-ne", "if-lt", "if-ge", "if-gt", "if-le"],
+    REG_REG_REG => [
+        "aget",
+        "aget-wide",
+        "aget-object",
+        "aget-boolean",
+        "aget-byte",
+        "aget-char",
+        "aget-short",
+        "aput",
+        "aput-wide",
+        "aput-object",
+        "aput-boolean",
+        "aput-byte",
+        "aput-char",
+        "aput-short",
+    ],
+    REG_REG_FIELD => [
+        "iget",
+        "iget-wide",
+        "iget-object",
+        "iget-boolean",
+        "iget-byte",
+        "iget-char",
+        "iget-short",
+        "iput",
+        "iput-wide",
+        "iput-object",
+        "iput-boolean",
+        "iput-byte",
+        "iput-char",
+        "iput-short",
+    ],
+    REG_FIELD => [
+        "sget",
+        "sget-wide",
+        "sget-object",
+        "sget-boolean",
+        "sget-byte",
+        "sget-char",
+        "sget-short",
+        "sput",
+        "sput-wide",
+        "sput-object",
+        "sput-boolean",
+        "sput-byte",
+        "sput-char",
+        "sput-short",
+    ],
+    INVOKE => [
+        "invoke-direct",
+        "invoke-direct/range",
+        "invoke-static",
+        "invoke-static/range",
+        "invoke-virtual",
+        "invoke-virtual/range",
+        "invoke-interface",
+        "invoke-interface/range",
+    ],
+    REG_REG_REG => [
+        "add-int",
+        "sub-int",
+        "mul-int",
+        "div-int",
+        "rem-int",
+        "and-int",
+        "or-int",
+        "xor-int",
+        "shl-int",
+        "shr-int",
+        "ushr-int",
+        "add-long",
+        "sub-long",
+        "mul-long",
+        "div-long",
+        "rem-long",
+        "and-long",
+        "or-long",
+        "xor-long",
+        "shl-long",
+        "shr-long",
+        "ushr-long",
+        "add-float",
+        "sub-float",
+        "mul-float",
+        "div-float",
+        "rem-float",
+        "add-double",
+        "sub-double",
+        "mul-double",
+        "div-double",
+        "rem-double",
+    ],
+    REG_REG => [
+        "add-int/2addr",
+        "sub-int/2addr",
+        "mul-int/2addr",
+        "div-int/2addr",
+        "rem-int/2addr",
+        "and-int/2addr",
+        "or-int/2addr",
+        "xor-int/2addr",
+        "shl-int/2addr",
+        "shr-int/2addr",
+        "ushr-int/2addr",
+        "add-long/2addr",
+        "sub-long/2addr",
+        "mul-long/2addr",
+        "div-long/2addr",
+        "rem-long/2addr",
+        "and-long/2addr",
+        "or-long/2addr",
+        "xor-long/2addr",
+        "shl-long/2addr",
+        "shr-long/2addr",
+        "ushr-long/2addr",
+        "add-float/2addr",
+        "sub-float/2addr",
+        "mul-float/2addr",
+        "div-float/2addr",
+        "rem-float/2addr",
+        "add-double/2addr",
+        "sub-double/2addr",
+        "mul-double/2addr",
+        "div-double/2addr",
+        "rem-double/2addr",
+        "neg-int",
+        "not-int",
+        "neg-long",
+        "not-long",
+        "neg-float",
+        "neg-double",
+    ],
+    REG_REG_LIT => [
+        "add-int/lit16",
+        "rsub-int",
+        "mul-int/lit16",
+        "div-int/lit16",
+        "rem-int/lit16",
+        "and-int/lit16",
+        "or-int/lit16",
+        "xor-int/lit16",
+        "add-int/lit8",
+        "rsub-int/lit8",
+        "mul-int/lit8",
+        "div-int/lit8",
+        "rem-int/lit8",
+        "and-int/lit8",
+        "or-int/lit8",
+        "xor-int/lit8",
+        "shl-int/lit8",
+        "shr-int/lit8",
+        "ushr-int/lit8",
+    ],
+    LIST_TYPE => ["filled-new-array", "filled-new-array/range"],
+    LABEL => ["goto", "goto/16", "goto/32"],
+];
+
+/// Looks up the catalog entry for the mnemonic `name`.
+pub fn lookup(name: &str) -> Option<&'static Opcode> {
+    OPCODES.iter().find(|opcode| opcode.name == name)
+}
+
+/// Splits the operands of the instruction in `line` on the commas between
+/// them, commas inside a register list are kept with the list.
+pub fn split_operands(line: &[Token]) -> Vec<&[Token]> {
+    let mut operands = Vec::new();
+    let mut start = 1;
+    let mut in_list = false;
+
+    for (idx, token) in line.iter().enumerate().skip(1) {
+        match (&token.token_type, token.content.as_ref()) {
+            (TokenType::Brace, "{") => in_list = true,
+            (TokenType::Brace, "}") => in_list = false,
+            (TokenType::CommaOp, _) if !in_list => {
+                operands.push(trim_spaces(&line[start..idx]));
+                start = idx + 1;
+            },
+            _ => {},
+        }
+    }
+
+    let last = trim_spaces(&line[start.min(line.len())..]);
+    if !last.is_empty() || !operands.is_empty() {
+        operands.push(last);
+    }
+
+    operands
+}
+
+/// Works out which kind of operand `tokens` is, `None` if it isn't any.
+pub fn classify(tokens: &[Token]) -> Option<Operand> {
+    let first = tokens.first()?;
+
+    match (&first.token_type, tokens.len()) {
+        (TokenType::Register, 1) => return Some(Operand::Register),
+        (TokenType::Number, 1) => return Some(Operand::Literal),
+        (TokenType::String, 1) => return Some(Operand::String),
+        (TokenType::Label, 1) => return Some(Operand::Label),
+        (TokenType::Brace, _) => return classify_register_list(tokens),
+        _ => {},
+    }
+
+    let (_, consumed) = read_type(tokens)?;
+    let rest = &tokens[consumed..];
+
+    match rest.first().map(|token| &token.token_type) {
+        None if first.token_type != TokenType::BuiltinType => Some(Operand::Type),
+        Some(TokenType::FieldAccess) if type_fills(&rest[1..]) => Some(Operand::FieldRef),
+        Some(TokenType::MethodCall) => classify_method_signature(&rest[1..]),
+        _ => None,
+    }
+}
+
+fn classify_register_list(tokens: &[Token]) -> Option<Operand> {
+    let (last, inner) = tokens[1..].split_last()?;
+    if last.content != "}" {
+        return None;
+    }
+
+    let is_list = inner.iter().all(|token| {
+        matches!(
+            token.token_type,
+            TokenType::Register | TokenType::CommaOp | TokenType::RangeOp | TokenType::Space
+        )
+    });
+
+    if is_list { Some(Operand::RegisterList) } else { None }
+}
+
+/// Checks the `(params)Return` following a method name.
+fn classify_method_signature(tokens: &[Token]) -> Option<Operand> {
+    let mut idx = 0;
+    while tokens.get(idx)?.content != ")" {
+        let (_, consumed) = read_type(&tokens[idx..])?;
+        idx += consumed;
+    }
+
+    if type_fills(&tokens[idx + 1..]) { Some(Operand::MethodRef) } else { None }
+}
+
+/// Whether `tokens` is exactly one type descriptor.
+fn type_fills(tokens: &[Token]) -> bool {
+    matches!(read_type(tokens), Some((_, consumed)) if consumed == tokens.len())
+}
+
+fn trim_spaces(tokens: &[Token]) -> &[Token] {
+    let start = tokens.iter().position(|token| token.token_type != TokenType::Space);
+    let end = tokens.iter().rposition(|token| token.token_type != TokenType::Space);
+
+    match (start, end) {
+        (Some(start), Some(end)) => &tokens[start..=end],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{classify, lookup, split_operands, Operand};
+    use crate::server::lexer::lex_str;
+
+    fn shape(line: &str) -> Vec<Option<Operand>> {
+        let tokens = lex_str(line);
+        split_operands(&tokens).into_iter().map(classify).collect()
+    }
+
+    #[test]
+    fn operand_shapes() {
+        assert_eq!(shape("move v0, p1"), vec![Some(Operand::Register), Some(Operand::Register)]);
+        assert_eq!(shape("const/4 v0, -0x1"), vec![Some(Operand::Register), Some(Operand::Literal)]);
+        assert_eq!(shape("invoke-virtual/range {v0 .. v2}, Lfoo;->bar([IJ)[Lfoo;"), vec![
+            Some(Operand::RegisterList),
+            Some(Operand::MethodRef)
+        ]);
+        assert_eq!(shape("invoke-static {}, Lfoo;->bar()V"), vec![
+            Some(Operand::RegisterList),
+            Some(Operand::MethodRef)
+        ]);
+        assert_eq!(shape("iget-object v0, p0, Lfoo;->bar:[Ljava/lang/String;"), vec![
+            Some(Operand::Register),
+            Some(Operand::Register),
+            Some(Operand::FieldRef)
+        ]);
+        assert_eq!(shape("check-cast v0, [Lfoo;"), vec![Some(Operand::Register), Some(Operand::Type)]);
+        assert_eq!(shape("if-eqz v0, :cond_0"), vec![Some(Operand::Register), Some(Operand::Label)]);
+        assert!(shape("return-void").is_empty());
+    }
+
+    #[test]
+    fn malformed_operands() {
+        assert_eq!(shape("move v0, "), vec![Some(Operand::Register), None]);
+        assert_eq!(shape("check-cast v0, I"), vec![Some(Operand::Register), None]);
+        assert_eq!(shape("invoke-static {v0}, Lfoo;->bar(I"), vec![Some(Operand::RegisterList), None]);
+        assert_eq!(shape("sget v0, Lfoo;->bar:"), vec![Some(Operand::Register), None]);
+    }
+
+    #[test]
+    fn usage() {
+        assert_eq!(lookup("move").unwrap().usage(), "move vA, vB");
+        assert_eq!(lookup("const/16").unwrap().usage(), "const/16 vA, lit");
+        assert_eq!(lookup("return-void").unwrap().usage(), "return-void");
+        assert_eq!(lookup("add-int/lit8").unwrap().usage(), "add-int/lit8 vA, vB, lit");
+        assert!(lookup("move-result/16").is_none());
+    }
+}
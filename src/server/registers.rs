@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+
+use super::{
+    descriptor::{is_wide, read_type, return_type},
+    helper::parse_count,
+    lexer::{Token, TokenType},
+};
+
+/// The type of value a register is known to hold.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegisterType {
+    /// A primitive value with the given descriptor, e.g. `I` or `J`.
+    Primitive(String),
+    /// An initialized reference with the given class or array descriptor.
+    Reference(String),
+    /// The result of `new-instance` before its `<init>` has been invoked.
+    Uninitialized(String),
+    /// A zero literal, which can be either `null` or a numeric zero.
+    Null,
+}
+
+impl RegisterType {
+    pub fn from_descriptor(descriptor: &str) -> Option<Self> {
+        match descriptor.chars().next()? {
+            'V' => None,
+            'L' | '[' => Some(RegisterType::Reference(descriptor.to_string())),
+            _ => Some(RegisterType::Primitive(descriptor.to_string())),
+        }
+    }
+}
+
+/// Tracks register types through a method body in a single linear pass.
+///
+/// Branches aren't followed, so the tracked type is the type most recently
+/// written to a register above the current line rather than a proper
+/// dataflow result.
+#[derive(Debug, Default)]
+pub struct RegisterTracker {
+    types:       HashMap<String, RegisterType>,
+    last_result: Option<RegisterType>,
+}
+
+impl RegisterTracker {
+    pub fn get(&self, register: &str) -> Option<&RegisterType> {
+        self.types.get(register)
+    }
+
+    pub fn reset(&mut self) {
+        self.types.clear();
+        self.last_result = None;
+    }
+
+    /// Applies the effects of the instruction in `line` to the tracked types.
+    pub fn update(&mut self, line: &[Token]) {
+        let registers = line
+            .iter()
+            .filter(|token| token.token_type == TokenType::Register)
+            .map(|token| token.content.clone())
+            .collect::<Vec<_>>();
+
+        let result = self.last_result.take();
+
+        if line[0].token_type == TokenType::Method {
+            self.reset();
+            return;
+        }
+
+        if line[0].token_type == TokenType::Invoke {
+            self.last_result = return_type(line).and_then(|descriptor| RegisterType::from_descriptor(&descriptor));
+        }
+
+        if line[0].token_type == TokenType::FilledNewArray {
+            self.last_result = array_type(line).map(RegisterType::Reference);
+        }
+
+        let dest = match registers.first() {
+            Some(dest) => dest.clone(),
+            None => return,
+        };
+
+        match line[0].token_type {
+            TokenType::NewInstance => {
+                if let Some(class) = line.iter().find(|token| token.token_type == TokenType::Class) {
+                    self.types.insert(dest, RegisterType::Uninitialized(class.content.clone()));
+                }
+            },
+            TokenType::Invoke => {
+                let is_init = line
+                    .iter()
+                    .any(|token| token.token_type == TokenType::MethodCall && token.content == "-><init>(");
+
+                // Only the receiver is initialised, other instances of the
+                // same class still need their own `<init>`
+                if is_init {
+                    if let Some(RegisterType::Uninitialized(class)) = self.types.get(&dest).cloned() {
+                        self.types.insert(dest, RegisterType::Reference(class));
+                    }
+                }
+            },
+            TokenType::Move => {
+                let value = if line[0].content.starts_with("move-result") {
+                    result
+                } else {
+                    registers.get(1).and_then(|src| self.types.get(src).cloned())
+                };
+
+                self.set(dest, value);
+            },
+            TokenType::ConstString => {
+                self.set(dest, Some(RegisterType::Reference("Ljava/lang/String;".to_string())));
+            },
+            TokenType::Const if line[0].content == "const-class" => {
+                self.set(dest, Some(RegisterType::Reference("Ljava/lang/Class;".to_string())));
+            },
+            TokenType::Const | TokenType::ConstInt => {
+                let is_zero = line
+                    .iter()
+                    .find(|token| token.token_type == TokenType::Number)
+                    .and_then(|number| parse_count(number.content.trim_start_matches('-')))
+                    == Some(0);
+
+                self.set(dest, Some(if is_zero {
+                    RegisterType::Null
+                } else {
+                    RegisterType::Primitive("I".to_string())
+                }));
+            },
+            // The literal alone doesn't tell a long from a double, only that
+            // the value is wide
+            TokenType::ConstWide => {
+                self.set(dest, Some(RegisterType::Primitive("J".to_string())));
+            },
+            TokenType::Arithmetic => {
+                let opcode = &line[0].content;
+                let primitive = if opcode.contains("-long") {
+                    "J"
+                } else if opcode.contains("-float") {
+                    "F"
+                } else if opcode.contains("-double") {
+                    "D"
+                } else {
+                    "I"
+                };
+
+                self.set(dest, Some(RegisterType::Primitive(primitive.to_string())));
+            },
+            TokenType::InstanceOf => {
+                self.set(dest, Some(RegisterType::Primitive("Z".to_string())));
+            },
+            TokenType::NewArray => {
+                let value = array_type(line).map(RegisterType::Reference);
+
+                self.set(dest, value);
+            },
+            TokenType::CheckCast => {
+                let idx = line.iter().rposition(|token| token.token_type == TokenType::CommaOp);
+                let value = idx
+                    .and_then(|idx| read_type(trim_leading_space(&line[idx + 1..])))
+                    .and_then(|(descriptor, _)| RegisterType::from_descriptor(&descriptor));
+
+                self.set(dest, value);
+            },
+            TokenType::IGet | TokenType::SGet => {
+                let idx = line.iter().position(|token| token.token_type == TokenType::FieldAccess);
+                let value = idx
+                    .and_then(|idx| read_type(&line[idx + 1..]))
+                    .and_then(|(descriptor, _)| RegisterType::from_descriptor(&descriptor));
+
+                self.set(dest, value);
+            },
+            TokenType::AGet => {
+                let value = match registers.get(1).and_then(|array| self.types.get(array)) {
+                    Some(RegisterType::Reference(array)) if array.starts_with('[') => {
+                        RegisterType::from_descriptor(&array[1..])
+                    },
+                    _ => None,
+                };
+
+                self.set(dest, value);
+            },
+            _ => {},
+        }
+    }
+
+    fn set(&mut self, register: String, value: Option<RegisterType>) {
+        // A wide value also overwrites the register after it
+        if let Some(RegisterType::Primitive(primitive)) = &value {
+            if is_wide(primitive) {
+                if let Ok(idx) = register[1..].parse::<u32>() {
+                    self.types.remove(&format!("{}{}", &register[..1], idx + 1));
+                }
+            }
+        }
+
+        match value {
+            Some(value) => self.types.insert(register, value),
+            None => self.types.remove(&register),
+        };
+    }
+}
+
+/// How an instruction uses one of its register operands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Access {
+    Read,
+    Write,
+    /// Read and then overwritten, e.g. the destination of `check-cast`.
+    ReadWrite,
+}
+
+/// Classifies every register operand of the instruction in `line`.
+pub fn register_accesses(line: &[Token]) -> Vec<(&Token, Access)> {
+    let opcode = &line[0];
+    let dest_access = match opcode.token_type {
+        TokenType::CheckCast => Some(Access::ReadWrite),
+        TokenType::Arithmetic if opcode.content.ends_with("/2addr") => Some(Access::ReadWrite),
+        TokenType::Move
+        | TokenType::ConstString
+        | TokenType::ConstInt
+        | TokenType::ConstWide
+        | TokenType::Const
+        | TokenType::NewInstance
+        | TokenType::NewArray
+        | TokenType::InstanceOf
+        | TokenType::IGet
+        | TokenType::SGet
+        | TokenType::AGet
+        | TokenType::Arithmetic => Some(Access::Write),
+        ref token_type if token_type.is_opcode() => None,
+        _ => return Vec::new(),
+    };
+
+    line.iter()
+        .filter(|token| token.token_type == TokenType::Register)
+        .enumerate()
+        .map(|(idx, token)| match dest_access {
+            Some(access) if idx == 0 => (token, access),
+            _ => (token, Access::Read),
+        })
+        .collect()
+}
+
+/// Reads the array type operand following the last comma of a
+/// `new-array` or `filled-new-array`.
+pub fn array_type(line: &[Token]) -> Option<String> {
+    let idx = line.iter().rposition(|token| token.token_type == TokenType::CommaOp)?;
+
+    read_type(trim_leading_space(&line[idx + 1..]))
+        .map(|(descriptor, _)| descriptor)
+        .filter(|descriptor| descriptor.starts_with('['))
+}
+
+/// Dalvik register indices are 16 bit, so a method can use at most this many
+/// registers including its parameters.
+pub const MAX_REGISTERS: u32 = 65535;
+
+/// Lists the registers passed in a `{vA, vB}` list or `{vA .. vB}` range.
+///
+/// Reversed ranges and ranges past the last addressable register list
+/// nothing, rather than every number the user typed.
+pub fn list_registers(line: &[Token]) -> Vec<String> {
+    if !line.iter().any(|token| token.token_type == TokenType::RangeOp) {
+        return line
+            .iter()
+            .filter(|token| token.token_type == TokenType::Register)
+            .map(|token| token.content.clone())
+            .collect();
+    }
+
+    let (first, last) = match register_range(line) {
+        Some(range) => range,
+        None => return Vec::new(),
+    };
+
+    match (register_number(first), register_number(last)) {
+        (Some(start), Some(end)) if start <= end && end < MAX_REGISTERS => {
+            (start..=end).map(|number| format!("{}{}", &first.content[..1], number)).collect()
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Finds the first and last register of a `{vA .. vB}` range, when both are
+/// of the same kind.
+pub fn register_range(line: &[Token]) -> Option<(&Token, &Token)> {
+    if !line.iter().any(|token| token.token_type == TokenType::RangeOp) {
+        return None;
+    }
+
+    let registers = line
+        .iter()
+        .filter(|token| token.token_type == TokenType::Register)
+        .collect::<Vec<_>>();
+
+    match registers.as_slice() {
+        [first, last] if first.content[..1] == last.content[..1] => Some((first, last)),
+        _ => None,
+    }
+}
+
+/// Reads the number of a register, e.g. `3` for `v3`.
+pub fn register_number(register: &Token) -> Option<u32> {
+    register.content[1..].parse().ok()
+}
+
+/// Finds registers numbered in hex such as `v0x1`, which the lexer splits
+/// into the `v0` register and an `x1` error token.
+pub fn hex_registers(tokens: &[Token]) -> Vec<&[Token]> {
+    tokens
+        .windows(2)
+        .filter(|pair| {
+            pair[0].token_type == TokenType::Register
+                && pair[1].token_type == TokenType::Error
+                && pair[1].content.starts_with('x')
+                && pair[0].range.end == pair[1].range.start
+        })
+        .collect()
+}
+
+fn trim_leading_space(tokens: &[Token]) -> &[Token] {
+    match tokens.first() {
+        Some(token) if token.token_type == TokenType::Space => &tokens[1..],
+        _ => tokens,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{list_registers, register_accesses, Access, RegisterTracker, RegisterType};
+    use crate::server::{helper::split_lines, lexer::lex_str};
+
+    fn track(content: &str) -> RegisterTracker {
+        let mut tracker = RegisterTracker::default();
+        for line in split_lines(lex_str(content)) {
+            tracker.update(&line);
+        }
+
+        tracker
+    }
+
+    #[test]
+    fn new_instance_until_init() {
+        let tracker = track("new-instance v0, Ljava/lang/StringBuilder;");
+        assert_eq!(
+            tracker.get("v0"),
+            Some(&RegisterType::Uninitialized("Ljava/lang/StringBuilder;".to_string()))
+        );
+
+        let tracker = track(
+            "new-instance v0, Ljava/lang/StringBuilder;\ninvoke-direct {v0}, Ljava/lang/StringBuilder;-><init>()V",
+        );
+        assert_eq!(
+            tracker.get("v0"),
+            Some(&RegisterType::Reference("Ljava/lang/StringBuilder;".to_string()))
+        );
+
+        let tracker = track("new-instance v0, Lfoo;\nnew-instance v1, Lfoo;\ninvoke-direct {v1}, Lfoo;-><init>()V");
+        assert_eq!(tracker.get("v0"), Some(&RegisterType::Uninitialized("Lfoo;".to_string())));
+        assert_eq!(tracker.get("v1"), Some(&RegisterType::Reference("Lfoo;".to_string())));
+    }
+
+    #[test]
+    fn move_result() {
+        let tracker = track("invoke-static {}, Lfoo;->bar()J\nmove-result-wide v2\nmove v3, v2");
+        assert_eq!(tracker.get("v2"), Some(&RegisterType::Primitive("J".to_string())));
+        assert_eq!(tracker.get("v3"), Some(&RegisterType::Primitive("J".to_string())));
+    }
+
+    #[test]
+    fn wide_values() {
+        let tracker = track("const/4 v1, 0x1\nconst-wide v0, 0x1");
+        assert_eq!(tracker.get("v0"), Some(&RegisterType::Primitive("J".to_string())));
+        assert_eq!(tracker.get("v1"), None);
+    }
+
+    #[test]
+    fn constants() {
+        let tracker = track("const/4 v0, 0x0\nconst/4 v1, 0x1\nconst-string v2, \"a\"\ncheck-cast v0, Lfoo;");
+        assert_eq!(tracker.get("v0"), Some(&RegisterType::Reference("Lfoo;".to_string())));
+        assert_eq!(tracker.get("v1"), Some(&RegisterType::Primitive("I".to_string())));
+        assert_eq!(tracker.get("v2"), Some(&RegisterType::Reference("Ljava/lang/String;".to_string())));
+
+        let tracker = track("const/4 v0, 0x00\nconst/16 v1, -0x0\nconst/16 v2, 0x10");
+        assert_eq!(tracker.get("v0"), Some(&RegisterType::Null));
+        assert_eq!(tracker.get("v1"), Some(&RegisterType::Null));
+        assert_eq!(tracker.get("v2"), Some(&RegisterType::Primitive("I".to_string())));
+    }
+
+    #[test]
+    fn array_element() {
+        let tracker =
+            track("invoke-static {}, Lfoo;->bar()[Ljava/lang/String;\nmove-result-object v1\naget-object v0, v1, v2");
+        assert_eq!(tracker.get("v0"), Some(&RegisterType::Reference("Ljava/lang/String;".to_string())));
+    }
+
+    #[test]
+    fn filled_new_array() {
+        let tracker = track("filled-new-array {v0, v1}, [I\nmove-result-object v2");
+        assert_eq!(tracker.get("v2"), Some(&RegisterType::Reference("[I".to_string())));
+    }
+
+    #[test]
+    fn type_operands() {
+        let tracker = track("new-array v0, v1, [Lfoo;\ninstance-of v1, v0, [Lfoo;");
+        assert_eq!(tracker.get("v0"), Some(&RegisterType::Reference("[Lfoo;".to_string())));
+        assert_eq!(tracker.get("v1"), Some(&RegisterType::Primitive("Z".to_string())));
+    }
+
+    #[test]
+    fn arithmetic_results() {
+        let tracker = track("const-string v0, \"a\"\nadd-int/lit8 v0, v1, 0x1\nmul-double/2addr v2, v4");
+        assert_eq!(tracker.get("v0"), Some(&RegisterType::Primitive("I".to_string())));
+        assert_eq!(tracker.get("v2"), Some(&RegisterType::Primitive("D".to_string())));
+    }
+
+    #[test]
+    fn accesses() {
+        let accesses = |line: &str| {
+            register_accesses(&lex_str(line))
+                .into_iter()
+                .map(|(token, access)| (token.content.clone(), access))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(accesses("move v0, v1"), vec![
+            ("v0".to_string(), Access::Write),
+            ("v1".to_string(), Access::Read)
+        ]);
+        assert_eq!(accesses("add-int/2addr v0, v1"), vec![
+            ("v0".to_string(), Access::ReadWrite),
+            ("v1".to_string(), Access::Read)
+        ]);
+        assert_eq!(accesses("iput v0, p0, Lfoo;->bar:I"), vec![
+            ("v0".to_string(), Access::Read),
+            ("p0".to_string(), Access::Read)
+        ]);
+        assert!(accesses(".local v0, \"name\":I").is_empty());
+    }
+
+    #[test]
+    fn register_lists() {
+        let registers = |line: &str| list_registers(&lex_str(line));
+
+        assert_eq!(registers("filled-new-array {v0, p1}, [I"), vec!["v0", "p1"]);
+        assert_eq!(registers("filled-new-array/range {v1 .. v3}, [I"), vec!["v1", "v2", "v3"]);
+        assert!(registers("filled-new-array {}, [I").is_empty());
+        assert!(registers("filled-new-array/range {v3 .. v1}, [I").is_empty());
+        assert!(registers("filled-new-array/range {v0 .. v4000000000}, [I").is_empty());
+        assert!(registers("filled-new-array/range {v0 .. v65535}, [I").is_empty());
+    }
+
+    #[test]
+    fn reset_on_method() {
+        let tracker = track("const/4 v1, 0x1\n.end method\n.method public test()V");
+        assert_eq!(tracker.get("v1"), None);
+    }
+}
@@ -0,0 +1,362 @@
+use lspower::lsp::{Position, Range, TextEdit};
+
+use super::{
+    descriptor::{parameter_register_count, parameter_types, return_type},
+    helper::{method_lines_at, parse_count, split_lines, token_at},
+    lexer::{lex_str, Token, TokenType},
+};
+
+/// A method of the class in the file, with the range of the name under the
+/// cursor.
+struct MethodTarget {
+    name:       String,
+    descriptor: String,
+    range:      Range,
+}
+
+/// The edits renaming a register, with a warning when the new name is past
+/// the registers the method declares.
+#[derive(Debug, PartialEq)]
+pub struct RegisterRename {
+    pub edits:   Vec<TextEdit>,
+    pub warning: Option<String>,
+}
+
+/// Returns the range of the method name or register under `position`, if
+/// it can be renamed.
+pub fn prepare_rename(content: &str, position: Position) -> Option<Range> {
+    let lines = split_lines(lex_str(content));
+
+    method_at(&lines, position)
+        .map(|method| method.range)
+        .or_else(|| register_at(&lines, position).map(|(register, _)| register.range))
+}
+
+/// Renames the register under `position` to `new_name` throughout the
+/// enclosing method.
+///
+/// Fails when the method already uses `new_name`, as the two registers
+/// would be merged into one.
+pub fn rename_register(content: &str, position: Position, new_name: &str) -> Result<Option<RegisterRename>, String> {
+    let lines = split_lines(lex_str(content));
+    let (register, method) = match register_at(&lines, position) {
+        Some(register) => register,
+        None => return Ok(None),
+    };
+
+    let index = match new_name.strip_prefix(|c: char| c == 'v' || c == 'p') {
+        Some(digits) if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) => digits.parse().ok(),
+        _ => None,
+    };
+    let index = index.ok_or_else(|| format!("'{}' isn't a valid register name.", new_name))?;
+
+    let registers = method
+        .iter()
+        .flatten()
+        .filter(|token| token.token_type == TokenType::Register)
+        .collect::<Vec<_>>();
+    if new_name != register.content && registers.iter().any(|token| token.content == new_name) {
+        return Err(format!("'{}' is already used in this method.", new_name));
+    }
+
+    let edits = registers
+        .into_iter()
+        .filter(|token| token.content == register.content)
+        .map(|token| TextEdit {
+            range:    token.range,
+            new_text: new_name.to_string(),
+        })
+        .collect();
+
+    Ok(Some(RegisterRename {
+        edits,
+        warning: register_bound_warning(method, new_name, index),
+    }))
+}
+
+/// Renames the method declared or called under `position` to `new_name`,
+/// along with every call to it from the same file.
+///
+/// Only methods of the class in the file are renamed, as calls from other
+/// files aren't updated yet. Fails when the class already has a method
+/// called `new_name` with the same signature.
+pub fn rename_method(content: &str, position: Position, new_name: &str) -> Result<Option<Vec<TextEdit>>, String> {
+    let lines = split_lines(lex_str(content));
+    let method = match method_at(&lines, position) {
+        Some(method) => method,
+        None => return Ok(None),
+    };
+
+    if method.name.starts_with('<') {
+        return Err(format!("'{}' can't be renamed.", method.name));
+    }
+
+    // The lexer only reads these characters as part of a method name
+    if new_name.is_empty() || !new_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '$') {
+        return Err(format!("'{}' isn't a valid method name.", new_name));
+    }
+
+    let class = declared_class(&lines).unwrap_or_default();
+    let renamed = (new_name.to_string(), method.descriptor.clone());
+    if new_name != method.name && declarations(&lines).contains(&renamed) {
+        return Err(format!("'{}' already declares '{}{}'.", class, new_name, method.descriptor));
+    }
+
+    let target = Some((method.name, method.descriptor));
+    let mut edits = Vec::new();
+
+    for line in &lines {
+        let (token_type, prefix) = match reference_kind(line) {
+            Some(kind) => kind,
+            None => continue,
+        };
+        let idx = match line.iter().position(|token| token.token_type == token_type) {
+            Some(idx) => idx,
+            None => continue,
+        };
+
+        // Calls to methods of other classes are left alone
+        let is_own = token_type == TokenType::MethodName || idx > 0 && line[idx - 1].content == class;
+        if is_own && signature(line, &token_type) == target {
+            edits.push(TextEdit {
+                range:    name_range(&line[idx], prefix),
+                new_text: new_name.to_string(),
+            });
+        }
+    }
+
+    Ok(Some(edits))
+}
+
+/// Finds the method declared or called under `position`, calls only count
+/// when they target a method the class in the file declares.
+fn method_at(lines: &[Vec<Token>], position: Position) -> Option<MethodTarget> {
+    let line = lines.iter().find(|line| line[0].range.start.line == position.line)?;
+    let (token_type, prefix) = reference_kind(line)?;
+    let token = token_at(line, position).filter(|token| token.token_type == token_type)?;
+    let (name, descriptor) = signature(line, &token_type)?;
+
+    if token_type == TokenType::MethodCall {
+        let idx = line.iter().position(|call| call.range == token.range)?;
+        let owner = &line[idx.checked_sub(1)?];
+
+        let is_declared = declarations(lines).contains(&(name.clone(), descriptor.clone()));
+        if Some(&owner.content) != declared_class(lines).as_ref() || !is_declared {
+            return None;
+        }
+    }
+
+    Some(MethodTarget {
+        name,
+        descriptor,
+        range: name_range(token, prefix),
+    })
+}
+
+/// Finds the register under `position`, alongside the lines of the method
+/// it is used in.
+fn register_at(lines: &[Vec<Token>], position: Position) -> Option<(&Token, &[Vec<Token>])> {
+    let method = method_lines_at(lines, position.line)?;
+    let line = method.iter().find(|line| line[0].range.start.line == position.line)?;
+    let register = token_at(line, position).filter(|token| token.token_type == TokenType::Register)?;
+
+    Some((register, method))
+}
+
+/// Describes how register `name`, numbered `index`, is past the registers
+/// `method` declares, `None` when it is within them or they're unknown.
+fn register_bound_warning(method: &[Vec<Token>], name: &str, index: u32) -> Option<String> {
+    let parameters = parameter_register_count(&method[0])?;
+    let line = method[1..].iter().find(|line| {
+        line[0].token_type == TokenType::Directive && matches!(line[0].content.as_str(), ".locals" | ".registers")
+    })?;
+    let number = line.iter().find(|token| token.token_type == TokenType::Number)?;
+    let count = parse_count(&number.content)?;
+
+    if name.starts_with('p') && index >= parameters {
+        Some(format!("'{}' is out of range, the method has {} parameter registers.", name, parameters))
+    } else if name.starts_with('v') && index >= count {
+        Some(format!("'{}' is out of range under '{} {}'.", name, line[0].content, count))
+    } else {
+        None
+    }
+}
+
+/// The token naming the method in a `.method` or invoke `line`, alongside
+/// the length of the `->` before the name.
+fn reference_kind(line: &[Token]) -> Option<(TokenType, u32)> {
+    if line[0].token_type == TokenType::Method && line[0].content == ".method" {
+        Some((TokenType::MethodName, 0))
+    } else if line[0].token_type == TokenType::Invoke {
+        Some((TokenType::MethodCall, 2))
+    } else {
+        None
+    }
+}
+
+/// The name and descriptor of the method declared or referenced in `line`
+/// by its `token_type` token.
+fn signature(line: &[Token], token_type: &TokenType) -> Option<(String, String)> {
+    let name = line.iter().find(|token| token.token_type == *token_type)?;
+    let descriptor = format!("({}){}", parameter_types(line)?.concat(), return_type(line)?);
+
+    Some((name.content.trim_start_matches("->").trim_end_matches('(').to_string(), descriptor))
+}
+
+/// The name and descriptor of every method declared in the file.
+fn declarations(lines: &[Vec<Token>]) -> Vec<(String, String)> {
+    lines
+        .iter()
+        .filter(|line| line[0].token_type == TokenType::Method && line[0].content == ".method")
+        .filter_map(|line| signature(line, &TokenType::MethodName))
+        .collect()
+}
+
+fn declared_class(lines: &[Vec<Token>]) -> Option<String> {
+    lines
+        .iter()
+        .find(|line| line[0].content == ".class")?
+        .iter()
+        .find(|token| token.token_type == TokenType::Class)
+        .map(|class| class.content.clone())
+}
+
+/// The range of the name in a `MethodName` or `MethodCall` token, without
+/// the `->` `prefix` or the trailing `(`.
+fn name_range(token: &Token, prefix: u32) -> Range {
+    Range::new(
+        Position::new(token.range.start.line, token.range.start.character + prefix),
+        Position::new(token.range.end.line, token.range.end.character - 1),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range};
+
+    use super::{prepare_rename, rename_method, rename_register};
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.method private helper(I)V
+    .locals 0
+    return-void
+.end method
+
+.method private helper()V
+    .locals 0
+    return-void
+.end method
+
+.method public run()V
+    .locals 1
+    const/4 v0, 0x1
+    invoke-direct {p0, v0}, Lme/l3af/Test;->helper(I)V
+    invoke-direct {p0}, Lme/l3af/Test;->helper()V
+    invoke-virtual {p0, v0}, Lme/l3af/Other;->helper(I)V
+    invoke-direct {p0, v0}, Lme/l3af/Test;->helper(I)V
+    return-void
+.end method
+";
+
+    fn renamed_ranges(position: Position, new_name: &str) -> Vec<Range> {
+        rename_method(CONTENT, position, new_name)
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .inspect(|edit| assert_eq!(edit.new_text, new_name))
+            .map(|edit| edit.range)
+            .collect()
+    }
+
+    #[test]
+    fn rename_declaration_and_calls() {
+        let expected = vec![
+            Range::new(Position::new(3, 16), Position::new(3, 22)),
+            Range::new(Position::new(16, 44), Position::new(16, 50)),
+            Range::new(Position::new(19, 44), Position::new(19, 50)),
+        ];
+
+        assert_eq!(renamed_ranges(Position::new(3, 17), "update"), expected);
+        assert_eq!(renamed_ranges(Position::new(19, 46), "update"), expected);
+    }
+
+    #[test]
+    fn prepare_name_range() {
+        assert_eq!(
+            prepare_rename(CONTENT, Position::new(16, 46)),
+            Some(Range::new(Position::new(16, 44), Position::new(16, 50)))
+        );
+        assert_eq!(prepare_rename(CONTENT, Position::new(18, 47)), None);
+        assert_eq!(prepare_rename(CONTENT, Position::new(15, 6)), None);
+    }
+
+    #[test]
+    fn rejected_names() {
+        assert_eq!(
+            rename_method(CONTENT, Position::new(12, 17), "helper"),
+            Err("'Lme/l3af/Test;' already declares 'helper()V'.".to_string())
+        );
+        assert_eq!(
+            rename_method(CONTENT, Position::new(3, 17), "do_update"),
+            Err("'do_update' isn't a valid method name.".to_string())
+        );
+        assert_eq!(rename_method(CONTENT, Position::new(18, 47), "update"), Ok(None));
+    }
+
+    const REGISTERS: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.method public static run(I)V
+    .locals 5
+    const/4 v2, 0x1
+    add-int v1, v2, p0
+    invoke-static {v2, v1}, Lme/l3af/Test;->helper(II)V
+    return-void
+.end method
+
+.method public static other()V
+    .locals 3
+    const/4 v2, 0x0
+    return-void
+.end method
+";
+
+    #[test]
+    fn rename_register_in_method() {
+        let rename = rename_register(REGISTERS, Position::new(6, 16), "v4").unwrap().unwrap();
+
+        assert_eq!(
+            rename.edits.iter().map(|edit| edit.range).collect::<Vec<_>>(),
+            vec![
+                Range::new(Position::new(5, 12), Position::new(5, 14)),
+                Range::new(Position::new(6, 16), Position::new(6, 18)),
+                Range::new(Position::new(7, 19), Position::new(7, 21)),
+            ]
+        );
+        assert!(rename.edits.iter().all(|edit| edit.new_text == "v4"));
+        assert_eq!(rename.warning, None);
+        assert_eq!(
+            prepare_rename(REGISTERS, Position::new(5, 13)),
+            Some(Range::new(Position::new(5, 12), Position::new(5, 14)))
+        );
+    }
+
+    #[test]
+    fn rejected_register_names() {
+        assert_eq!(
+            rename_register(REGISTERS, Position::new(5, 13), "v1"),
+            Err("'v1' is already used in this method.".to_string())
+        );
+        assert_eq!(
+            rename_register(REGISTERS, Position::new(5, 13), "x2"),
+            Err("'x2' isn't a valid register name.".to_string())
+        );
+        assert_eq!(
+            rename_register(REGISTERS, Position::new(5, 13), "v5").unwrap().unwrap().warning,
+            Some("'v5' is out of range under '.locals 5'.".to_string())
+        );
+        assert_eq!(rename_register(REGISTERS, Position::new(5, 6), "v4"), Ok(None));
+    }
+}
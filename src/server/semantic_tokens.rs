@@ -0,0 +1,210 @@
+use lspower::lsp::{SemanticToken, SemanticTokenType, SemanticTokensEdit, SemanticTokensLegend};
+
+use super::lexer::{lex_str, TokenType};
+
+const LABEL: SemanticTokenType = SemanticTokenType::new("label");
+
+/// Token types in the order their indices are reported to the client.
+const TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::MODIFIER,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::STRING,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::COMMENT,
+    LABEL,
+];
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types:     TOKEN_TYPES.to_vec(),
+        token_modifiers: Vec::new(),
+    }
+}
+
+fn token_type_index(token_type: &TokenType) -> Option<u32> {
+    let semantic_type = match token_type {
+        TokenType::Directive
+        | TokenType::Method
+        | TokenType::Field
+        | TokenType::Param
+        | TokenType::Annotation
+        | TokenType::SwitchPayload
+        | TokenType::Catch => SemanticTokenType::KEYWORD,
+        TokenType::Visibility | TokenType::Modifier => SemanticTokenType::MODIFIER,
+        TokenType::Class | TokenType::BuiltinType => SemanticTokenType::TYPE,
+        TokenType::Register => SemanticTokenType::VARIABLE,
+        TokenType::MethodCall | TokenType::MethodName => SemanticTokenType::METHOD,
+        TokenType::FieldAccess | TokenType::FieldName => SemanticTokenType::PROPERTY,
+        TokenType::String => SemanticTokenType::STRING,
+        TokenType::Number => SemanticTokenType::NUMBER,
+        TokenType::Comment => SemanticTokenType::COMMENT,
+        TokenType::Label => LABEL,
+        token_type if token_type.is_opcode() => SemanticTokenType::FUNCTION,
+        _ => return None,
+    };
+
+    TOKEN_TYPES.iter().position(|known| *known == semantic_type).map(|idx| idx as u32)
+}
+
+/// Encodes the tokens of `content` relative to each other as the protocol
+/// expects. Tokens spanning several lines, such as unterminated strings, are
+/// left out.
+pub fn semantic_tokens(content: &str) -> Vec<SemanticToken> {
+    let mut tokens = Vec::new();
+    let mut previous_line = 0;
+    let mut previous_start = 0;
+
+    for token in lex_str(content) {
+        let range = token.range;
+        let token_type = match token_type_index(&token.token_type) {
+            Some(token_type) if range.start.line == range.end.line => token_type,
+            _ => continue,
+        };
+
+        let delta_line = range.start.line - previous_line;
+        let delta_start = if delta_line == 0 {
+            range.start.character - previous_start
+        } else {
+            range.start.character
+        };
+
+        tokens.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: range.end.character - range.start.character,
+            token_type,
+            token_modifiers_bitset: 0,
+        });
+
+        previous_line = range.start.line;
+        previous_start = range.start.character;
+    }
+
+    tokens
+}
+
+/// Computes the edit turning `previous` into `current`, replacing only the
+/// tokens between their common prefix and suffix.
+///
+/// Edit offsets count integers in the encoded array, five per token.
+pub fn semantic_tokens_delta(previous: &[SemanticToken], current: &[SemanticToken]) -> Vec<SemanticTokensEdit> {
+    let prefix = previous
+        .iter()
+        .zip(current.iter())
+        .take_while(|(previous, current)| previous == current)
+        .count();
+
+    if prefix == previous.len() && prefix == current.len() {
+        return Vec::new();
+    }
+
+    let suffix = previous[prefix..]
+        .iter()
+        .rev()
+        .zip(current[prefix..].iter().rev())
+        .take_while(|(previous, current)| previous == current)
+        .count();
+
+    let replaced = &current[prefix..current.len() - suffix];
+
+    vec![SemanticTokensEdit {
+        start:        (prefix * 5) as u32,
+        delete_count: ((previous.len() - prefix - suffix) * 5) as u32,
+        data:         if replaced.is_empty() { None } else { Some(replaced.to_vec()) },
+    }]
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{SemanticToken, SemanticTokensEdit};
+
+    use super::{semantic_tokens, semantic_tokens_delta};
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.method public static test()V
+    .locals 1
+
+    const/4 v0, 0x1
+
+    return-void
+.end method
+";
+
+    #[test]
+    fn encode_relative() {
+        let tokens = semantic_tokens(".class public LTest;\n    const/4 v0, 0x1");
+
+        assert_eq!(tokens, vec![
+            SemanticToken {
+                delta_line:             0,
+                delta_start:            0,
+                length:                 6,
+                token_type:             0,
+                token_modifiers_bitset: 0,
+            },
+            SemanticToken {
+                delta_line:             0,
+                delta_start:            7,
+                length:                 6,
+                token_type:             2,
+                token_modifiers_bitset: 0,
+            },
+            SemanticToken {
+                delta_line:             0,
+                delta_start:            7,
+                length:                 6,
+                token_type:             3,
+                token_modifiers_bitset: 0,
+            },
+            SemanticToken {
+                delta_line:             1,
+                delta_start:            4,
+                length:                 7,
+                token_type:             1,
+                token_modifiers_bitset: 0,
+            },
+            SemanticToken {
+                delta_line:             0,
+                delta_start:            8,
+                length:                 2,
+                token_type:             4,
+                token_modifiers_bitset: 0,
+            },
+            SemanticToken {
+                delta_line:             0,
+                delta_start:            4,
+                length:                 3,
+                token_type:             8,
+                token_modifiers_bitset: 0,
+            },
+        ]);
+    }
+
+    #[test]
+    fn unchanged_delta() {
+        let tokens = semantic_tokens(CONTENT);
+        assert!(semantic_tokens_delta(&tokens, &tokens).is_empty());
+    }
+
+    #[test]
+    fn small_edit_delta() {
+        let previous = semantic_tokens(CONTENT);
+        let current = semantic_tokens(&CONTENT.replace("const/4 v0, 0x1", "const/4 v0, 0x12"));
+
+        let edits = semantic_tokens_delta(&previous, &current);
+        let changed = current.iter().position(|token| token.length == 4).unwrap();
+
+        assert_eq!(edits, vec![SemanticTokensEdit {
+            start:        (changed * 5) as u32,
+            delete_count: 5,
+            data:         Some(vec![current[changed]]),
+        }]);
+    }
+}
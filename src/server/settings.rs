@@ -0,0 +1,286 @@
+use lspower::lsp::DiagnosticSeverity;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::log::LogLevel;
+
+/// The section of the client settings holding [`Settings`].
+pub const SECTION: &str = "smali-lsp";
+
+/// User configuration, read from the `smali-lsp` section of the client
+/// settings.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// Show the Java name of every class descriptor as an inlay hint.
+    pub decode_class_names:        bool,
+    /// Identify fields by name and type, so same-named fields of different
+    /// types are only reported as information.
+    pub strict_field_identity:     bool,
+    /// Report `-` in class descriptors as an error, it isn't reported
+    /// otherwise as D8 uses it in the names of synthetic classes.
+    pub strict_descriptors:        bool,
+    /// Report interface methods that are neither abstract nor static, for
+    /// code targeting Android versions without default and private
+    /// interface methods (before API 24).
+    pub strict_interface_methods:  bool,
+    /// Index every `.smali` file in the workspace for cross-file features.
+    pub index_workspace:           bool,
+    /// Cache the workspace index in `.smali-lsp-cache.json`, so unchanged
+    /// files aren't indexed again on the next start.
+    pub cache_index:               bool,
+    /// Report `check-cast` of registers holding primitives. Register types
+    /// are tracked approximately, so this can be turned off when it misfires.
+    pub check_primitive_casts:     bool,
+    /// Check annotation element values against the element types declared
+    /// by indexed annotation classes.
+    pub check_annotation_values:   bool,
+    /// Show color swatches for integer constants that look like ARGB colors.
+    pub show_colors:               bool,
+    /// Collapse the whitespace between instruction operands to a single
+    /// space when formatting.
+    pub normalize_operand_spacing: bool,
+    /// Report every warning as an error, for gating builds on a clean run.
+    pub treat_warnings_as_errors:  bool,
+    /// Report spaces at the end of a line.
+    pub lint_trailing_whitespace:  bool,
+    /// Report files that don't end with exactly one newline.
+    pub lint_final_newline:        bool,
+    /// Report more than one blank line in a row.
+    pub lint_blank_lines:          bool,
+    /// Report lines longer than this many characters, unless a string
+    /// literal crosses the limit.
+    pub max_line_length:           Option<u32>,
+    /// Report instance calls and field accesses on registers most recently
+    /// set to `null`.
+    pub lint_null_dereference:     bool,
+    /// Report malformed specifiers in strings passed straight to `format`
+    /// or `printf`.
+    pub lint_format_strings:       bool,
+    /// Report moves from a register to itself, which have no effect.
+    pub lint_redundant_moves:      bool,
+    /// Report branches leaving a try block or entering one past its start.
+    pub lint_try_branches:         bool,
+    /// Report classes referenced by instructions that aren't declared in the
+    /// workspace, other than those in `external_packages`.
+    pub lint_unresolved_classes:   bool,
+    /// Descriptor prefixes of packages that come from outside the workspace,
+    /// such as the Android framework.
+    pub external_packages:         Vec<String>,
+    /// Report methods with more instructions than this, to spot them before
+    /// they near the 64K code unit limit of a method.
+    pub max_method_instructions:   Option<u32>,
+    /// Severity overrides for whole categories of diagnostics.
+    pub categories:                CategorySeverities,
+    /// The least severe messages written to the client's output channel.
+    pub log_level:                 LogLevel,
+    /// Gitignore-style patterns for files that are neither validated nor
+    /// indexed, such as decompiled framework classes.
+    pub ignore:                    Vec<String>,
+    /// Revalidate only the edited method when a change stays inside one
+    /// method body, keeping the diagnostics of the rest of the file.
+    pub incremental_diagnostics:   bool,
+    /// The line ending every line should use, enforced by a style lint and
+    /// by formatting.
+    pub line_ending:               LineEnding,
+}
+
+/// The severity every diagnostic in a category is reported at, unset
+/// categories keep the severity of each diagnostic.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CategorySeverities {
+    /// Errors and warnings about code that is invalid or likely wrong.
+    pub correctness: Option<CategorySeverity>,
+    /// Formatting lints, such as trailing whitespace.
+    pub style:       Option<CategorySeverity>,
+    /// Informational notes, along with the hints accompanying them.
+    pub info:        Option<CategorySeverity>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CategorySeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+    /// Don't report the category at all.
+    Off,
+}
+
+impl CategorySeverity {
+    /// The LSP severity to report at, `None` when the category is off.
+    pub fn to_lsp(self) -> Option<DiagnosticSeverity> {
+        match self {
+            CategorySeverity::Error => Some(DiagnosticSeverity::Error),
+            CategorySeverity::Warning => Some(DiagnosticSeverity::Warning),
+            CategorySeverity::Information => Some(DiagnosticSeverity::Information),
+            CategorySeverity::Hint => Some(DiagnosticSeverity::Hint),
+            CategorySeverity::Off => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    /// Whichever ending the first line of the file uses.
+    Auto,
+}
+
+impl LineEnding {
+    /// The characters ending each line, `None` for `auto` as it depends on
+    /// the file.
+    pub fn as_str(self) -> Option<&'static str> {
+        match self {
+            LineEnding::Lf => Some("\n"),
+            LineEnding::Crlf => Some("\r\n"),
+            LineEnding::Auto => None,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            decode_class_names:        false,
+            strict_field_identity:     false,
+            strict_descriptors:        false,
+            strict_interface_methods:  false,
+            index_workspace:           true,
+            cache_index:               false,
+            check_primitive_casts:     true,
+            check_annotation_values:   false,
+            show_colors:               false,
+            normalize_operand_spacing: true,
+            treat_warnings_as_errors:  false,
+            lint_trailing_whitespace:  false,
+            lint_final_newline:        false,
+            lint_blank_lines:          false,
+            max_line_length:           None,
+            lint_null_dereference:     true,
+            lint_format_strings:       false,
+            lint_redundant_moves:      true,
+            lint_try_branches:         false,
+            lint_unresolved_classes:   false,
+            external_packages:         ["Landroid/", "Ljava/", "Ljavax/", "Lkotlin/"]
+                .iter()
+                .map(|package| package.to_string())
+                .collect(),
+            max_method_instructions:   None,
+            categories:                CategorySeverities::default(),
+            log_level:                 LogLevel::Info,
+            ignore:                    Vec::new(),
+            incremental_diagnostics:   false,
+            line_ending:               LineEnding::Auto,
+        }
+    }
+}
+
+impl Settings {
+    /// Parses settings from a JSON value, falling back to the defaults for
+    /// anything missing or malformed.
+    pub fn from_value(value: Option<Value>) -> Self {
+        Self::parse(value).0
+    }
+
+    /// Parses settings from a JSON value one key at a time, so a malformed
+    /// key only falls back to its own default. The rejected keys are
+    /// returned along with the settings.
+    pub fn parse(value: Option<Value>) -> (Self, Vec<String>) {
+        let entries = match value {
+            Some(Value::Object(entries)) => entries,
+            _ => return (Self::default(), Vec::new()),
+        };
+
+        let mut accepted = Map::new();
+        let mut rejected = Vec::new();
+        for (key, value) in entries {
+            accepted.insert(key.clone(), value);
+
+            if serde_json::from_value::<Self>(Value::Object(accepted.clone())).is_err() {
+                accepted.remove(&key);
+                rejected.push(key);
+            }
+        }
+
+        (serde_json::from_value(Value::Object(accepted)).unwrap_or_default(), rejected)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{CategorySeverities, CategorySeverity, LineEnding, Settings};
+    use crate::server::log::LogLevel;
+
+    #[test]
+    fn parse_settings() {
+        assert_eq!(Settings::from_value(None), Settings::default());
+        assert!(Settings::from_value(Some(json!({ "decodeClassNames": true }))).decode_class_names);
+        assert!(!Settings::from_value(Some(json!({ "decodeClassNames": "yes" }))).decode_class_names);
+        assert!(Settings::from_value(Some(json!({ "strictFieldIdentity": true }))).strict_field_identity);
+        assert!(Settings::from_value(Some(json!({ "strictDescriptors": true }))).strict_descriptors);
+        assert!(Settings::from_value(Some(json!({ "strictInterfaceMethods": true }))).strict_interface_methods);
+        assert!(Settings::from_value(Some(json!({}))).index_workspace);
+        assert!(!Settings::from_value(Some(json!({ "indexWorkspace": false }))).index_workspace);
+        assert!(Settings::from_value(Some(json!({ "cacheIndex": true }))).cache_index);
+        assert!(!Settings::from_value(Some(json!({ "checkPrimitiveCasts": false }))).check_primitive_casts);
+        assert!(Settings::from_value(Some(json!({ "checkAnnotationValues": true }))).check_annotation_values);
+        assert!(Settings::from_value(Some(json!({ "showColors": true }))).show_colors);
+        assert!(!Settings::from_value(Some(json!({ "normalizeOperandSpacing": false }))).normalize_operand_spacing);
+        assert!(Settings::from_value(Some(json!({ "treatWarningsAsErrors": true }))).treat_warnings_as_errors);
+        assert!(Settings::from_value(Some(json!({ "lintTrailingWhitespace": true }))).lint_trailing_whitespace);
+        assert!(Settings::from_value(Some(json!({ "lintFinalNewline": true }))).lint_final_newline);
+        assert!(Settings::from_value(Some(json!({ "lintBlankLines": true }))).lint_blank_lines);
+        assert_eq!(Settings::from_value(Some(json!({ "maxLineLength": 100 }))).max_line_length, Some(100));
+        assert!(!Settings::from_value(Some(json!({ "lintNullDereference": false }))).lint_null_dereference);
+        assert!(Settings::from_value(Some(json!({ "lintFormatStrings": true }))).lint_format_strings);
+        assert!(!Settings::from_value(Some(json!({ "lintRedundantMoves": false }))).lint_redundant_moves);
+        assert!(Settings::from_value(Some(json!({ "lintTryBranches": true }))).lint_try_branches);
+        assert!(Settings::from_value(Some(json!({ "lintUnresolvedClasses": true }))).lint_unresolved_classes);
+        assert_eq!(
+            Settings::from_value(Some(json!({ "externalPackages": ["Lcom/google/"] }))).external_packages,
+            vec!["Lcom/google/".to_string()]
+        );
+        assert_eq!(
+            Settings::from_value(Some(json!({ "maxMethodInstructions": 1000 }))).max_method_instructions,
+            Some(1000)
+        );
+        assert_eq!(
+            Settings::from_value(Some(json!({ "categories": { "style": "off", "info": "hint" } }))).categories.style,
+            Some(CategorySeverity::Off)
+        );
+        assert_eq!(Settings::from_value(Some(json!({ "logLevel": "trace" }))).log_level, LogLevel::Trace);
+        assert_eq!(Settings::from_value(Some(json!({ "ignore": ["android/"] }))).ignore, vec!["android/"]);
+        assert!(Settings::from_value(Some(json!({ "incrementalDiagnostics": true }))).incremental_diagnostics);
+        assert_eq!(Settings::from_value(Some(json!({ "lineEnding": "crlf" }))).line_ending, LineEnding::Crlf);
+    }
+
+    #[test]
+    fn malformed_keys() {
+        let (settings, rejected) = Settings::parse(Some(json!({
+            "decodeClassNames": "yes",
+            "ignore": ["android/"],
+            "treatWarningsAsErrors": true,
+            "categories": { "style": "off" },
+            "maxLineLength": -1,
+        })));
+
+        // Only the malformed keys fall back to their defaults
+        assert_eq!(rejected, vec!["decodeClassNames", "maxLineLength"]);
+        assert_eq!(settings, Settings {
+            ignore: vec!["android/".to_string()],
+            treat_warnings_as_errors: true,
+            categories: CategorySeverities {
+                style: Some(CategorySeverity::Off),
+                ..CategorySeverities::default()
+            },
+            ..Settings::default()
+        });
+    }
+}
@@ -0,0 +1,194 @@
+use lspower::lsp::{DocumentSymbol, Range, SymbolKind};
+
+use super::{
+    helper::split_lines,
+    lexer::{lex_str, Token, TokenType},
+};
+
+/// Builds the document outline, the class with its fields and methods as
+/// children.
+pub fn document_symbols(content: &str) -> Vec<DocumentSymbol> {
+    let lines = split_lines(lex_str(content));
+
+    let mut class: Option<DocumentSymbol> = None;
+    let mut children = Vec::new();
+    let mut method: Option<(DocumentSymbol, usize)> = None;
+    let mut in_annotation = false;
+
+    for line in &lines {
+        let first = &line[0];
+
+        match (&first.token_type, first.content.as_ref()) {
+            (TokenType::Directive, ".class") => {
+                if let Some(name) = line.iter().find(|token| token.token_type == TokenType::Class) {
+                    class = Some(symbol(name.content.clone(), None, SymbolKind::Class, line, name));
+                }
+            },
+            (TokenType::Field, ".field") => {
+                if let Some(name) = line.iter().find(|token| token.token_type == TokenType::FieldName) {
+                    let name_idx = line.iter().position(|token| token == name).unwrap();
+                    let field_type = line[name_idx + 1..]
+                        .iter()
+                        .take_while(|token| token.token_type != TokenType::Space)
+                        .map(|token| token.content.as_str())
+                        .collect::<String>();
+
+                    children.push(symbol(
+                        name.content.trim_end_matches(':').to_string(),
+                        Some(field_type),
+                        SymbolKind::Field,
+                        line,
+                        name,
+                    ));
+                }
+            },
+            (TokenType::Method, ".method") => {
+                if let Some(name) = line.iter().find(|token| token.token_type == TokenType::MethodName) {
+                    let name_idx = line.iter().position(|token| token == name).unwrap();
+                    let signature = format!(
+                        "({}",
+                        line[name_idx + 1..]
+                            .iter()
+                            .map(|token| token.content.as_str())
+                            .collect::<String>()
+                    );
+                    let kind = if name.content.starts_with('<') {
+                        SymbolKind::Constructor
+                    } else {
+                        SymbolKind::Method
+                    };
+
+                    let name = name.content.trim_end_matches('(').to_string();
+                    method = Some((symbol(name, Some(signature), kind, line, &line[name_idx]), 0));
+                }
+            },
+            (TokenType::Method, ".end method") => {
+                if let Some((mut symbol, count)) = method.take() {
+                    symbol.range.end = line.last().unwrap().range.end;
+                    symbol.detail = symbol.detail.map(|signature| {
+                        format!(
+                            "{} · {} instruction{}",
+                            signature,
+                            count,
+                            if count == 1 { "" } else { "s" }
+                        )
+                    });
+
+                    children.push(symbol);
+                }
+            },
+            (TokenType::Annotation, directive) => {
+                in_annotation = !directive.starts_with(".end");
+            },
+            _ => {
+                if let Some((_, count)) = &mut method {
+                    if !in_annotation && is_instruction_line(first) {
+                        *count += 1;
+                    }
+                }
+            },
+        }
+    }
+
+    match class {
+        Some(mut class) => {
+            if let Some(last) = lines.last() {
+                class.range.end = last.last().unwrap().range.end;
+            }
+            class.children = Some(children);
+
+            vec![class]
+        },
+        None => children,
+    }
+}
+
+/// Whether a line inside a method body is an instruction rather than a
+/// directive, label or comment.
+fn is_instruction_line(first: &Token) -> bool {
+    !(first.token_type == TokenType::Comment || first.content.starts_with('.') || first.content.starts_with(':'))
+}
+
+#[allow(deprecated)]
+fn symbol(name: String, detail: Option<String>, kind: SymbolKind, line: &[Token], name_token: &Token) -> DocumentSymbol {
+    DocumentSymbol {
+        name,
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: Range::new(line[0].range.start, line.last().unwrap().range.end),
+        selection_range: name_token.range,
+        children: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, SymbolKind};
+
+    use super::document_symbols;
+
+    const CONTENT: &str = ".class public Lme/l3af/Test;
+.super Ljava/lang/Object;
+
+.field private count:I
+
+.method public constructor <init>()V
+    .registers 1
+
+    invoke-direct {p0}, Ljava/lang/Object;-><init>()V
+
+    return-void
+.end method
+
+.method public add(I)I
+    .locals 1
+    .param p1, \"amount\"
+    .annotation runtime Lfoo;
+        value = 0x1
+    .end annotation
+    .end param
+
+    # Adds to the count
+    iget v0, p0, Lme/l3af/Test;->count:I
+
+    if-eqz p1, :cond_0
+
+    add-int/2addr v0, p1
+
+    iput v0, p0, Lme/l3af/Test;->count:I
+
+    :cond_0
+    return v0
+.end method
+";
+
+    #[test]
+    fn outline() {
+        let symbols = document_symbols(CONTENT);
+        assert_eq!(symbols.len(), 1);
+
+        let class = &symbols[0];
+        assert_eq!(class.name, "Lme/l3af/Test;");
+        assert_eq!(class.kind, SymbolKind::Class);
+
+        let children = class.children.as_ref().unwrap();
+        let names = children.iter().map(|symbol| symbol.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["count", "<init>", "add"]);
+
+        assert_eq!(children[0].detail.as_deref(), Some("I"));
+        assert_eq!(children[1].kind, SymbolKind::Constructor);
+        assert_eq!(children[2].range.start, Position::new(13, 0));
+        assert_eq!(children[2].range.end, Position::new(32, 11));
+    }
+
+    #[test]
+    fn method_instruction_count() {
+        let symbols = document_symbols(CONTENT);
+        let children = symbols[0].children.as_ref().unwrap();
+
+        assert_eq!(children[1].detail.as_deref(), Some("()V · 2 instructions"));
+        assert_eq!(children[2].detail.as_deref(), Some("(I)I · 5 instructions"));
+    }
+}
@@ -0,0 +1,153 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    blocks::{Block, BlockTracker},
+    helper::tokens_to_diagnostic,
+    lexer::{Token, TokenType},
+};
+
+const VISIBILITIES: &[&str] = &["build", "runtime", "system"];
+
+/// Checks `.annotation` visibilities and that each annotation suits the
+/// element it is declared on.
+#[derive(Debug, Default)]
+pub struct AnnotationValidator {
+    blocks: BlockTracker,
+}
+
+impl Validator for AnnotationValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        self.blocks.update(line);
+
+        if line[0].token_type != TokenType::Annotation || line[0].content != ".annotation" {
+            return diags;
+        }
+
+        let (visibility, name) = match read_visibility(line) {
+            Ok(visibility) => visibility,
+            Err(diag) => return vec![diag],
+        };
+
+        match self.blocks.parent() {
+            Some(Block::Annotation) | Some(Block::SubAnnotation) => {
+                diags.push(tokens_to_diagnostic(
+                    line,
+                    "Annotations can't be declared inside an annotation.\nUse '.subannotation' for nested values.",
+                    Some(DiagnosticSeverity::Error),
+                ));
+            },
+            // System annotations are generated for classes, fields and methods
+            Some(Block::Param) if name == "system" => {
+                diags.push(tokens_to_diagnostic(
+                    &visibility,
+                    "Parameter annotations can't have 'system' visibility.\nUse 'runtime' or 'build' instead.",
+                    Some(DiagnosticSeverity::Error),
+                ));
+            },
+            _ => {},
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+/// Reads the visibility of the `.annotation` in `line`, the keywords aren't
+/// lexed so it may span several tokens.
+fn read_visibility(line: &[Token]) -> Result<(Vec<Token>, String), Diagnostic> {
+    let type_idx = line
+        .iter()
+        .position(|token| matches!(token.token_type, TokenType::Class | TokenType::ArrayOp))
+        .ok_or_else(|| {
+            tokens_to_diagnostic(
+                line,
+                "'.annotation' expects a visibility and a type.\n'.annotation runtime Lclass/Name;'",
+                Some(DiagnosticSeverity::Error),
+            )
+        })?;
+
+    let visibility = line[1..type_idx]
+        .iter()
+        .filter(|token| token.token_type != TokenType::Space)
+        .cloned()
+        .collect::<Vec<_>>();
+    let name = visibility.iter().map(|token| token.content.as_str()).collect::<String>();
+
+    if visibility.is_empty() {
+        return Err(tokens_to_diagnostic(
+            line,
+            "Missing annotation visibility.\nExpected 'build', 'runtime' or 'system'.",
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    if !VISIBILITIES.contains(&name.as_str()) {
+        return Err(tokens_to_diagnostic(
+            &visibility,
+            format!("Invalid annotation visibility '{}'.\nExpected 'build', 'runtime' or 'system'.", name),
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    Ok((visibility, name))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_class;
+
+    #[test]
+    fn runtime_method_annotation() {
+        assert!(validate_class(
+            ".method public test(I)V
+    .locals 0
+    .annotation runtime Ljava/lang/Deprecated;
+    .end annotation
+    .param p1
+        .annotation build Landroidx/annotation/NonNull;
+        .end annotation
+    .end param
+    return-void
+.end method"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn invalid_visibility() {
+        assert_eq!(
+            validate_class(".annotation compile Ljava/lang/Deprecated;\n.end annotation"),
+            vec!["Invalid annotation visibility 'compile'.\nExpected 'build', 'runtime' or 'system'."]
+        );
+        assert_eq!(validate_class(".annotation Ljava/lang/Deprecated;\n.end annotation"), vec![
+            "Missing annotation visibility.\nExpected 'build', 'runtime' or 'system'."
+        ]);
+    }
+
+    #[test]
+    fn misplaced_annotation() {
+        assert_eq!(
+            validate_class(
+                ".method public test(I)V
+    .locals 0
+    .param p1
+        .annotation system Ldalvik/annotation/Signature;
+        .end annotation
+    .end param
+    return-void
+.end method"
+            ),
+            vec!["Parameter annotations can't have 'system' visibility.\nUse 'runtime' or 'build' instead."]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    blocks::{Block, BlockTracker},
+    helper::tokens_to_diagnostic,
+    index::ClassIndex,
+    lexer::{Token, TokenType},
+    validation::ValidationContext,
+};
+
+const NUMERIC_TYPES: &[&str] = &["I", "J", "S", "B", "C", "F", "D"];
+
+/// Checks the values assigned to annotation elements against the return
+/// types of the element methods, when the annotation class is indexed.
+///
+/// Only values whose type is obvious from the first token are checked, such
+/// as strings, numbers, booleans, class literals, enums and arrays.
+#[derive(Debug)]
+pub struct AnnotationElementValidator<'a> {
+    index:   &'a ClassIndex,
+    enabled: bool,
+    blocks:  BlockTracker,
+}
+
+impl<'a> AnnotationElementValidator<'a> {
+    pub fn new(context: &ValidationContext<'a>) -> Self {
+        Self {
+            index:   context.index,
+            enabled: context.settings.check_annotation_values,
+            blocks:  BlockTracker::default(),
+        }
+    }
+}
+
+impl<'a> Validator for AnnotationElementValidator<'a> {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        // A `.subannotation` value opens its own block, the element it is
+        // assigned to belongs to the enclosing one
+        let annotation = match self.blocks.current() {
+            Some(Block::Annotation) | Some(Block::SubAnnotation) => self.blocks.annotation_class().map(str::to_owned),
+            _ => None,
+        };
+        self.blocks.update(line);
+
+        let annotation = match annotation {
+            Some(annotation) if self.enabled && line[0].token_type != TokenType::Annotation => annotation,
+            _ => return diags,
+        };
+
+        if let Some(message) = element_mismatch(line, &annotation, self.index) {
+            let value = value_tokens(line);
+            diags.push(tokens_to_diagnostic(&value, message, Some(DiagnosticSeverity::Error)));
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+/// The tokens after the `=` of an element assignment, up to any comment.
+fn value_tokens(line: &[Token]) -> Vec<Token> {
+    // Element names aren't lexed, so the `=` may share a token with them
+    let start = match line.iter().position(|token| token.content.contains('=')) {
+        Some(idx) => idx + 1,
+        None => return Vec::new(),
+    };
+
+    line[start..]
+        .iter()
+        .take_while(|token| token.token_type != TokenType::Comment)
+        .skip_while(|token| token.token_type == TokenType::Space)
+        .cloned()
+        .collect::<Vec<_>>()
+}
+
+/// Describes how the value assigned in `line` doesn't fit the type of the
+/// element of `annotation` it is assigned to, `None` when it fits or either
+/// type is unknown.
+fn element_mismatch(line: &[Token], annotation: &str, index: &ClassIndex) -> Option<String> {
+    let text = line.iter().map(|token| token.content.as_str()).collect::<String>();
+    let name = text.split('=').next()?.trim();
+
+    let element = index
+        .get(annotation)?
+        .methods
+        .iter()
+        .find(|method| method.name == name && method.descriptor.starts_with("()"))?;
+    let declared = &element.descriptor[2..];
+
+    let value = value_tokens(line);
+    let first = value.first()?;
+    let (kind, fits) = match (&first.token_type, first.content.as_str()) {
+        (TokenType::String, _) => ("a string".to_string(), declared == "Ljava/lang/String;"),
+        (TokenType::Number, _) => ("a number".to_string(), NUMERIC_TYPES.contains(&declared)),
+        (TokenType::Class, _) => ("a class".to_string(), declared == "Ljava/lang/Class;"),
+        (TokenType::Brace, "{") => ("an array".to_string(), declared.starts_with('[')),
+        (TokenType::Directive, ".enum") => {
+            let owner = value.iter().find(|token| token.token_type == TokenType::Class)?;
+            (format!("a '{}' value", owner.content), declared == owner.content)
+        },
+        _ => match value.iter().map(|token| token.content.as_str()).collect::<String>().trim_end() {
+            "true" | "false" => ("a boolean".to_string(), declared == "Z"),
+            _ => return None,
+        },
+    };
+
+    if fits {
+        return None;
+    }
+
+    let shown = value.iter().map(|token| token.content.as_str()).collect::<String>();
+    Some(format!("'{}' is {}, but element '{}' is of type '{}'.", shown.trim_end(), kind, name, declared))
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::Url;
+
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::fixture::{messages, validate_class_with},
+    };
+
+    const ANNOTATION: &str = ".class public interface abstract annotation Lme/l3af/Ann;
+.super Ljava/lang/Object;
+.implements Ljava/lang/annotation/Annotation;
+
+.method public abstract value()Ljava/lang/String;
+.end method
+
+.method public abstract count()I
+.end method
+";
+
+    fn validate_elements(elements: &str, enabled: bool) -> Vec<String> {
+        let mut index = ClassIndex::default();
+        index.index_content(&Url::parse("file:///Ann.smali").unwrap(), ANNOTATION);
+
+        let settings = Settings {
+            check_annotation_values: enabled,
+            ..Settings::default()
+        };
+        let annotation = format!(".annotation runtime Lme/l3af/Ann;\n{}\n.end annotation", elements);
+
+        messages(validate_class_with(&annotation, &settings, &index))
+    }
+
+    #[test]
+    fn matching_elements() {
+        assert!(validate_elements("    value = \"name\"\n    count = 0x5", true).is_empty());
+    }
+
+    #[test]
+    fn mismatched_element() {
+        assert_eq!(validate_elements("    value = 0x5", true), vec![
+            "'0x5' is a number, but element 'value' is of type 'Ljava/lang/String;'."
+        ]);
+        assert_eq!(validate_elements("    count = \"name\"", true), vec![
+            "'\"name\"' is a string, but element 'count' is of type 'I'."
+        ]);
+        assert!(validate_elements("    value = 0x5", false).is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    helper::tokens_to_diagnostic,
+    lexer::{Token, TokenType},
+};
+
+/// The most dimensions an array type can have in a dex file.
+const MAX_DIMENSIONS: usize = 255;
+
+/// Checks the number of dimensions of every array type, whether it is part
+/// of a declaration or an instruction operand.
+#[derive(Debug, Default)]
+pub struct ArrayTypeValidator;
+
+impl Validator for ArrayTypeValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        // Each `[` is lexed on its own, so a type is a run of them followed
+        // by the element type
+        let mut idx = 0;
+        while idx < line.len() {
+            let dimensions = line[idx..]
+                .iter()
+                .take_while(|token| token.token_type == TokenType::ArrayOp)
+                .count();
+
+            if dimensions > MAX_DIMENSIONS {
+                let end = (idx + dimensions + 1).min(line.len());
+                diags.push(tokens_to_diagnostic(
+                    &line[idx..end],
+                    format!("Array type has {} dimensions, the maximum is {}.", dimensions, MAX_DIMENSIONS),
+                    Some(DiagnosticSeverity::Error),
+                ));
+            }
+
+            idx += dimensions.max(1);
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_class;
+
+    #[test]
+    fn nested_arrays() {
+        assert!(validate_class(".field public grid:[[I").is_empty());
+        assert!(validate_class(&format!(".field public deep:{}I", "[".repeat(255))).is_empty());
+    }
+
+    #[test]
+    fn too_many_dimensions() {
+        assert_eq!(validate_class(&format!(".field public deep:{}I", "[".repeat(256))), vec![
+            "Array type has 256 dimensions, the maximum is 255."
+        ]);
+    }
+}
@@ -0,0 +1,135 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    blocks::{Block, BlockTracker},
+    helper::tokens_to_diagnostic,
+    lexer::Token,
+};
+
+/// Checks that each `.end` directive closes the innermost open block.
+#[derive(Debug, Default)]
+pub struct EndDirectiveValidator {
+    blocks:    BlockTracker,
+    /// Blocks closed implicitly by a mismatched `.end`, so their own `.end`
+    /// later on isn't reported a second time.
+    discarded: Vec<Block>,
+}
+
+impl Validator for EndDirectiveValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        let closed = match Block::closed_by(line) {
+            Some(closed) => closed,
+            None => {
+                if self.blocks.is_top_level() {
+                    self.discarded.clear();
+                }
+
+                self.blocks.update(line);
+                return diags;
+            },
+        };
+
+        let blocks = self.blocks.blocks();
+        let opener = self.blocks.opener();
+        self.blocks.update(line);
+
+        if blocks.last() == Some(&closed) {
+            return diags;
+        }
+
+        if !blocks.contains(&closed) {
+            if let Some(idx) = self.discarded.iter().rposition(|block| *block == closed) {
+                self.discarded.remove(idx);
+                return diags;
+            }
+
+            // Stray `.end param`s are reported by the method validator
+            if closed == Block::Param {
+                return diags;
+            }
+        }
+
+        // Closers outside of every block are left to the other validators
+        let (open, opener) = match (blocks.last(), opener) {
+            (Some(open), Some(opener)) => (*open, opener),
+            _ => return diags,
+        };
+
+        // Closing an outer block drops the ones inside it, remember them so
+        // their own `.end` doesn't cascade into more errors
+        if let Some(idx) = blocks.iter().rposition(|block| *block == closed) {
+            self.discarded.extend_from_slice(&blocks[idx + 1..]);
+        }
+
+        diags.push(Diagnostic::new(
+            opener,
+            Some(DiagnosticSeverity::Hint),
+            None,
+            None,
+            format!("'.{}' block opened here.", open.name()),
+            None,
+            None,
+        ));
+        diags.push(tokens_to_diagnostic(
+            line,
+            format!(
+                "'.end {}' does not match open '{}' block.\nClose it with '.end {}' first.",
+                closed.name(),
+                open.name(),
+                open.name()
+            ),
+            Some(DiagnosticSeverity::Error),
+        ));
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_class;
+
+    fn validate_method(body: &str) -> Vec<String> {
+        validate_class(&format!(".method public test()V\n    .locals 0\n{}", body))
+    }
+
+    #[test]
+    fn matching_end_directives() {
+        assert!(validate_method(
+            "    .annotation runtime Ljava/lang/Deprecated;\n    .end annotation\n    return-void\n.end method"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn end_annotation_in_method() {
+        assert_eq!(validate_method("    return-void\n    .end annotation\n.end method"), vec![
+            "'.method' block opened here.",
+            "'.end annotation' does not match open 'method' block.\nClose it with '.end method' first."
+        ]);
+    }
+
+    #[test]
+    fn crossed_end_directives() {
+        assert_eq!(
+            validate_method(
+                "    .annotation runtime Ljava/lang/Deprecated;\n    return-void\n.end method\n    .end annotation"
+            ),
+            vec![
+                "'.annotation' block opened here.",
+                "'.end method' does not match open 'annotation' block.\nClose it with '.end annotation' first."
+            ]
+        );
+    }
+}
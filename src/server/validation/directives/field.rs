@@ -0,0 +1,275 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    descriptor::read_type,
+    helper::tokens_to_diagnostic,
+    index::ClassIndex,
+    lexer::{Token, TokenType},
+    validation::{Category, ValidationContext},
+};
+
+/// How many superclasses to walk up when looking for shadowed fields.
+const MAX_SUPER_DEPTH: usize = 32;
+
+const RESERVED_NAMES: &[&str] = &[
+    "abstract", "assert", "boolean", "break", "byte", "case", "catch", "char", "class", "const", "continue",
+    "default", "do", "double", "else", "enum", "extends", "false", "final", "finally", "float", "for", "goto", "if",
+    "implements", "import", "instanceof", "int", "interface", "long", "native", "new", "null", "package",
+    "private", "protected", "public", "return", "short", "static", "strictfp", "super", "switch", "synchronized",
+    "this", "throw", "throws", "transient", "true", "try", "void", "volatile", "while",
+];
+
+#[derive(Debug)]
+pub struct FieldValidator<'a> {
+    index:       &'a ClassIndex,
+    strict:      bool,
+    super_class: Option<String>,
+    declared:    Vec<FieldDeclaration>,
+}
+
+#[derive(Debug)]
+struct FieldDeclaration {
+    name:       String,
+    field_type: Option<String>,
+    tokens:     Vec<Token>,
+}
+
+impl<'a> FieldValidator<'a> {
+    pub fn new(context: &ValidationContext<'a>) -> Self {
+        Self {
+            index:       context.index,
+            strict:      context.settings.strict_field_identity,
+            super_class: None,
+            declared:    Vec::new(),
+        }
+    }
+}
+
+impl<'a> Validator for FieldValidator<'a> {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        match (&line[0].token_type, line[0].content.as_ref()) {
+            (TokenType::Directive, ".super") => {
+                self.super_class = line
+                    .iter()
+                    .find(|token| token.token_type == TokenType::Class)
+                    .map(|token| token.content.clone());
+            },
+            (TokenType::Field, ".field") => {
+                diags.append(&mut validate_field_name(line, self));
+                diags.append(&mut self.validate_duplicate(line));
+            },
+            _ => {},
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+impl<'a> FieldValidator<'a> {
+    fn validate_duplicate(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        let idx = match line.iter().position(|token| token.token_type == TokenType::FieldName) {
+            Some(idx) => idx,
+            None => return diags,
+        };
+
+        let name = line[idx].content.trim_end_matches(':').to_string();
+        let field_type = read_type(&line[idx + 1..]).map(|(descriptor, _)| descriptor);
+
+        if let Some(first) = self.declared.iter().find(|field| field.name == name) {
+            let (message, severity, category) = if first.field_type == field_type {
+                (format!("Field '{}' already declared.", name), DiagnosticSeverity::Error, Category::Correctness)
+            } else {
+                let (severity, category) = if self.strict {
+                    (DiagnosticSeverity::Information, Category::Info)
+                } else {
+                    (DiagnosticSeverity::Warning, Category::Correctness)
+                };
+
+                (
+                    format!(
+                        "Field '{}' already declared with type '{}'.",
+                        name,
+                        first.field_type.as_deref().unwrap_or("?")
+                    ),
+                    severity,
+                    category,
+                )
+            };
+
+            // The hint pointing at the first declaration goes along with the
+            // diagnostic it belongs to
+            diags.push(category.tag(tokens_to_diagnostic(
+                &first.tokens,
+                "Field declared here.",
+                Some(DiagnosticSeverity::Hint),
+            )));
+            diags.push(category.tag(tokens_to_diagnostic(line, message, Some(severity))));
+        } else {
+            self.declared.push(FieldDeclaration {
+                name,
+                field_type,
+                tokens: line.into(),
+            });
+        }
+
+        diags
+    }
+}
+
+fn validate_field_name(line: &[Token], validator: &FieldValidator) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let token = match line.iter().find(|token| token.token_type == TokenType::FieldName) {
+        Some(token) => token,
+        None => {
+            diags.push(tokens_to_diagnostic(
+                line,
+                "Field name expected.\n'.field visibility name:Type'",
+                Some(DiagnosticSeverity::Error),
+            ));
+
+            return diags;
+        },
+    };
+
+    let name = token.content.trim_end_matches(':');
+
+    if name.starts_with(|chr: char| chr.is_ascii_digit()) {
+        diags.push(token.to_diagnostic(
+            format!("'{}' is not a valid identifier.\nField names cannot start with a digit.", name),
+            Some(DiagnosticSeverity::Error),
+        ));
+    } else if RESERVED_NAMES.contains(&name) {
+        diags.push(token.to_diagnostic(
+            format!("'{}' is a reserved Java keyword.", name),
+            Some(DiagnosticSeverity::Warning),
+        ));
+    }
+
+    let mut super_class = validator.super_class.clone();
+    for _ in 0..MAX_SUPER_DEPTH {
+        let class = match super_class.and_then(|name| validator.index.get(&name)) {
+            Some(class) => class,
+            None => break,
+        };
+
+        if class.field(name).is_some() {
+            diags.push(token.to_diagnostic(
+                format!("Field '{}' shadows a field declared in '{}'.", name, class.name),
+                Some(DiagnosticSeverity::Warning),
+            ));
+            break;
+        }
+
+        super_class = class.super_class.clone();
+    }
+
+    diags
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{DiagnosticSeverity, Url};
+
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::fixture::{messages, severities, validate_class_with, validate_file_with},
+    };
+
+    /// Validates `fields` in a class extending `Lme/l3af/Base;`, so they can
+    /// shadow fields of its superclasses in `index`.
+    fn validate_fields(fields: &str, index: &ClassIndex) -> Vec<String> {
+        let content = format!(".class public Lme/l3af/Test;\n.super Lme/l3af/Base;\n\n{}\n", fields);
+
+        messages(validate_file_with(&content, &Settings::default(), index))
+    }
+
+    #[test]
+    fn valid_identifiers() {
+        let index = ClassIndex::default();
+
+        assert!(validate_fields(".field public count:I", &index).is_empty());
+        assert!(validate_fields(".field public $jacocoData:[Z", &index).is_empty());
+        assert!(validate_fields(".field private _value2:Ljava/lang/String;", &index).is_empty());
+    }
+
+    #[test]
+    fn invalid_identifiers() {
+        let index = ClassIndex::default();
+
+        assert_eq!(validate_fields(".field public 1bad:I", &index), vec![
+            "'1bad' is not a valid identifier.\nField names cannot start with a digit."
+        ]);
+        assert_eq!(validate_fields(".field public class:I", &index), vec![
+            "'class' is a reserved Java keyword."
+        ]);
+        assert_eq!(validate_fields(".field public I", &index), vec![
+            "Field name expected.\n'.field visibility name:Type'"
+        ]);
+    }
+
+    #[test]
+    fn shadowed_field() {
+        let mut index = ClassIndex::default();
+        index.index_content(
+            &Url::parse("file:///Root.smali").unwrap(),
+            ".class public Lme/l3af/Root;\n.super Ljava/lang/Object;\n.field protected count:I\n",
+        );
+        index.index_content(
+            &Url::parse("file:///Base.smali").unwrap(),
+            ".class public Lme/l3af/Base;\n.super Lme/l3af/Root;\n",
+        );
+
+        assert_eq!(validate_fields(".field public count:I", &index), vec![
+            "Field 'count' shadows a field declared in 'Lme/l3af/Root;'."
+        ]);
+        assert!(validate_fields(".field public total:I", &index).is_empty());
+    }
+
+    fn validate_duplicates(fields: &str, strict: bool) -> Vec<(String, Option<DiagnosticSeverity>)> {
+        let settings = Settings {
+            strict_field_identity: strict,
+            ..Settings::default()
+        };
+
+        severities(validate_class_with(fields, &settings, &ClassIndex::default()))
+    }
+
+    #[test]
+    fn duplicate_same_type() {
+        assert_eq!(validate_duplicates(".field public foo:I\n.field public foo:I", false), vec![
+            ("Field declared here.".to_string(), Some(DiagnosticSeverity::Hint)),
+            ("Field 'foo' already declared.".to_string(), Some(DiagnosticSeverity::Error)),
+        ]);
+    }
+
+    #[test]
+    fn duplicate_different_type() {
+        let fields = ".field public foo:I\n.field public foo:Ljava/lang/String;";
+
+        assert_eq!(validate_duplicates(fields, false), vec![
+            ("Field declared here.".to_string(), Some(DiagnosticSeverity::Hint)),
+            ("Field 'foo' already declared with type 'I'.".to_string(), Some(DiagnosticSeverity::Warning)),
+        ]);
+        assert_eq!(validate_duplicates(fields, true)[1].1, Some(DiagnosticSeverity::Information));
+    }
+
+    #[test]
+    fn distinct_names() {
+        assert!(validate_duplicates(".field public foo:I\n.field public bar:I", false).is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    blocks::BlockTracker,
+    helper::tokens_to_diagnostic,
+    lexer::{Token, TokenType},
+};
+
+/// Warns about annotations that follow a `.field` but are separated from it
+/// by another directive, so they attach to the class instead.
+#[derive(Debug, Default)]
+pub struct FieldAnnotationValidator {
+    blocks:    BlockTracker,
+    /// The last field declared at class level, until the next member.
+    field:     Option<Vec<Token>>,
+    /// The first line after `field` that isn't part of it.
+    separator: Option<Vec<Token>>,
+}
+
+impl Validator for FieldAnnotationValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        // Annotations directly below a field open its block and are nested
+        if self.blocks.update(line) > 0 {
+            return diags;
+        }
+
+        match (&line[0].token_type, line[0].content.as_str()) {
+            (TokenType::Field, ".field") => {
+                self.field = Some(line.to_vec());
+                self.separator = None;
+            },
+            (TokenType::Annotation, ".annotation") => {
+                if let (Some(field), Some(separator)) = (self.field.take(), self.separator.take()) {
+                    diags.push(tokens_to_diagnostic(
+                        &field,
+                        "Field declared here.",
+                        Some(DiagnosticSeverity::Hint),
+                    ));
+                    diags.push(tokens_to_diagnostic(
+                        line,
+                        format!(
+                            "Annotation is separated from the field above it by '{}'.\nMove it directly below the \
+                             '.field' line to annotate the field.",
+                            separator[0].content
+                        ),
+                        Some(DiagnosticSeverity::Warning),
+                    ));
+                }
+            },
+            (TokenType::Method, ".method") | (TokenType::Field, ".end field") => self.field = None,
+            _ => {
+                if self.field.is_some() && self.separator.is_none() {
+                    self.separator = Some(line.to_vec());
+                }
+            },
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_class;
+
+    #[test]
+    fn attached_annotation() {
+        assert!(validate_class(
+            ".field public count:I
+    .annotation runtime Ljava/lang/Deprecated;
+    .end annotation
+.end field"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn detached_annotation() {
+        // Both directives are also out of place after a field
+        assert_eq!(
+            validate_class(
+                ".field public count:I
+.source \"Test.java\"
+    .annotation runtime Ljava/lang/Deprecated;
+    .end annotation
+.end field"
+            ),
+            vec![
+                "First member declared here.",
+                "'.source' directive must precede fields and methods.",
+                "First member declared here.",
+                "'.annotation' directive must precede fields and methods.",
+                "Field declared here.",
+                "Annotation is separated from the field above it by '.source'.\nMove it directly below the '.field' line \
+                 to annotate the field."
+            ]
+        );
+    }
+}
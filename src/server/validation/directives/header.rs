@@ -2,8 +2,11 @@ use lspower::lsp::{Diagnostic, DiagnosticSeverity};
 
 use super::Validator;
 use crate::server::{
+    blocks::BlockTracker,
     helper::tokens_to_diagnostic,
     lexer::{Token, TokenType},
+    settings::Settings,
+    validation::{BlankLineTracker, Category},
 };
 
 #[derive(Debug)]
@@ -13,34 +16,45 @@ pub struct HeaderValidator {
     class_declaration:  Option<Vec<Token>>,
     source_declaration: Option<Vec<Token>>,
     blank_line:         bool,
-    last_token:         Option<Token>,
+    line_tracker:       BlankLineTracker,
+    first_member:       Option<Vec<Token>>,
+    early_implements:   Vec<Vec<Token>>,
+    /// A `.source` declared before `.class` or `.super`.
+    early_source:       Option<Vec<Token>>,
+    blocks:             BlockTracker,
+    strict_descriptors: bool,
 }
 
-impl Default for HeaderValidator {
-    fn default() -> Self {
+impl HeaderValidator {
+    pub fn new(settings: &Settings) -> Self {
         Self {
             top_line:           None,
             super_declaration:  None,
             class_declaration:  None,
             source_declaration: None,
             blank_line:         false,
-            last_token:         None,
+            line_tracker:       BlankLineTracker::default(),
+            first_member:       None,
+            early_implements:   Vec::new(),
+            early_source:       None,
+            blocks:             BlockTracker::default(),
+            strict_descriptors: settings.strict_descriptors,
         }
     }
+
+    /// Whether the declared class carries the `interface` modifier.
+    pub fn is_interface(&self) -> bool {
+        self.class_declaration
+            .iter()
+            .flatten()
+            .any(|token| token.token_type == TokenType::Modifier && token.content == "interface")
+    }
 }
 
 impl Validator for HeaderValidator {
     fn validate_token(&mut self, token: &Token) -> Vec<Diagnostic> {
-        if token.token_type == TokenType::NewLine {
-            if let Some(tkn) = &self.last_token {
-                if tkn.token_type == TokenType::NewLine {
-                    self.blank_line = true;
-                }
-            }
-        }
-
-        if token.token_type != TokenType::Space {
-            self.last_token = Some(token.clone());
+        if self.line_tracker.update(token) {
+            self.blank_line = true;
         }
 
         Vec::new()
@@ -49,6 +63,10 @@ impl Validator for HeaderValidator {
     fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
         let mut diags = Vec::new();
 
+        if self.blocks.update(line) == 0 {
+            diags.append(&mut validate_member_order(line, self));
+        }
+
         if line[0].token_type == TokenType::Directive {
             match line[0].content.as_ref() {
                 ".class" => {
@@ -64,7 +82,7 @@ impl Validator for HeaderValidator {
                             Some(DiagnosticSeverity::Error),
                         ));
                     } else {
-                        diags.append(&mut validate_class(line.into()));
+                        diags.append(&mut validate_class(line.into(), self.strict_descriptors));
                         self.class_declaration = Some(line.into());
                     }
                 },
@@ -81,12 +99,40 @@ impl Validator for HeaderValidator {
                             Some(DiagnosticSeverity::Error),
                         ));
                     } else {
-                        diags.append(&mut validate_simple(line.into()));
+                        diags.append(&mut validate_simple(line.into(), self.strict_descriptors));
                         self.super_declaration = Some(line.into());
+
+                        for implements in self.early_implements.drain(..) {
+                            diags.push(tokens_to_diagnostic(
+                                &line,
+                                "Super declared here.",
+                                Some(DiagnosticSeverity::Hint),
+                            ));
+                            diags.push(tokens_to_diagnostic(
+                                &implements,
+                                "'.implements' directive should follow '.super'.",
+                                Some(DiagnosticSeverity::Warning),
+                            ));
+                        }
                     }
                 },
                 ".implements" => {
-                    diags.append(&mut validate_simple(line.into()));
+                    diags.append(&mut validate_simple(line.into(), self.strict_descriptors));
+
+                    if let Some(member) = &self.first_member {
+                        diags.push(tokens_to_diagnostic(
+                            member,
+                            "First member declared here.",
+                            Some(DiagnosticSeverity::Hint),
+                        ));
+                        diags.push(tokens_to_diagnostic(
+                            &line,
+                            "'.implements' directive should precede fields and methods.",
+                            Some(DiagnosticSeverity::Warning),
+                        ));
+                    } else if self.super_declaration.is_none() {
+                        self.early_implements.push(line.into());
+                    }
                 },
                 ".source" => {
                     if let Some(tokens) = &self.source_declaration {
@@ -101,12 +147,35 @@ impl Validator for HeaderValidator {
                             Some(DiagnosticSeverity::Error),
                         ));
                     } else {
-                        diags.append(&mut validate_simple(line.into()));
+                        diags.append(&mut validate_simple(line.into(), self.strict_descriptors));
                         self.source_declaration = Some(line.into());
+
+                        if self.class_declaration.is_none() || self.super_declaration.is_none() {
+                            self.early_source = Some(line.into());
+                        }
                     }
                 },
                 _ => {},
             }
+
+            // Only reported once both are declared, files missing either are
+            // already told about it
+            if let (Some(_), Some(_)) = (&self.class_declaration, &self.super_declaration) {
+                if let Some(source) = self.early_source.take() {
+                    let declared = if line[0].content == ".class" { "Class" } else { "Super" };
+
+                    diags.push(tokens_to_diagnostic(
+                        line,
+                        format!("{} declared here.", declared),
+                        Some(DiagnosticSeverity::Hint),
+                    ));
+                    diags.push(tokens_to_diagnostic(
+                        &source,
+                        "'.source' directive should follow '.class' and '.super'.",
+                        Some(DiagnosticSeverity::Warning),
+                    ));
+                }
+            }
         }
 
         if self.top_line.is_none() {
@@ -128,7 +197,8 @@ impl Validator for HeaderValidator {
                 ));
             }
 
-            if self.super_declaration.is_none() {
+            // Files with members have already been told at the first one
+            if self.super_declaration.is_none() && self.first_member.is_none() {
                 diags.push(tokens_to_diagnostic(
                     top_line,
                     "Missing super directive.\nExtend 'Ljava/lang/Object;' by default",
@@ -141,18 +211,73 @@ impl Validator for HeaderValidator {
     }
 }
 
+/// Flags class level directives placed after the first field or method.
+///
+/// `.implements` is only reported as a warning by the `.implements` check as
+/// the assembler accepts it anywhere in the class body, `.source` is only a
+/// convention so it is a warning too.
+fn validate_member_order(line: &[Token], validator: &mut HeaderValidator) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let is_class_level = match (&line[0].token_type, line[0].content.as_ref()) {
+        (TokenType::Directive, ".class" | ".super" | ".source") => true,
+        (TokenType::Annotation, ".annotation") => true,
+        (TokenType::Field, ".field") | (TokenType::Method, ".method") => {
+            if validator.first_member.is_none() {
+                validator.first_member = Some(line.into());
+
+                if validator.super_declaration.is_none() {
+                    diags.push(tokens_to_diagnostic(
+                        line,
+                        "Missing super directive before first member.\nExtend 'Ljava/lang/Object;' by default",
+                        Some(DiagnosticSeverity::Error),
+                    ));
+                }
+            }
+
+            false
+        },
+        _ => false,
+    };
+
+    if let (true, Some(member)) = (is_class_level, &validator.first_member) {
+        let severity = if line[0].content == ".source" {
+            DiagnosticSeverity::Warning
+        } else {
+            DiagnosticSeverity::Error
+        };
+
+        diags.push(tokens_to_diagnostic(
+            member,
+            "First member declared here.",
+            Some(DiagnosticSeverity::Hint),
+        ));
+        diags.push(tokens_to_diagnostic(
+            line,
+            format!("'{}' directive must precede fields and methods.", line[0].content),
+            Some(severity),
+        ));
+    }
+
+    diags
+}
+
 #[derive(Debug, PartialEq)]
 enum Stage {
     Modifier,
     Other,
 }
 
-fn validate_class(line: Vec<Token>) -> Vec<Diagnostic> {
+fn validate_class(line: Vec<Token>, strict: bool) -> Vec<Diagnostic> {
     let mut diags = Vec::new();
 
     let mut vsblty_decl: Option<Token> = None;
     let mut final_decl: Option<Token> = None;
     let mut synthc_decl: Option<Token> = None;
+    let mut abstrc_decl: Option<Token> = None;
+    let mut intrfc_decl: Option<Token> = None;
+    let mut anntn_decl: Option<Token> = None;
+    let mut enum_decl: Option<Token> = None;
     let mut stage = Stage::Modifier;
 
     for (idx, token) in line.iter().enumerate() {
@@ -226,9 +351,75 @@ fn validate_class(line: Vec<Token>) -> Vec<Diagnostic> {
 
                         synthc_decl = Some(token.clone());
                     },
+                    "abstract" => {
+                        if let Some(abstrc_token) = &abstrc_decl {
+                            diags.push(
+                                abstrc_token
+                                    .to_diagnostic("Abstract modifier defined here.", Some(DiagnosticSeverity::Hint)),
+                            );
+                            diags.push(
+                                token.to_diagnostic("Abstract modifier already defined.", Some(DiagnosticSeverity::Error)),
+                            );
+
+                            continue;
+                        }
+
+                        abstrc_decl = Some(token.clone());
+                    },
+                    "interface" => {
+                        if let Some(intrfc_token) = &intrfc_decl {
+                            diags.push(
+                                intrfc_token
+                                    .to_diagnostic("Interface modifier defined here.", Some(DiagnosticSeverity::Hint)),
+                            );
+                            diags.push(
+                                token.to_diagnostic(
+                                    "Interface modifier already defined.",
+                                    Some(DiagnosticSeverity::Error),
+                                ),
+                            );
+
+                            continue;
+                        }
+
+                        intrfc_decl = Some(token.clone());
+                    },
+                    "annotation" => {
+                        if let Some(anntn_token) = &anntn_decl {
+                            diags.push(
+                                anntn_token
+                                    .to_diagnostic("Annotation modifier defined here.", Some(DiagnosticSeverity::Hint)),
+                            );
+                            diags.push(
+                                token.to_diagnostic(
+                                    "Annotation modifier already defined.",
+                                    Some(DiagnosticSeverity::Error),
+                                ),
+                            );
+
+                            continue;
+                        }
+
+                        anntn_decl = Some(token.clone());
+                    },
+                    "enum" => {
+                        if let Some(enum_token) = &enum_decl {
+                            diags.push(
+                                enum_token.to_diagnostic("Enum modifier defined here.", Some(DiagnosticSeverity::Hint)),
+                            );
+                            diags.push(
+                                token.to_diagnostic("Enum modifier already defined.", Some(DiagnosticSeverity::Error)),
+                            );
+
+                            continue;
+                        }
+
+                        enum_decl = Some(token.clone());
+                    },
                     _ => {},
                 },
                 TokenType::Class => {
+                    diags.append(&mut validate_descriptor(token, strict));
                     stage = Stage::Other;
                 },
                 _ => {},
@@ -238,10 +429,106 @@ fn validate_class(line: Vec<Token>) -> Vec<Diagnostic> {
         }
     }
 
+    if let (Some(abstrc_token), Some(final_token)) = (&abstrc_decl, &final_decl) {
+        diags.push(abstrc_token.to_diagnostic("Abstract modifier defined here.", Some(DiagnosticSeverity::Hint)));
+        diags.push(final_token.to_diagnostic("Final modifier defined here.", Some(DiagnosticSeverity::Hint)));
+        diags.push(tokens_to_diagnostic(
+            &line,
+            "Class cannot be defined as both abstract and final.",
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    let contradictions = [
+        (&intrfc_decl, &final_decl, "interface", "final"),
+        (&intrfc_decl, &enum_decl, "interface", "enum"),
+        (&enum_decl, &abstrc_decl, "enum", "abstract"),
+    ];
+    for (first, second, first_name, second_name) in contradictions.iter() {
+        if let (Some(first_token), Some(second_token)) = (first, second) {
+            diags.push(first_token.to_diagnostic(
+                format!("{} modifier defined here.", capitalize(first_name)),
+                Some(DiagnosticSeverity::Hint),
+            ));
+            diags.push(second_token.to_diagnostic(
+                format!("{} modifier defined here.", capitalize(second_name)),
+                Some(DiagnosticSeverity::Hint),
+            ));
+            diags.push(tokens_to_diagnostic(
+                &line,
+                format!("Class cannot be defined as both {} and {}.", first_name, second_name),
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+    }
+
+    if let (Some(anntn_token), None) = (&anntn_decl, &intrfc_decl) {
+        diags.push(anntn_token.to_diagnostic(
+            "Annotation class must also be defined as interface.",
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    if let (Some(synthc_token), Some(vsblty_token)) = (&synthc_decl, &vsblty_decl) {
+        if vsblty_token.content == "public" {
+            diags.push(Category::Info.tag(synthc_token.to_diagnostic(
+                "Public class is marked as synthetic.\nSynthetic classes are usually generated by the compiler.",
+                Some(DiagnosticSeverity::Information),
+            )));
+        }
+    }
+
+    diags
+}
+
+/// Checks that a class descriptor is `L`, `/` separated identifiers and `;`,
+/// with `$` only in the class name itself.
+///
+/// `-` is only reported when `strict` is set, as D8 names synthetic classes
+/// `-$$Lambda$Foo`.
+fn validate_descriptor(token: &Token, strict: bool) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let name = match token.content.strip_prefix('L').and_then(|name| name.strip_suffix(';')) {
+        Some(name) => name,
+        None => return diags,
+    };
+    let segments = name.split('/').collect::<Vec<_>>();
+
+    let error = if name.contains('.') {
+        Some(format!("'{}' uses '.' as a separator.\nClass descriptors separate packages with '/'.", token.content))
+    } else if segments.iter().any(|segment| segment.is_empty()) {
+        Some(format!("'{}' contains an empty package or class name.", token.content))
+    } else if segments[..segments.len() - 1].iter().any(|segment| segment.contains('$')) {
+        Some(format!("'{}' uses '$' in a package name.\n'$' is only valid in nested class names.", token.content))
+    } else if segments.iter().any(|segment| segment.starts_with(|c: char| c.is_ascii_digit())) {
+        Some(format!("'{}' contains a name starting with a digit.", token.content))
+    } else {
+        None
+    };
+
+    if let Some(error) = error {
+        diags.push(token.to_diagnostic(error, Some(DiagnosticSeverity::Error)));
+    } else if strict && name.contains('-') {
+        diags.push(token.to_diagnostic(
+            format!("'{}' contains '-', which isn't valid in Java names.", token.content),
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
     diags
 }
 
-fn validate_simple(line: Vec<Token>) -> Vec<Diagnostic> {
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn validate_simple(line: Vec<Token>, strict: bool) -> Vec<Diagnostic> {
     let mut diags = Vec::new();
 
     if line.len() < 3 {
@@ -273,6 +560,8 @@ fn validate_simple(line: Vec<Token>) -> Vec<Diagnostic> {
             2 => {
                 if token.token_type != TokenType::Class && line[0].content != ".source" {
                     diags.push(token.to_diagnostic("Class expected.", Some(DiagnosticSeverity::Error)));
+                } else if token.token_type == TokenType::Class {
+                    diags.append(&mut validate_descriptor(token, strict));
                 } else if token.token_type != TokenType::String && line[0].content == ".source" {
                     diags.push(token.to_diagnostic("String expected.", Some(DiagnosticSeverity::Error)));
                 }
@@ -285,3 +574,299 @@ fn validate_simple(line: Vec<Token>) -> Vec<Diagnostic> {
 
     diags
 }
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::DiagnosticSeverity;
+
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::fixture::{severities, validate_file, validate_file_with},
+    };
+
+    fn validate_header(class: &str) -> Vec<(String, Option<DiagnosticSeverity>)> {
+        validate_messages(&format!("{}\n.super Ljava/lang/Object;\n", class))
+    }
+
+    fn validate_messages(content: &str) -> Vec<(String, Option<DiagnosticSeverity>)> {
+        severities(validate_file(content))
+    }
+
+    #[test]
+    fn abstract_final_class() {
+        assert_eq!(validate_header(".class public abstract final Lme/l3af/Test;"), vec![
+            ("Abstract modifier defined here.".to_string(), Some(DiagnosticSeverity::Hint)),
+            ("Final modifier defined here.".to_string(), Some(DiagnosticSeverity::Hint)),
+            (
+                "Class cannot be defined as both abstract and final.".to_string(),
+                Some(DiagnosticSeverity::Error)
+            ),
+        ]);
+    }
+
+    #[test]
+    fn abstract_or_final_class() {
+        assert!(validate_header(".class public abstract Lme/l3af/Test;").is_empty());
+        assert!(validate_header(".class public final Lme/l3af/Test;").is_empty());
+    }
+
+    fn contradiction(first: &str, second: &str) -> Vec<(String, Option<DiagnosticSeverity>)> {
+        vec![
+            (format!("{} modifier defined here.", first), Some(DiagnosticSeverity::Hint)),
+            (format!("{} modifier defined here.", second), Some(DiagnosticSeverity::Hint)),
+            (
+                format!(
+                    "Class cannot be defined as both {} and {}.",
+                    first.to_lowercase(),
+                    second.to_lowercase()
+                ),
+                Some(DiagnosticSeverity::Error),
+            ),
+        ]
+    }
+
+    #[test]
+    fn interface_final_class() {
+        assert_eq!(
+            validate_header(".class public interface final Lme/l3af/Test;"),
+            contradiction("Interface", "Final")
+        );
+    }
+
+    #[test]
+    fn interface_enum_class() {
+        assert_eq!(
+            validate_header(".class public interface enum Lme/l3af/Test;"),
+            contradiction("Interface", "Enum")
+        );
+    }
+
+    #[test]
+    fn enum_abstract_class() {
+        assert_eq!(validate_header(".class public abstract enum Lme/l3af/Test;"), contradiction("Enum", "Abstract"));
+    }
+
+    #[test]
+    fn annotation_without_interface() {
+        assert_eq!(validate_header(".class public annotation Lme/l3af/Test;"), vec![(
+            "Annotation class must also be defined as interface.".to_string(),
+            Some(DiagnosticSeverity::Error)
+        )]);
+    }
+
+    #[test]
+    fn valid_class_kinds() {
+        assert!(validate_header(".class public interface abstract Lme/l3af/Test;").is_empty());
+        assert!(validate_header(".class public interface abstract annotation Lme/l3af/Test;").is_empty());
+        assert!(validate_header(".class public final enum Lme/l3af/Test;").is_empty());
+        assert!(validate_header(".class synthetic Lme/l3af/Test$1;").is_empty());
+    }
+
+    #[test]
+    fn public_synthetic_class() {
+        assert_eq!(validate_header(".class public synthetic Lme/l3af/Test;"), vec![(
+            "Public class is marked as synthetic.\nSynthetic classes are usually generated by the compiler."
+                .to_string(),
+            Some(DiagnosticSeverity::Information)
+        )]);
+        assert_eq!(
+            validate_header(".class public synthetic synthetic Lme/l3af/Test;")
+                .into_iter()
+                .map(|(message, _)| message)
+                .collect::<Vec<_>>(),
+            vec![
+                "Synthetic modifier defined here.",
+                "Synthetic modifier already defined.",
+                "Public class is marked as synthetic.\nSynthetic classes are usually generated by the compiler."
+            ]
+        );
+    }
+
+    #[test]
+    fn valid_descriptors() {
+        assert!(validate_header(".class public Lcom/foo/Bar;").is_empty());
+        assert!(validate_header(".class public Lcom/foo/Bar$Inner$1;").is_empty());
+        assert!(
+            validate_messages(".class public LBar;\n.super Ljava/lang/Object;\n.implements Lcom/foo_bar/Baz;\n")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn invalid_descriptors() {
+        assert_eq!(validate_header(".class public Lcom.foo/Bar;"), vec![(
+            "'Lcom.foo/Bar;' uses '.' as a separator.\nClass descriptors separate packages with '/'.".to_string(),
+            Some(DiagnosticSeverity::Error)
+        )]);
+        assert_eq!(validate_header(".class public Lcom//Bar;"), vec![(
+            "'Lcom//Bar;' contains an empty package or class name.".to_string(),
+            Some(DiagnosticSeverity::Error)
+        )]);
+        assert_eq!(validate_header(".class public Lcom$foo/Bar;"), vec![(
+            "'Lcom$foo/Bar;' uses '$' in a package name.\n'$' is only valid in nested class names.".to_string(),
+            Some(DiagnosticSeverity::Error)
+        )]);
+        assert_eq!(validate_header(".class public Lcom/1foo/Bar;"), vec![(
+            "'Lcom/1foo/Bar;' contains a name starting with a digit.".to_string(),
+            Some(DiagnosticSeverity::Error)
+        )]);
+        assert!(validate_header(".class public LL2;").is_empty());
+        assert_eq!(
+            validate_messages(".class public LBar;\n.super Ljava/lang/Object;\n.implements Lcom.foo.Baz;\n"),
+            vec![(
+                "'Lcom.foo.Baz;' uses '.' as a separator.\nClass descriptors separate packages with '/'.".to_string(),
+                Some(DiagnosticSeverity::Error)
+            )]
+        );
+    }
+
+    #[test]
+    fn dashed_descriptor() {
+        assert!(validate_header(".class final synthetic Lcom/foo/-$$Lambda$Bar$1;").is_empty());
+
+        let content = ".class final synthetic Lcom/foo/-$$Lambda$Bar$1;\n.super Ljava/lang/Object;\n";
+        let settings = Settings {
+            strict_descriptors: true,
+            ..Settings::default()
+        };
+        let diags = validate_file_with(content, &settings, &ClassIndex::default());
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::Error));
+    }
+
+    #[test]
+    fn duplicate_abstract_class() {
+        assert_eq!(
+            validate_header(".class public abstract abstract Lme/l3af/Test;")
+                .into_iter()
+                .map(|(message, _)| message)
+                .collect::<Vec<_>>(),
+            vec!["Abstract modifier defined here.", "Abstract modifier already defined."]
+        );
+    }
+
+    #[test]
+    fn trailing_comment() {
+        assert!(validate_messages(
+            ".class public Lme/l3af/Test; # test\n# comment\n.super Ljava/lang/Object;\n.source \"Test.java\" # \
+             source\n"
+        )
+        .is_empty());
+
+        assert!(validate_messages(".class public Lme/l3af/Test;\n.super Ljava/lang/Object; # base\n").is_empty());
+    }
+
+    #[test]
+    fn class_directive_after_member() {
+        let content = ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n.method public test()V\n    .locals \
+                       0\n    return-void\n.end method\n\n.source \"Test.java\"\n";
+
+        assert_eq!(validate_messages(content), vec![
+            ("First member declared here.".to_string(), Some(DiagnosticSeverity::Hint)),
+            (
+                "'.source' directive must precede fields and methods.".to_string(),
+                Some(DiagnosticSeverity::Warning)
+            ),
+        ]);
+    }
+
+    #[test]
+    fn source_placement() {
+        assert!(validate_messages(
+            ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n.implements Ljava/lang/Runnable;\n.source \
+             \"Test.java\"\n"
+        )
+        .is_empty());
+        assert_eq!(
+            validate_messages(".source \"Test.java\"\n.class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n"),
+            vec![
+                ("Super declared here.".to_string(), Some(DiagnosticSeverity::Hint)),
+                (
+                    "'.source' directive should follow '.class' and '.super'.".to_string(),
+                    Some(DiagnosticSeverity::Warning)
+                ),
+            ]
+        );
+        assert_eq!(
+            validate_messages(
+                ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n.method public test()V\n    .locals \
+                 0\n    return-void\n.end method\n.source \"Test.java\"\n"
+            )
+            .last(),
+            Some(&(
+                "'.source' directive must precede fields and methods.".to_string(),
+                Some(DiagnosticSeverity::Warning)
+            ))
+        );
+    }
+
+    #[test]
+    fn class_directives_before_members() {
+        let content = ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n.source \"Test.java\"\n\n.annotation \
+                       system Lfoo;\n.end annotation\n\n.field private count:I\n    .annotation runtime Lbar;\n    \
+                       .end annotation\n.end field\n\n.method public test()V\n    .locals 0\n    .annotation \
+                       runtime Lbar;\n    .end annotation\n    return-void\n.end method\n";
+
+        assert!(validate_messages(content).is_empty());
+    }
+
+    #[test]
+    fn member_before_super() {
+        let content = ".class public Lme/l3af/Test;\n\n.method public test()V\n    .locals 0\n    return-void\n.end \
+                       method\n";
+
+        let diags = validate_file(content);
+
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].message,
+            "Missing super directive before first member.\nExtend 'Ljava/lang/Object;' by default"
+        );
+        assert_eq!(diags[0].range.start.line, 2);
+    }
+
+    #[test]
+    fn missing_super_without_members() {
+        assert_eq!(validate_messages(".class public Lme/l3af/Test;\n"), vec![(
+            "Missing super directive.\nExtend 'Ljava/lang/Object;' by default".to_string(),
+            Some(DiagnosticSeverity::Error)
+        )]);
+    }
+
+    #[test]
+    fn implements_order() {
+        assert!(validate_messages(
+            ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n.implements Ljava/lang/Runnable;\n\n.field \
+             private count:I\n"
+        )
+        .is_empty());
+
+        assert_eq!(
+            validate_messages(
+                ".class public Lme/l3af/Test;\n.implements Ljava/lang/Runnable;\n.super Ljava/lang/Object;\n"
+            ),
+            vec![
+                ("Super declared here.".to_string(), Some(DiagnosticSeverity::Hint)),
+                (
+                    "'.implements' directive should follow '.super'.".to_string(),
+                    Some(DiagnosticSeverity::Warning)
+                ),
+            ]
+        );
+
+        assert_eq!(
+            validate_messages(
+                ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n.field private \
+                 count:I\n\n.implements Ljava/lang/Runnable;\n"
+            ),
+            vec![
+                ("First member declared here.".to_string(), Some(DiagnosticSeverity::Hint)),
+                (
+                    "'.implements' directive should precede fields and methods.".to_string(),
+                    Some(DiagnosticSeverity::Warning)
+                ),
+            ]
+        );
+    }
+}
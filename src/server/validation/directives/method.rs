@@ -2,8 +2,13 @@ use lspower::lsp::{Diagnostic, DiagnosticSeverity};
 
 use super::Validator;
 use crate::server::{
-    helper::tokens_to_diagnostic,
+    blocks::{Block, BlockTracker},
+    descriptor::{is_wide, parameter_register_count},
+    helper::{parse_count, tokens_to_diagnostic},
     lexer::{Token, TokenType},
+    registers::MAX_REGISTERS,
+    settings::Settings,
+    validation::{validate_parameter_register, Category},
 };
 
 #[derive(Debug)]
@@ -11,6 +16,22 @@ pub struct MethodValidator {
     method_decl:         Option<MethodDeclaration>,
     constructor_static:  Option<MethodDeclaration>,
     constructor_virtual: Option<MethodDeclaration>,
+    param_decl:          Option<ParamDeclaration>,
+    found_instruction:   bool,
+    found_registers:     bool,
+    /// Whether the class is an interface, set from the header.
+    is_interface:        bool,
+    strict_interface:    bool,
+    blocks:              BlockTracker,
+    /// The instructions in the current method so far.
+    instructions:        u32,
+    max_instructions:    Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+struct ParamDeclaration {
+    tokens:   Vec<Token>,
+    is_block: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -45,14 +66,26 @@ macro_rules! breakable {
     };
 }
 
-impl Default for MethodValidator {
-    fn default() -> Self {
+impl MethodValidator {
+    pub fn new(settings: &Settings) -> Self {
         Self {
             method_decl:         None,
             constructor_static:  None,
             constructor_virtual: None,
+            param_decl:          None,
+            found_instruction:   false,
+            found_registers:     false,
+            is_interface:        false,
+            strict_interface:    settings.strict_interface_methods,
+            blocks:              BlockTracker::default(),
+            instructions:        0,
+            max_instructions:    settings.max_method_instructions,
         }
     }
+
+    pub fn set_interface(&mut self, is_interface: bool) {
+        self.is_interface = is_interface;
+    }
 }
 
 impl Validator for MethodValidator {
@@ -73,11 +106,47 @@ impl Validator for MethodValidator {
     fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
         let mut diags = Vec::new();
 
-        #[allow(clippy::single_match)]
+        // The opener has to be read before `.end method` pops the block
+        if Block::closed_by(line) == Some(Block::Method) && self.blocks.current() == Some(Block::Method) {
+            diags.append(&mut validate_method_size(self));
+        }
+        self.blocks.update(line);
+
         match line[0].token_type {
             TokenType::Method => {
+                diags.append(&mut validate_unterminated_param(self));
+                if line[0].content == ".method" {
+                    self.found_instruction = false;
+                    self.found_registers = false;
+                    self.instructions = 0;
+                }
+
                 diags.append(&mut validate_method_declaration(line, self));
             },
+            TokenType::Param => {
+                diags.append(&mut validate_param(line, self));
+            },
+            TokenType::Directive if line[0].content == ".locals" || line[0].content == ".registers" => {
+                self.found_registers = true;
+                diags.append(&mut validate_register_count(line, self));
+            },
+            TokenType::Annotation => {
+                if let Some(param) = &mut self.param_decl {
+                    param.is_block = true;
+                }
+            },
+            ref token_type if token_type.is_opcode() => {
+                diags.append(&mut validate_unterminated_param(self));
+                self.found_instruction = true;
+                self.instructions += 1;
+
+                // Returns are checked against the declared type per token
+                if token_type.is_terminal() && *token_type != TokenType::Return {
+                    if let Some(method) = &mut self.method_decl {
+                        method.found_return = true;
+                    }
+                }
+            },
             _ => {},
         }
 
@@ -89,45 +158,216 @@ impl Validator for MethodValidator {
     }
 }
 
+fn validate_param(line: &[Token], validator: &mut MethodValidator) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let method = match &validator.method_decl {
+        Some(method) if method.is_start => method.clone(),
+        _ => {
+            diags.push(tokens_to_diagnostic(
+                line,
+                format!("'{}' directive must be inside a method block.", line[0].content),
+                Some(DiagnosticSeverity::Error),
+            ));
+
+            return diags;
+        },
+    };
+
+    if line[0].content == ".end param" {
+        if validator.param_decl.take().is_none() {
+            diags.push(tokens_to_diagnostic(
+                line,
+                "'.end param' directive must close a '.param' block.",
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+
+        return diags;
+    }
+
+    diags.append(&mut validate_unterminated_param(validator));
+    validator.param_decl = Some(ParamDeclaration {
+        tokens:   line.into(),
+        is_block: false,
+    });
+
+    if validator.found_instruction {
+        diags.push(tokens_to_diagnostic(
+            line,
+            "'.param' directive must precede the first instruction.",
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    let register = match line.get(2) {
+        Some(token) if token.token_type == TokenType::Register && token.content.starts_with('p') => token,
+        Some(token) => {
+            diags.push(token.to_diagnostic("Parameter register expected.", Some(DiagnosticSeverity::Error)));
+            return diags;
+        },
+        None => {
+            diags.push(tokens_to_diagnostic(
+                line,
+                "'.param pN, \"name\"'",
+                Some(DiagnosticSeverity::Error),
+            ));
+            return diags;
+        },
+    };
+
+    let count = match parameter_register_count(&method.tokens) {
+        Some(count) => count,
+        None => return diags,
+    };
+    let is_static = method
+        .tokens
+        .iter()
+        .any(|token| token.token_type == TokenType::Modifier && token.content == "static");
+    let first = if is_static { 0 } else { 1 };
+
+    diags.extend(validate_parameter_register(register, first, count));
+
+    diags
+}
+
+fn validate_register_count(line: &[Token], validator: &MethodValidator) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    let directive = &line[0].content;
+
+    let number = match &line[1..] {
+        [space, number] if space.token_type == TokenType::Space && number.token_type == TokenType::Number => number,
+        _ => {
+            diags.push(tokens_to_diagnostic(
+                line,
+                format!("'{}' expects a register count.\n'{} N'", directive, directive),
+                Some(DiagnosticSeverity::Error),
+            ));
+            return diags;
+        },
+    };
+
+    if number.content.starts_with('-') {
+        diags.push(number.to_diagnostic(
+            format!("'{}' count cannot be negative.", directive),
+            Some(DiagnosticSeverity::Error),
+        ));
+        return diags;
+    }
+
+    let count = match parse_count(&number.content) {
+        Some(count) => count,
+        None => {
+            diags.push(number.to_diagnostic(
+                format!("'{}' is not a valid register count.", number.content),
+                Some(DiagnosticSeverity::Error),
+            ));
+            return diags;
+        },
+    };
+
+    if number.content.starts_with("0x") {
+        diags.push(number.to_diagnostic(
+            format!("Register count '{}' is hexadecimal.\nUse '{} {}' instead.", number.content, directive, count),
+            Some(DiagnosticSeverity::Warning),
+        ));
+    }
+
+    let count = u64::from(count);
+
+    let params = match &validator.method_decl {
+        Some(method) if method.is_start => parameter_register_count(&method.tokens),
+        _ => None,
+    };
+
+    // `.registers` already includes the parameter registers, so it has to
+    // leave room for all of them
+    if let Some(params) = params.filter(|params| directive == ".registers" && count < u64::from(*params)) {
+        diags.push(number.to_diagnostic(
+            format!(
+                "'.registers {}' is too small for the method's parameters.\nThe parameters alone need {} registers.",
+                count, params
+            ),
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    let params = if directive == ".locals" { params.unwrap_or(0) as u64 } else { 0 };
+    let total = count.saturating_add(params);
+    if total > u64::from(MAX_REGISTERS) {
+        diags.push(number.to_diagnostic(
+            format!("Method uses {} registers, the maximum is {}.", total, MAX_REGISTERS),
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    diags
+}
+
+fn validate_unterminated_param(validator: &mut MethodValidator) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    if let Some(param) = validator.param_decl.take() {
+        if param.is_block {
+            diags.push(tokens_to_diagnostic(
+                &param.tokens,
+                "'.end param' expected to close '.param' block.",
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+    }
+
+    diags
+}
+
 fn validate_method_token(token: &Token, validator: &mut MethodValidator) -> Vec<Diagnostic> {
     let mut diags = Vec::new();
 
-    if let Some(mut method) = validator.method_decl.clone() {
+    // Every return is checked, not just the first, as branches can end in
+    // different return instructions
+    if let Some(method) = &mut validator.method_decl {
+        if !method.is_start {
+            return diags;
+        }
+
         method.found_return = true;
-        validator.method_decl = Some(method.clone());
 
-        match method.return_type {
+        let (expected, declared) = match &method.return_type {
             ReturnType::None => {
-                diags.push(token.to_diagnostic(
+                diags.push(Category::Info.tag(token.to_diagnostic(
                     "Unable to get return type from method declaration.",
                     Some(DiagnosticSeverity::Information),
-                ));
-            },
-            ReturnType::Void => {
-                if token.content != "return-void" {
-                    diags.push(
-                        method
-                            .tokens
-                            .last()
-                            .unwrap()
-                            .to_diagnostic("Return type declared here.", Some(DiagnosticSeverity::Hint)),
-                    );
-                    diags.push(token.to_diagnostic("'return-void' expected.", Some(DiagnosticSeverity::Error)));
-                }
-            },
-            ReturnType::Class(_) => {
-                if token.content != "return-object" {
-                    diags.push(
-                        method
-                            .tokens
-                            .last()
-                            .unwrap()
-                            .to_diagnostic("Return type declared here.", Some(DiagnosticSeverity::Hint)),
-                    );
-                    diags.push(token.to_diagnostic("'return-object' expected.", Some(DiagnosticSeverity::Error)));
-                }
+                )));
+                return diags;
             },
-            _ => {},
+            ReturnType::Void => ("return-void", "V"),
+            ReturnType::Class(class) => ("return-object", class.as_str()),
+            ReturnType::BuiltinType(builtin_type) if is_wide(builtin_type) => ("return-wide", builtin_type.as_str()),
+            ReturnType::BuiltinType(builtin_type) => ("return", builtin_type.as_str()),
+        };
+
+        if token.content != expected {
+            let returned = match token.content.as_str() {
+                "return-void" => "returns nothing",
+                "return-wide" => "returns a wide primitive",
+                "return-object" => "returns a reference",
+                _ => "returns a narrow primitive",
+            };
+
+            diags.push(
+                method
+                    .tokens
+                    .last()
+                    .unwrap()
+                    .to_diagnostic("Return type declared here.", Some(DiagnosticSeverity::Hint)),
+            );
+            diags.push(token.to_diagnostic(
+                format!(
+                    "'{}' {}, but the method is declared to return '{}'.\nUse '{}' instead.",
+                    token.content, returned, declared, expected
+                ),
+                Some(DiagnosticSeverity::Error),
+            ));
         }
     }
 
@@ -159,6 +399,7 @@ fn validate_method_declaration(line: &[Token], validator: &mut MethodValidator)
 
         if valid_placement {
             diags.append(&mut method_decl.0);
+            diags.append(&mut validate_interface_method(line, validator));
         }
 
         validator.method_decl = Some(MethodDeclaration {
@@ -180,7 +421,12 @@ fn validate_method_declaration(line: &[Token], validator: &mut MethodValidator)
                 Some(DiagnosticSeverity::Error),
             ));
         } else {
-            if !method.found_return {
+            let is_concrete = !method
+                .tokens
+                .iter()
+                .any(|token| token.content == "abstract" || token.content == "native");
+
+            if is_concrete && !method.found_return {
                 diags.push(tokens_to_diagnostic(
                     &method.tokens,
                     "No return instruction found in method block.",
@@ -188,6 +434,14 @@ fn validate_method_declaration(line: &[Token], validator: &mut MethodValidator)
                 ));
             }
 
+            if is_concrete && !validator.found_registers && !validator.found_instruction {
+                diags.push(tokens_to_diagnostic(
+                    &method.tokens,
+                    "Empty method body.\nAdd '.locals 0' and a return instruction.",
+                    Some(DiagnosticSeverity::Warning),
+                ));
+            }
+
             validator.method_decl = Some(MethodDeclaration {
                 is_start:     false,
                 found_return: false,
@@ -206,6 +460,57 @@ fn validate_method_declaration(line: &[Token], validator: &mut MethodValidator)
     diags
 }
 
+/// Reports methods with more instructions than the configured maximum, as
+/// they get hard to follow well before reaching the 64K code unit limit.
+fn validate_method_size(validator: &MethodValidator) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let (max, opener) = match (validator.max_instructions, validator.blocks.opener()) {
+        (Some(max), Some(opener)) if validator.instructions > max => (max, opener),
+        _ => return diags,
+    };
+
+    diags.push(Diagnostic::new(
+        opener,
+        Some(DiagnosticSeverity::Warning),
+        None,
+        None,
+        format!(
+            "Method has {} instructions, the maximum is {}.\nSplit it into smaller methods.",
+            validator.instructions, max
+        ),
+        None,
+        None,
+    ));
+
+    diags
+}
+
+/// Interface methods have no body unless they're static, e.g. `<clinit>`.
+/// Default and private methods lift this from API 24, so it is only reported
+/// in strict mode.
+fn validate_interface_method(line: &[Token], validator: &MethodValidator) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    if !validator.is_interface || !validator.strict_interface {
+        return diags;
+    }
+
+    let has_modifier = |modifier: &str| {
+        line.iter()
+            .any(|token| token.token_type == TokenType::Modifier && token.content == modifier)
+    };
+
+    if !has_modifier("abstract") && !has_modifier("static") {
+        diags.push(tokens_to_diagnostic(
+            line,
+            "Interface methods must be 'abstract' or 'static'.\nDefault and private methods need API 24.",
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    diags
+}
+
 fn validate_method_declaration_line(line: &[Token], validator: &mut MethodValidator) -> (Vec<Diagnostic>, ReturnType) {
     let mut diags = Vec::new();
     let mut return_type = ReturnType::None;
@@ -216,6 +521,7 @@ fn validate_method_declaration_line(line: &[Token], validator: &mut MethodValida
     let mut const_decl: Option<Token> = None;
     let mut stage = MethodDeclarationStage::Modifiers;
     let mut has_return_type = false;
+    let mut array_prefix = String::new();
     let mut was_space = false;
 
     for (idx, token) in line.iter().enumerate() {
@@ -346,6 +652,12 @@ fn validate_method_declaration_line(line: &[Token], validator: &mut MethodValida
                 }
             }),
             MethodDeclarationStage::Params => breakable!({match token.token_type {
+                TokenType::BuiltinType if token.content == "V" => {
+                    diags.push(token.to_diagnostic(
+                        "Parameter type cannot be void.\n'V' is only valid as a return type.",
+                        Some(DiagnosticSeverity::Error),
+                    ));
+                },
                 TokenType::BuiltinType | TokenType::Class => {},
                 _ => {
                     if token.content == ")" {
@@ -365,7 +677,14 @@ fn validate_method_declaration_line(line: &[Token], validator: &mut MethodValida
                 }
 
                 match token.token_type {
-                    TokenType::BuiltinType => {
+                    TokenType::ArrayOp => {
+                        array_prefix.push('[');
+                    },
+                    TokenType::BuiltinType if !array_prefix.is_empty() && token.content != "V" => {
+                        has_return_type = true;
+                        return_type = ReturnType::Class(format!("{}{}", array_prefix, token.content));
+                    },
+                    TokenType::BuiltinType if array_prefix.is_empty() => {
                         has_return_type = true;
 
                         return_type = if token.content == "V" {
@@ -376,7 +695,7 @@ fn validate_method_declaration_line(line: &[Token], validator: &mut MethodValida
                     },
                     TokenType::Class => {
                         has_return_type = true;
-                        return_type = ReturnType::Class(token.content.clone());
+                        return_type = ReturnType::Class(format!("{}{}", array_prefix, token.content));
                     },
                     _ => {
                         diags.push(
@@ -435,3 +754,303 @@ fn validate_method_declaration_line(line: &[Token], validator: &mut MethodValida
 
     (diags, return_type)
 }
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::DiagnosticSeverity;
+
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::fixture::{
+            messages, severities, validate_body_with, validate_class, validate_class_with, validate_file_with,
+        },
+    };
+
+    fn validate_method(declaration: &str, body: &str) -> Vec<String> {
+        validate_class(&format!("{}\n    .locals 0\n{}\n    return-void\n.end method", declaration, body))
+    }
+
+    fn validate_registers(declaration: &str, directive: &str) -> Vec<String> {
+        validate_class(&format!("{}\n    {}\n    return-void\n.end method", declaration, directive))
+    }
+
+    #[test]
+    fn valid_register_count() {
+        assert!(validate_registers(".method public test(I)V", ".locals 65533").is_empty());
+        assert!(validate_registers(".method public test(I)V", ".registers 65535").is_empty());
+        assert!(validate_registers(".method public test()V", ".locals 2").is_empty());
+    }
+
+    #[test]
+    fn hexadecimal_register_count() {
+        assert_eq!(validate_registers(".method public test()V", ".locals 0x2"), vec![
+            "Register count '0x2' is hexadecimal.\nUse '.locals 2' instead."
+        ]);
+        assert_eq!(validate_registers(".method public test(I)V", ".registers 0x10"), vec![
+            "Register count '0x10' is hexadecimal.\nUse '.registers 16' instead."
+        ]);
+    }
+
+    #[test]
+    fn register_count_too_large() {
+        assert_eq!(validate_registers(".method public test()V", ".locals 70000"), vec![
+            "Method uses 70001 registers, the maximum is 65535."
+        ]);
+        assert_eq!(validate_registers(".method public test(J)V", ".locals 65533"), vec![
+            "Method uses 65536 registers, the maximum is 65535."
+        ]);
+        assert_eq!(validate_registers(".method public test()V", ".locals 99999999999999999999999"), vec![
+            "'99999999999999999999999' is not a valid register count."
+        ]);
+    }
+
+    #[test]
+    fn registers_below_parameter_count() {
+        assert_eq!(validate_registers(".method public test(II)V", ".registers 2"), vec![
+            "'.registers 2' is too small for the method's parameters.\nThe parameters alone need 3 registers."
+        ]);
+        assert_eq!(validate_registers(".method public static test(J)V", ".registers 1"), vec![
+            "'.registers 1' is too small for the method's parameters.\nThe parameters alone need 2 registers."
+        ]);
+        assert!(validate_registers(".method public test(II)V", ".registers 3").is_empty());
+    }
+
+    #[test]
+    fn negative_register_count() {
+        assert_eq!(validate_registers(".method public test()V", ".locals -1"), vec![
+            "'.locals' count cannot be negative."
+        ]);
+    }
+
+    #[test]
+    fn non_numeric_register_count() {
+        assert_eq!(validate_registers(".method public test()V", ".locals abc"), vec![
+            "'.locals' expects a register count.\n'.locals N'"
+        ]);
+        assert_eq!(validate_registers(".method public test()V", ".registers"), vec![
+            "'.registers' expects a register count.\n'.registers N'"
+        ]);
+    }
+
+    #[test]
+    fn empty_method_body() {
+        let method = ".method public test()V\n.end method";
+        let diags = validate_class_with(method, &Settings::default(), &ClassIndex::default());
+
+        assert_eq!(severities(diags), vec![
+            (
+                "No return instruction found in method block.".to_string(),
+                Some(DiagnosticSeverity::Error)
+            ),
+            (
+                "Empty method body.\nAdd '.locals 0' and a return instruction.".to_string(),
+                Some(DiagnosticSeverity::Warning)
+            ),
+        ]);
+    }
+
+    #[test]
+    fn minimal_method_stub() {
+        assert!(validate_registers(".method public test()V", ".locals 0").is_empty());
+    }
+
+    #[test]
+    fn terminal_instructions_end_method() {
+        assert!(validate_class(".method public test()I\n    .locals 1\n    throw v0\n.end method").is_empty());
+    }
+
+    fn validate_returns(declaration: &str, body: &str) -> Vec<(String, DiagnosticSeverity)> {
+        let method = format!("{}\n    .locals 1\n{}\n.end method", declaration, body);
+
+        validate_class_with(&method, &Settings::default(), &ClassIndex::default())
+            .into_iter()
+            .map(|diag| (diag.message, diag.severity.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn every_return_checked() {
+        assert_eq!(
+            validate_returns(
+                ".method public test(Z)I",
+                "    const/4 v0, 0x0\n    if-eqz p1, :cond_0\n    return v0\n    :cond_0\n    return-void"
+            ),
+            vec![
+                ("Return type declared here.".to_string(), DiagnosticSeverity::Hint),
+                (
+                    "'return-void' returns nothing, but the method is declared to return 'I'.\nUse 'return' instead."
+                        .to_string(),
+                    DiagnosticSeverity::Error
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn wide_and_array_returns() {
+        assert!(validate_returns(".method public test()J", "    const-wide/16 v0, 0x0\n    return-wide v0").is_empty());
+        assert!(validate_returns(".method public test()[I", "    const/4 v0, 0x0\n    return-object v0").is_empty());
+        assert_eq!(
+            validate_returns(".method public test()D", "    const/4 v0, 0x0\n    return v0")
+                .into_iter()
+                .map(|(message, _)| message)
+                .collect::<Vec<_>>(),
+            vec![
+                "Return type declared here.",
+                "'return' returns a narrow primitive, but the method is declared to return 'D'.\nUse 'return-wide' \
+                 instead."
+            ]
+        );
+    }
+
+    fn return_errors(return_type: &str, instruction: &str) -> Vec<String> {
+        let body = match instruction {
+            "return-void" => "    return-void".to_string(),
+            "return-wide" => "    const-wide/16 v0, 0x0\n    return-wide v0".to_string(),
+            _ => format!("    const/4 v0, 0x0\n    {} v0", instruction),
+        };
+
+        validate_returns(&format!(".method public static test(){}", return_type), &body)
+            .into_iter()
+            .filter(|(_, severity)| *severity == DiagnosticSeverity::Error)
+            .map(|(message, _)| message)
+            .collect()
+    }
+
+    #[test]
+    fn return_type_pairings() {
+        let categories = [
+            ("V", "return-void"),
+            ("I", "return"),
+            ("Z", "return"),
+            ("J", "return-wide"),
+            ("D", "return-wide"),
+            ("Ljava/lang/String;", "return-object"),
+            ("[I", "return-object"),
+        ];
+        let returned = [
+            ("return-void", "returns nothing"),
+            ("return", "returns a narrow primitive"),
+            ("return-wide", "returns a wide primitive"),
+            ("return-object", "returns a reference"),
+        ];
+
+        for (return_type, expected) in &categories {
+            for (instruction, description) in &returned {
+                let errors = return_errors(return_type, instruction);
+
+                if instruction == expected {
+                    assert!(errors.is_empty(), "{} in {}: {:?}", instruction, return_type, errors);
+                } else {
+                    assert_eq!(errors, vec![format!(
+                        "'{}' {}, but the method is declared to return '{}'.\nUse '{}' instead.",
+                        instruction, description, return_type, expected
+                    )]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn valid_param() {
+        assert!(validate_method(".method public test(ILjava/lang/String;)V", "    .param p1, \"arg\"").is_empty());
+        assert!(validate_method(
+            ".method public static test(ILjava/lang/String;)V",
+            "    .param p1, \"arg\"\n    .annotation build Lfoo;\n    .end annotation\n    .end param"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn param_out_of_range() {
+        assert_eq!(validate_method(".method public test(ILjava/lang/String;)V", "    .param p9"), vec![
+            "'p9' is not a parameter register.\nParameters are p1..p2."
+        ]);
+        assert_eq!(validate_method(".method public test()V", "    .param p0, \"this\""), vec![
+            "'p0' is not a parameter register.\nMethod takes no parameters."
+        ]);
+    }
+
+    #[test]
+    fn param_after_instruction() {
+        assert_eq!(
+            validate_method(".method public test(I)V", "    const/4 p1, 0x0\n    .param p1, \"arg\""),
+            vec!["'.param' directive must precede the first instruction."]
+        );
+    }
+
+    #[test]
+    fn unbalanced_param_block() {
+        assert_eq!(
+            validate_method(".method public test(I)V", "    .param p1\n    .annotation build Lfoo;\n    .end annotation"),
+            vec!["'.end param' expected to close '.param' block."]
+        );
+        assert_eq!(validate_method(".method public test(I)V", "    .end param"), vec![
+            "'.end param' directive must close a '.param' block."
+        ]);
+    }
+
+    #[test]
+    fn void_parameter() {
+        assert_eq!(validate_method(".method public test(VI)V", ""), vec![
+            "Parameter type cannot be void.\n'V' is only valid as a return type."
+        ]);
+        assert!(validate_method(".method public test()V", "").is_empty());
+    }
+
+    fn validate_interface(method: &str, strict: bool) -> Vec<String> {
+        let content = format!(
+            ".class public interface abstract Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n{}\n.end method\n",
+            method
+        );
+        let settings = Settings {
+            strict_interface_methods: strict,
+            ..Settings::default()
+        };
+
+        messages(validate_file_with(&content, &settings, &ClassIndex::default()))
+    }
+
+    #[test]
+    fn abstract_interface_method() {
+        assert!(validate_interface(".method public abstract run()V", true).is_empty());
+        assert!(validate_interface(".method public static of()V\n    .locals 0\n    return-void", true).is_empty());
+    }
+
+    #[test]
+    fn default_interface_method() {
+        assert!(validate_interface(".method public run()V\n    .locals 0\n    return-void", false).is_empty());
+        assert!(validate_interface(".method private helper()V\n    .locals 0\n    return-void", false).is_empty());
+    }
+
+    #[test]
+    fn concrete_interface_method() {
+        assert_eq!(validate_interface(".method public run()V\n    .locals 0\n    return-void", true), vec![
+            "Interface methods must be 'abstract' or 'static'.\nDefault and private methods need API 24."
+        ]);
+        assert_eq!(validate_interface(".method private helper()V\n    .locals 0\n    return-void", true), vec![
+            "Interface methods must be 'abstract' or 'static'.\nDefault and private methods need API 24."
+        ]);
+        assert!(validate_method(".method public run()V", "").is_empty());
+    }
+
+    fn validate_size(max_instructions: Option<u32>) -> Vec<String> {
+        let body = "    invoke-static {}, Lme/l3af/Test;->run()V\n    invoke-static {}, Lme/l3af/Test;->run()V";
+        let settings = Settings {
+            max_method_instructions: max_instructions,
+            ..Settings::default()
+        };
+
+        messages(validate_body_with(body, &settings, &ClassIndex::default()))
+    }
+
+    #[test]
+    fn method_size() {
+        assert_eq!(validate_size(Some(2)), vec![
+            "Method has 3 instructions, the maximum is 2.\nSplit it into smaller methods."
+        ]);
+        assert!(validate_size(Some(3)).is_empty());
+        assert!(validate_size(None).is_empty());
+    }
+}
@@ -1,3 +1,9 @@
+mod annotation;
+mod annotation_element;
+mod array_type;
+mod end;
+mod field;
+mod field_annotation;
 mod method;
 mod header;
 
@@ -5,22 +11,58 @@ use lspower::lsp::Diagnostic;
 
 use crate::server::lexer::Token;
 
-use self::{header::HeaderValidator, method::MethodValidator};
+use self::{
+    annotation::AnnotationValidator,
+    annotation_element::AnnotationElementValidator,
+    array_type::ArrayTypeValidator,
+    end::EndDirectiveValidator,
+    field::FieldValidator,
+    field_annotation::FieldAnnotationValidator,
+    header::HeaderValidator,
+    method::MethodValidator,
+};
 
-use super::Validator;
+use super::{ValidationContext, Validator};
 
-#[derive(Debug, Default)]
-pub struct DirectivesValidator {
-    header_validator: HeaderValidator,
-    method_validator: MethodValidator,
+#[derive(Debug)]
+pub struct DirectivesValidator<'a> {
+    header_validator:     HeaderValidator,
+    method_validator:     MethodValidator,
+    field_validator:      FieldValidator<'a>,
+    annotation_validator: AnnotationValidator,
+    element_validator:    AnnotationElementValidator<'a>,
+    end_validator:        EndDirectiveValidator,
+    array_validator:      ArrayTypeValidator,
+    detached_validator:   FieldAnnotationValidator,
 }
 
-impl Validator for DirectivesValidator {
+impl<'a> DirectivesValidator<'a> {
+    pub fn new(context: &ValidationContext<'a>) -> Self {
+        Self {
+            header_validator:     HeaderValidator::new(context.settings),
+            method_validator:     MethodValidator::new(context.settings),
+            field_validator:      FieldValidator::new(context),
+            annotation_validator: AnnotationValidator::default(),
+            element_validator:    AnnotationElementValidator::new(context),
+            end_validator:        EndDirectiveValidator::default(),
+            array_validator:      ArrayTypeValidator,
+            detached_validator:   FieldAnnotationValidator::default(),
+        }
+    }
+}
+
+impl<'a> Validator for DirectivesValidator<'a> {
     fn validate_token(&mut self, token: &Token) -> Vec<Diagnostic> {
         let mut diags = Vec::new();
 
         diags.append(&mut self.header_validator.validate_token(token));
         diags.append(&mut self.method_validator.validate_token(token));
+        diags.append(&mut self.field_validator.validate_token(token));
+        diags.append(&mut self.annotation_validator.validate_token(token));
+        diags.append(&mut self.element_validator.validate_token(token));
+        diags.append(&mut self.end_validator.validate_token(token));
+        diags.append(&mut self.array_validator.validate_token(token));
+        diags.append(&mut self.detached_validator.validate_token(token));
 
         diags
     }
@@ -29,7 +71,14 @@ impl Validator for DirectivesValidator {
         let mut diags = Vec::new();
 
         diags.append(&mut self.header_validator.validate_line(line));
+        self.method_validator.set_interface(self.header_validator.is_interface());
         diags.append(&mut self.method_validator.validate_line(line));
+        diags.append(&mut self.field_validator.validate_line(line));
+        diags.append(&mut self.annotation_validator.validate_line(line));
+        diags.append(&mut self.element_validator.validate_line(line));
+        diags.append(&mut self.end_validator.validate_line(line));
+        diags.append(&mut self.array_validator.validate_line(line));
+        diags.append(&mut self.detached_validator.validate_line(line));
 
         diags
     }
@@ -39,6 +88,12 @@ impl Validator for DirectivesValidator {
 
         diags.append(&mut self.header_validator.validate_end());
         diags.append(&mut self.method_validator.validate_end());
+        diags.append(&mut self.field_validator.validate_end());
+        diags.append(&mut self.annotation_validator.validate_end());
+        diags.append(&mut self.element_validator.validate_end());
+        diags.append(&mut self.end_validator.validate_end());
+        diags.append(&mut self.array_validator.validate_end());
+        diags.append(&mut self.detached_validator.validate_end());
 
         diags
     }
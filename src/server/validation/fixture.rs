@@ -0,0 +1,56 @@
+//! Classes for the validator tests to run against.
+
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::{validate, ValidationContext};
+use crate::server::{index::ClassIndex, settings::Settings};
+
+/// Validates a whole file, for tests of the class header or the layout of
+/// the file.
+pub fn validate_file_with(content: &str, settings: &Settings, index: &ClassIndex) -> Vec<Diagnostic> {
+    validate(content.to_string(), &ValidationContext {
+        index,
+        settings,
+    })
+    .unwrap()
+}
+
+/// Like `validate_file_with` with the default settings and an empty index.
+pub fn validate_file(content: &str) -> Vec<Diagnostic> {
+    validate_file_with(content, &Settings::default(), &ClassIndex::default())
+}
+
+/// Validates `members` declared in the `Lme/l3af/Test;` class.
+pub fn validate_class_with(members: &str, settings: &Settings, index: &ClassIndex) -> Vec<Diagnostic> {
+    let content = format!(".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n{}\n", members);
+
+    validate_file_with(&content, settings, index)
+}
+
+/// Validates `body` in a `static test(I)V` method with four locals, so the
+/// parameter is `p0` or `v4`. The method returns after `body`.
+pub fn validate_body_with(body: &str, settings: &Settings, index: &ClassIndex) -> Vec<Diagnostic> {
+    let method = format!(".method public static test(I)V\n    .locals 4\n{}\n    return-void\n.end method", body);
+
+    validate_class_with(&method, settings, index)
+}
+
+/// Like `validate_class_with` with the default settings and an empty index,
+/// returning only the messages.
+pub fn validate_class(members: &str) -> Vec<String> {
+    messages(validate_class_with(members, &Settings::default(), &ClassIndex::default()))
+}
+
+/// Like `validate_body_with` with the default settings and an empty index,
+/// returning only the messages.
+pub fn validate_body(body: &str) -> Vec<String> {
+    messages(validate_body_with(body, &Settings::default(), &ClassIndex::default()))
+}
+
+pub fn messages(diags: Vec<Diagnostic>) -> Vec<String> {
+    diags.into_iter().map(|diag| diag.message).collect()
+}
+
+pub fn severities(diags: Vec<Diagnostic>) -> Vec<(String, Option<DiagnosticSeverity>)> {
+    diags.into_iter().map(|diag| (diag.message, diag.severity)).collect()
+}
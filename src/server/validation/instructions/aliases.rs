@@ -0,0 +1,333 @@
+use std::collections::HashSet;
+
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    descriptor::parameter_register_count,
+    helper::parse_count,
+    lexer::{Token, TokenType},
+    registers::{register_accesses, Access},
+    validation::{validate_parameter_register, Category},
+};
+
+/// Flags parameter registers referred to by both their `vN` and `pN` names
+/// in methods using `.registers`, where the two overlap, and `vN` locals
+/// written over a parameter. Under `.locals`, `vN` registers past the
+/// declared locals are errors, as are `pN` registers past the parameters of
+/// the descriptor under either directive.
+#[derive(Debug, Default)]
+pub struct RegisterAliasValidator {
+    registers:  Option<u32>,
+    locals:     Option<u32>,
+    /// The parameter registers of the descriptor, `None` when it is malformed.
+    parameters: Option<u32>,
+    used:       Vec<Token>,
+    /// The `vN` registers overwritten by an instruction, as locals would be.
+    written:    Vec<Token>,
+}
+
+impl Validator for RegisterAliasValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        match (&line[0].token_type, line[0].content.as_ref()) {
+            (TokenType::Method, ".method") => {
+                self.registers = None;
+                self.locals = None;
+                self.parameters = parameter_register_count(line);
+                self.used.clear();
+                self.written.clear();
+            },
+            (TokenType::Method, ".end method") => {
+                diags.append(&mut validate_aliases(self));
+            },
+            (TokenType::Directive, ".registers") => {
+                self.registers = line
+                    .iter()
+                    .find(|token| token.token_type == TokenType::Number)
+                    .and_then(|number| parse_count(&number.content));
+            },
+            (TokenType::Directive, ".locals") => {
+                self.locals = line
+                    .iter()
+                    .find(|token| token.token_type == TokenType::Number)
+                    .and_then(|number| parse_count(&number.content));
+            },
+            (token_type, _) if token_type.is_opcode() => {
+                if let Some(locals) = self.locals {
+                    diags.append(&mut validate_locals(line, locals, self.parameters.unwrap_or(0)));
+                }
+                if let Some(parameters) = self.parameters {
+                    diags.append(&mut validate_parameters(line, parameters));
+                }
+
+                self.used.extend(
+                    line.iter()
+                        .filter(|token| token.token_type == TokenType::Register)
+                        .cloned(),
+                );
+                self.written.extend(
+                    register_accesses(line)
+                        .into_iter()
+                        .filter(|(token, access)| *access == Access::Write && token.content.starts_with('v'))
+                        .map(|(token, _)| token.clone()),
+                );
+            },
+            _ => {},
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+/// Reports the `vN` registers in `line` numbered past the `locals` the method
+/// declares, pointing at the `pN` name when one of the parameters is meant.
+fn validate_locals(line: &[Token], locals: u32, parameters: u32) -> Vec<Diagnostic> {
+    line.iter()
+        .filter(|token| token.token_type == TokenType::Register && token.content.starts_with('v'))
+        .filter_map(|token| {
+            let idx = token.content[1..].parse::<u32>().ok().filter(|idx| *idx >= locals)?;
+            let hint = match locals {
+                _ if idx - locals < parameters => format!("Use 'p{}' to refer to the parameter.", idx - locals),
+                0 => "'.locals 0' declares no local registers.".to_string(),
+                1 => "Only 'v0' is declared by '.locals 1'.".to_string(),
+                _ => format!("'.locals {}' declares 'v0' to 'v{}'.", locals, locals - 1),
+            };
+
+            Some(token.to_diagnostic(
+                format!("'{}' is out of range under '.locals {}'.\n{}", token.content, locals, hint),
+                Some(DiagnosticSeverity::Error),
+            ))
+        })
+        .collect()
+}
+
+/// Reports the `pN` registers in `line` numbered past the `parameters` the
+/// descriptor declares, as the body then doesn't match the signature.
+fn validate_parameters(line: &[Token], parameters: u32) -> Vec<Diagnostic> {
+    line.iter()
+        .filter(|token| token.token_type == TokenType::Register && token.content.starts_with('p'))
+        .filter_map(|token| validate_parameter_register(token, 0, parameters))
+        .collect()
+}
+
+fn validate_aliases(validator: &mut RegisterAliasValidator) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let parameters = validator.parameters.unwrap_or(0);
+    let registers = match validator.registers {
+        Some(registers) if registers >= parameters => registers,
+        _ => return diags,
+    };
+    let first_param = registers - parameters;
+
+    let index = |token: &Token| token.content[1..].parse::<u32>().ok();
+    let params = validator
+        .used
+        .iter()
+        .filter(|token| token.content.starts_with('p'))
+        .filter_map(index)
+        .collect::<HashSet<_>>();
+
+    let param_of = |token: &Token| match index(token) {
+        Some(idx) if idx >= first_param && idx < registers => Some(idx - first_param),
+        _ => None,
+    };
+
+    // Writing a parameter through its `vN` name is most likely a local
+    // numbered past the ones `.registers` leaves room for
+    let mut reported = HashSet::new();
+    for token in validator.written.drain(..) {
+        let param = match param_of(&token) {
+            Some(param) => param,
+            None => continue,
+        };
+
+        if !reported.insert(token.content.clone()) {
+            continue;
+        }
+
+        diags.push(token.to_diagnostic(
+            format!("'{}' overwrites parameter 'p{}'.\n{}", token.content, param, locals_hint(registers, first_param)),
+            Some(DiagnosticSeverity::Warning),
+        ));
+    }
+
+    for token in validator.used.drain(..).filter(|token| token.content.starts_with('v')) {
+        let param = match param_of(&token) {
+            Some(param) => param,
+            None => continue,
+        };
+
+        if params.contains(&param) && reported.insert(token.content.clone()) {
+            diags.push(Category::Info.tag(token.to_diagnostic(
+                format!(
+                    "'{}' is the same register as 'p{}'.\nParameter registers are usually referred to as 'pN'.",
+                    token.content, param
+                ),
+                Some(DiagnosticSeverity::Information),
+            )));
+        }
+    }
+
+    diags
+}
+
+/// Describes the registers `.registers` leaves for locals.
+fn locals_hint(registers: u32, locals: u32) -> String {
+    match locals {
+        0 => format!("'.registers {}' leaves no registers for locals.", registers),
+        1 => format!("Only 'v0' is a local register under '.registers {}'.", registers),
+        _ => format!("The local registers under '.registers {}' are 'v0' to 'v{}'.", registers, locals - 1),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_class;
+
+    #[test]
+    fn aliased_this() {
+        assert_eq!(
+            validate_class(
+                ".method public test(I)V
+    .registers 3
+
+    invoke-virtual {p0}, Lme/l3af/Test;->toString()Ljava/lang/String;
+    invoke-virtual {v1}, Lme/l3af/Test;->toString()Ljava/lang/String;
+    return-void
+.end method"
+            ),
+            vec!["'v1' is the same register as 'p0'.\nParameter registers are usually referred to as 'pN'."]
+        );
+    }
+
+    #[test]
+    fn distinct_registers() {
+        assert!(validate_class(
+            ".method public test(I)V
+    .registers 3
+
+    invoke-virtual {p0}, Lme/l3af/Test;->toString()Ljava/lang/String;
+    move v0, p1
+    return-void
+.end method"
+        )
+        .is_empty());
+        assert!(validate_class(
+            ".method public test(I)V
+    .locals 2
+
+    invoke-virtual {p0}, Lme/l3af/Test;->toString()Ljava/lang/String;
+    invoke-virtual {v1}, Lme/l3af/Test;->toString()Ljava/lang/String;
+    return-void
+.end method"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn local_out_of_range() {
+        assert_eq!(
+            validate_class(
+                ".method public test(I)V
+    .locals 2
+
+    invoke-virtual {v2}, Lme/l3af/Test;->toString()Ljava/lang/String;
+    invoke-virtual {p0}, Lme/l3af/Test;->toString()Ljava/lang/String;
+    return-void
+.end method"
+            ),
+            vec!["'v2' is out of range under '.locals 2'.\nUse 'p0' to refer to the parameter."]
+        );
+        assert_eq!(
+            validate_class(
+                ".method public static test()V
+    .locals 1
+
+    const/4 v3, 0x0
+    return-void
+.end method"
+            ),
+            vec!["'v3' is out of range under '.locals 1'.\nOnly 'v0' is declared by '.locals 1'."]
+        );
+    }
+
+    #[test]
+    fn local_over_parameter() {
+        assert_eq!(
+            validate_class(
+                ".method public static test(I)V
+    .registers 1
+
+    const/4 v0, 0x0
+    return-void
+.end method"
+            ),
+            vec!["'v0' overwrites parameter 'p0'.\n'.registers 1' leaves no registers for locals."]
+        );
+        assert_eq!(
+            validate_class(
+                ".method public test(I)V
+    .registers 4
+
+    const/4 v0, 0x0
+    add-int/lit8 v2, p1, 0x1
+    move v3, v2
+    return-void
+.end method"
+            ),
+            vec![
+                "'v2' overwrites parameter 'p0'.\nThe local registers under '.registers 4' are 'v0' to 'v1'.",
+                "'v3' overwrites parameter 'p1'.\nThe local registers under '.registers 4' are 'v0' to 'v1'.",
+            ]
+        );
+        assert_eq!(
+            validate_class(
+                ".method public static test(I)V
+    .registers 1
+
+    const/4 v0, 0x0
+    const/4 v0, 0x1
+    return-void
+.end method"
+            ),
+            vec!["'v0' overwrites parameter 'p0'.\n'.registers 1' leaves no registers for locals."]
+        );
+    }
+
+    #[test]
+    fn signature_and_body_mismatch() {
+        assert_eq!(
+            validate_class(
+                ".method public static test(IIJ)V
+    .registers 3
+
+    add-int p0, p0, p1
+    return-void
+.end method"
+            ),
+            vec!["'.registers 3' is too small for the method's parameters.\nThe parameters alone need 4 registers."]
+        );
+        assert_eq!(
+            validate_class(
+                ".method public static test(II)V
+    .locals 0
+
+    add-int p0, p1, p2
+    return-void
+.end method"
+            ),
+            vec!["'p2' is not a parameter register.\nParameters are p0..p1."]
+        );
+    }
+}
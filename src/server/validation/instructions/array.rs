@@ -0,0 +1,74 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    helper::tokens_to_diagnostic,
+    lexer::{Token, TokenType},
+};
+
+#[derive(Debug, Default)]
+pub struct ArrayAccessValidator;
+
+impl Validator for ArrayAccessValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        if !matches!(line[0].token_type, TokenType::AGet | TokenType::APut) {
+            return diags;
+        }
+
+        let opcode = &line[0].content;
+        let operands = line[1..]
+            .iter()
+            .filter(|token| token.token_type != TokenType::Space)
+            .map(|token| &token.token_type)
+            .collect::<Vec<_>>();
+
+        let is_valid = matches!(
+            operands.as_slice(),
+            [
+                TokenType::Register,
+                TokenType::CommaOp,
+                TokenType::Register,
+                TokenType::CommaOp,
+                TokenType::Register
+            ]
+        );
+
+        if !is_valid {
+            diags.push(tokens_to_diagnostic(
+                line,
+                format!("'{}' expects three registers.\n'{} vA, vB, vC'", opcode, opcode),
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_body;
+
+    #[test]
+    fn valid_array_access() {
+        assert!(validate_body("    aget-object v0, v1, v2").is_empty());
+        assert!(validate_body("    aput-wide v0, v1, v2").is_empty());
+    }
+
+    #[test]
+    fn missing_operand() {
+        assert_eq!(validate_body("    aget-object v0, v1"), vec![
+            "'aget-object' expects three registers.\n'aget-object vA, vB, vC'"
+        ]);
+    }
+}
@@ -0,0 +1,105 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    helper::tokens_to_diagnostic,
+    lexer::{Token, TokenType},
+    validation::Category,
+};
+
+#[derive(Debug, Default)]
+pub struct ConstStringValidator;
+
+impl Validator for ConstStringValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        if line[0].token_type != TokenType::ConstString {
+            return diags;
+        }
+
+        let opcode = &line[0].content;
+        let operands = line[1..]
+            .iter()
+            .filter(|token| token.token_type != TokenType::Space)
+            .collect::<Vec<_>>();
+
+        match operands.as_slice() {
+            [register, comma, string]
+                if register.token_type == TokenType::Register
+                    && comma.token_type == TokenType::CommaOp
+                    && string.token_type == TokenType::String =>
+            {
+                if opcode.ends_with("/jumbo") && string.content == "\"\"" {
+                    diags.push(Category::Info.tag(tokens_to_diagnostic(
+                        line,
+                        format!("'{}' used with an empty string.\nPlain 'const-string' is enough.", opcode),
+                        Some(DiagnosticSeverity::Information),
+                    )));
+                }
+            },
+            _ => {
+                diags.push(tokens_to_diagnostic(
+                    line,
+                    format!("'{}' expects a register and a string.\n'{} vA, \"value\"'", opcode, opcode),
+                    Some(DiagnosticSeverity::Error),
+                ));
+            },
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::DiagnosticSeverity;
+
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::fixture::validate_body_with,
+    };
+
+    fn validate_body(body: &str) -> Vec<(String, Option<DiagnosticSeverity>)> {
+        validate_body_with(body, &Settings::default(), &ClassIndex::default())
+            .into_iter()
+            .map(|diag| (diag.message, diag.severity))
+            .collect()
+    }
+
+    #[test]
+    fn valid_const_string() {
+        assert!(validate_body("    const-string v0, \"smali\"").is_empty());
+        assert!(validate_body("    const-string v0, \"\"").is_empty());
+        assert!(validate_body("    const-string/jumbo v0, \"smali\"").is_empty());
+    }
+
+    #[test]
+    fn invalid_operands() {
+        assert_eq!(validate_body("    const-string v0, 0x1"), vec![(
+            "'const-string' expects a register and a string.\n'const-string vA, \"value\"'".to_string(),
+            Some(DiagnosticSeverity::Error)
+        )]);
+        assert_eq!(validate_body("    const-string/jumbo \"smali\""), vec![(
+            "'const-string/jumbo' expects a register and a string.\n'const-string/jumbo vA, \"value\"'".to_string(),
+            Some(DiagnosticSeverity::Error)
+        )]);
+    }
+
+    #[test]
+    fn empty_jumbo_string() {
+        assert_eq!(validate_body("    const-string/jumbo v0, \"\""), vec![(
+            "'const-string/jumbo' used with an empty string.\nPlain 'const-string' is enough.".to_string(),
+            Some(DiagnosticSeverity::Information)
+        )]);
+    }
+}
@@ -0,0 +1,106 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    catch::parse_catch,
+    helper::tokens_to_diagnostic,
+    lexer::{Token, TokenType},
+};
+
+#[derive(Debug, Default)]
+pub struct ExceptionValidator {
+    in_handler: bool,
+}
+
+impl Validator for ExceptionValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+        let first = &line[0];
+
+        if first.token_type == TokenType::Label {
+            // Other labels can share the handler address, so they don't end it
+            if first.content.starts_with(":catch") {
+                self.in_handler = true;
+            }
+
+            return diags;
+        }
+
+        if first.token_type == TokenType::Catch && parse_catch(line).is_none() {
+            let usage = if first.content == ".catchall" {
+                "'.catchall {:try_start .. :try_end} :handler'"
+            } else {
+                "'.catch Lclass/Name; {:try_start .. :try_end} :handler'"
+            };
+
+            diags.push(tokens_to_diagnostic(
+                line,
+                format!("Malformed '{}' directive.\n{}", first.content, usage),
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+
+        // Debug directives such as `.line` may sit between the label and the
+        // first instruction
+        if first.content.starts_with('.') && first.token_type != TokenType::Method {
+            return diags;
+        }
+
+        if first.token_type == TokenType::Move && first.content == "move-exception" && !self.in_handler {
+            diags.push(tokens_to_diagnostic(
+                line,
+                "move-exception must be the first instruction of a catch handler.",
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+
+        self.in_handler = false;
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_body;
+
+    #[test]
+    fn catch_handler() {
+        assert!(validate_body("    :catch_0\n    move-exception v0").is_empty());
+        assert!(validate_body("    :cond_0\n    :catchall_1a\n    .line 12\n    move-exception v0").is_empty());
+    }
+
+    #[test]
+    fn misplaced_move_exception() {
+        assert_eq!(validate_body("    move-exception v0"), vec![
+            "move-exception must be the first instruction of a catch handler."
+        ]);
+        assert_eq!(validate_body("    :catch_0\n    const/4 v0, 0x0\n    move-exception v0"), vec![
+            "move-exception must be the first instruction of a catch handler."
+        ]);
+        assert_eq!(validate_body("    :cond_0\n    move-exception v0"), vec![
+            "move-exception must be the first instruction of a catch handler."
+        ]);
+    }
+
+    #[test]
+    fn malformed_catch() {
+        assert!(validate_body("    .catch Ljava/lang/Exception; {:try_start_0 .. :try_end_0} :catch_0").is_empty());
+        assert!(validate_body("    .catchall {:try_start_0 .. :try_end_0} :catchall_0").is_empty());
+
+        assert_eq!(validate_body("    .catch {:try_start_0 .. :try_end_0} :catch_0"), vec![
+            "Malformed '.catch' directive.\n'.catch Lclass/Name; {:try_start .. :try_end} :handler'"
+        ]);
+        assert_eq!(validate_body("    .catchall {:try_start_0} :catchall_0"), vec![
+            "Malformed '.catchall' directive.\n'.catchall {:try_start .. :try_end} :handler'"
+        ]);
+    }
+}
@@ -0,0 +1,170 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    descriptor::{expected_suffix, is_reference, read_type},
+    helper::tokens_to_diagnostic,
+    index::ClassIndex,
+    lexer::{Token, TokenType},
+};
+
+#[derive(Debug)]
+pub struct FieldAccessValidator<'a> {
+    index: &'a ClassIndex,
+}
+
+impl<'a> FieldAccessValidator<'a> {
+    pub fn new(index: &'a ClassIndex) -> Self {
+        Self {
+            index,
+        }
+    }
+}
+
+impl<'a> Validator for FieldAccessValidator<'a> {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        let requires_static = match line[0].token_type {
+            TokenType::SGet | TokenType::SPut => true,
+            TokenType::IGet | TokenType::IPut => false,
+            _ => return diags,
+        };
+
+        let access_idx = match line.iter().position(|token| token.token_type == TokenType::FieldAccess) {
+            Some(idx) if idx > 0 && line[idx - 1].token_type == TokenType::Class => idx,
+            _ => return diags,
+        };
+
+        let class_name = &line[access_idx - 1].content;
+        let field_name = line[access_idx].content.trim_start_matches("->").trim_end_matches(':');
+
+        if matches!(line[0].token_type, TokenType::IPut | TokenType::SPut) {
+            if let Some((field_type, consumed)) = read_type(&line[access_idx + 1..]) {
+                let opcode = &line[0].content;
+                let suffix = &opcode[4..];
+
+                if !suffix_matches(suffix, &field_type) {
+                    diags.push(tokens_to_diagnostic(
+                        &line[access_idx - 1..access_idx + 1 + consumed],
+                        format!(
+                            "'{}' cannot store to a field of type '{}'.\nUse '{}{}' instead.",
+                            opcode,
+                            field_type,
+                            &opcode[..4],
+                            expected_suffix(&field_type)
+                        ),
+                        Some(DiagnosticSeverity::Error),
+                    ));
+                }
+            }
+        }
+
+        let field = match self.index.get(class_name).and_then(|class| class.field(field_name)) {
+            Some(field) => field,
+            None => return diags,
+        };
+
+        if field.is_static != requires_static {
+            let reference = &line[access_idx - 1..];
+            let opcode = &line[0].content;
+
+            diags.push(tokens_to_diagnostic(
+                reference,
+                if requires_static {
+                    format!("'{}' requires a static field, '{}' is an instance field.", opcode, field_name)
+                } else {
+                    format!("'{}' requires an instance field, '{}' is static.", opcode, field_name)
+                },
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+pub(super) fn suffix_matches(suffix: &str, descriptor: &str) -> bool {
+    match suffix {
+        "-object" | "-string" => is_reference(descriptor),
+        _ => suffix == expected_suffix(descriptor),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{DiagnosticSeverity, Url};
+
+    use crate::server::{index::ClassIndex, settings::Settings, validation::fixture::validate_body_with};
+
+    const FIXTURE: &str = ".class public Lme/l3af/Fixture;
+.super Ljava/lang/Object;
+
+.field public static instance:Lme/l3af/Fixture;
+
+.field public count:I
+";
+
+    fn validate_body(body: &str) -> Vec<String> {
+        let mut index = ClassIndex::default();
+        index.index_content(&Url::parse("file:///Fixture.smali").unwrap(), FIXTURE);
+
+        validate_body_with(body, &Settings::default(), &index)
+            .into_iter()
+            .filter(|diag| diag.severity == Some(DiagnosticSeverity::Error))
+            .map(|diag| diag.message)
+            .collect()
+    }
+
+    #[test]
+    fn matching_access() {
+        assert!(validate_body("    sget-object v0, Lme/l3af/Fixture;->instance:Lme/l3af/Fixture;").is_empty());
+        assert!(validate_body("    iget v0, v0, Lme/l3af/Fixture;->count:I").is_empty());
+        assert!(validate_body("    iput v0, v0, Lme/l3af/Fixture;->count:I").is_empty());
+    }
+
+    #[test]
+    fn static_opcode_on_instance_field() {
+        assert_eq!(validate_body("    sget v0, Lme/l3af/Fixture;->count:I"), vec![
+            "'sget' requires a static field, 'count' is an instance field."
+        ]);
+    }
+
+    #[test]
+    fn instance_opcode_on_static_field() {
+        assert_eq!(
+            validate_body("    iput-object v0, v0, Lme/l3af/Fixture;->instance:Lme/l3af/Fixture;"),
+            vec!["'iput-object' requires an instance field, 'instance' is static."]
+        );
+    }
+
+    #[test]
+    fn store_opcode_matches_field_type() {
+        assert!(validate_body("    iput-wide v0, v0, Lme/l3af/Unknown;->total:J").is_empty());
+        assert!(validate_body("    sput-boolean v0, Lme/l3af/Unknown;->enabled:Z").is_empty());
+        assert!(validate_body("    iput-object v0, v0, Lme/l3af/Unknown;->names:[Ljava/lang/String;").is_empty());
+    }
+
+    #[test]
+    fn store_opcode_mismatches_field_type() {
+        assert_eq!(validate_body("    iput-object v0, v0, Lfoo;->bar:I"), vec![
+            "'iput-object' cannot store to a field of type 'I'.\nUse 'iput' instead."
+        ]);
+        assert_eq!(validate_body("    sput v0, Lfoo;->bar:J"), vec![
+            "'sput' cannot store to a field of type 'J'.\nUse 'sput-wide' instead."
+        ]);
+    }
+
+    #[test]
+    fn unindexed_class_is_skipped() {
+        assert!(validate_body("    sget v0, Lme/l3af/Unknown;->count:I").is_empty());
+    }
+}
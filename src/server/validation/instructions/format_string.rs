@@ -0,0 +1,166 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    lexer::{Token, TokenType},
+    settings::Settings,
+};
+
+/// Methods whose first string argument is a `java.util.Formatter` pattern.
+const FORMAT_METHODS: &[&str] = &["->format(", "->printf("];
+
+/// Conversions accepted by `java.util.Formatter`, `t` and `T` take a
+/// second character naming the date or time field.
+const CONVERSIONS: &str = "bBhHsScCdoxXeEfgGaAtT%n";
+
+/// Checks the format specifiers of a `const-string` that is passed straight
+/// to `format` or `printf` by the next instruction.
+#[derive(Debug)]
+pub struct FormatStringValidator {
+    enabled:      bool,
+    /// The register and literal of the previous instruction, when it was a
+    /// `const-string`.
+    const_string: Option<(String, Token)>,
+}
+
+impl FormatStringValidator {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            enabled:      settings.lint_format_strings,
+            const_string: None,
+        }
+    }
+}
+
+impl Validator for FormatStringValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        // Directives such as `.line` don't separate the two instructions
+        if !self.enabled || !line[0].token_type.is_opcode() {
+            return diags;
+        }
+
+        let const_string = self.const_string.take();
+
+        if line[0].token_type == TokenType::ConstString {
+            let register = line.iter().find(|token| token.token_type == TokenType::Register);
+            let string = line.iter().find(|token| token.token_type == TokenType::String);
+
+            if let (Some(register), Some(string)) = (register, string) {
+                self.const_string = Some((register.content.clone(), string.clone()));
+            }
+
+            return diags;
+        }
+
+        let (register, string) = match const_string {
+            Some(const_string) if line[0].token_type == TokenType::Invoke => const_string,
+            _ => return diags,
+        };
+
+        let is_format = line
+            .iter()
+            .any(|token| token.token_type == TokenType::MethodCall && FORMAT_METHODS.contains(&token.content.as_str()));
+        let is_argument = line
+            .iter()
+            .any(|token| token.token_type == TokenType::Register && token.content == register);
+
+        if !is_format || !is_argument {
+            return diags;
+        }
+
+        let pattern = string.content.trim_matches('"');
+        if let Some(specifier) = malformed_specifier(pattern) {
+            diags.push(string.to_diagnostic(
+                format!("Malformed format specifier '{}'.\nUse '%%' for a literal percent sign.", specifier),
+                Some(DiagnosticSeverity::Warning),
+            ));
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+/// Returns the first format specifier in `pattern` that doesn't end in a
+/// valid conversion.
+fn malformed_specifier(pattern: &str) -> Option<&str> {
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('%') {
+        let specifier = &rest[start..];
+
+        // Index, flags, width and precision, e.g. `%1$-08.2f`
+        let len = 1 + specifier[1..]
+            .find(|c: char| !(c.is_ascii_digit() || "$-#+ 0,(<.".contains(c)))
+            .unwrap_or(specifier.len() - 1);
+
+        let mut chars = specifier[len..].chars();
+        let end = match (chars.next(), chars.next()) {
+            (Some('t'), Some(field)) | (Some('T'), Some(field)) if field.is_ascii_alphabetic() => len + 2,
+            (Some('t'), _) | (Some('T'), _) => return Some(&specifier[..len + 1]),
+            (Some(c), _) if CONVERSIONS.contains(c) => len + 1,
+            (Some(c), _) => return Some(&specifier[..len + c.len_utf8()]),
+            (None, _) => return Some(specifier),
+        };
+
+        rest = &specifier[end..];
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::fixture::{messages, validate_body_with},
+    };
+
+    fn validate_body(body: &str, enabled: bool) -> Vec<String> {
+        let settings = Settings {
+            lint_format_strings: enabled,
+            ..Settings::default()
+        };
+
+        messages(validate_body_with(body, &settings, &ClassIndex::default()))
+    }
+
+    fn format_call(pattern: &str) -> String {
+        format!(
+            "    const-string v0, \"{}\"\n    invoke-static {{v0, v1}}, \
+             Ljava/lang/String;->format(Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/String;",
+            pattern
+        )
+    }
+
+    #[test]
+    fn dangling_percent() {
+        assert_eq!(validate_body(&format_call("%"), true), vec![
+            "Malformed format specifier '%'.\nUse '%%' for a literal percent sign."
+        ]);
+        assert_eq!(validate_body(&format_call("50%!"), true), vec![
+            "Malformed format specifier '%!'.\nUse '%%' for a literal percent sign."
+        ]);
+        assert!(validate_body(&format_call("%"), false).is_empty());
+    }
+
+    #[test]
+    fn valid_specifiers() {
+        assert!(validate_body(&format_call("%s: %1$-08.2f%% %tY%n"), true).is_empty());
+        assert!(validate_body(
+            "    const-string v0, \"%\"\n    invoke-static {v0}, Lfoo;->bar(Ljava/lang/String;)V",
+            true
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    lexer::{Token, TokenType},
+    opcodes::split_operands,
+};
+
+/// Checks that the literal of `const/high16` and `const-wide/high16` is the
+/// full value the register ends up holding, with the bits below the ones the
+/// instruction encodes left as zero.
+#[derive(Debug, Default)]
+pub struct HighLiteralValidator;
+
+impl Validator for HighLiteralValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        // Only the top 16 bits of the value are encoded
+        let low_bits = match line[0].content.as_str() {
+            "const/high16" => 16,
+            "const-wide/high16" => 48,
+            _ => return diags,
+        };
+
+        let operands = split_operands(line);
+        let literal = match operands.as_slice() {
+            [_, [literal]] if literal.token_type == TokenType::Number => literal,
+            _ => return diags,
+        };
+
+        let (sign, digits) = match literal.content.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", literal.content.as_str()),
+        };
+        let value = match digits.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => digits.parse(),
+        };
+
+        // Out of range literals are left to the assembler
+        let value = match value {
+            Ok(value) if value.trailing_zeros() < low_bits => value,
+            _ => return diags,
+        };
+
+        let mut message = format!(
+            "'{}' only encodes the high 16 bits, but '{}' has its low {} bits set.",
+            line[0].content, literal.content, low_bits
+        );
+        // A value that fits in 16 bits is most likely the encoded bits
+        // themselves rather than the value they produce
+        if value <= 0xffff {
+            message.push_str(&format!("\nUse '{}0x{:x}' instead.", sign, value << low_bits));
+        }
+
+        diags.push(literal.to_diagnostic(message, Some(DiagnosticSeverity::Warning)));
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_body;
+
+    #[test]
+    fn aligned_literals() {
+        assert!(validate_body("    const/high16 v0, 0x3f800000").is_empty());
+        assert!(validate_body("    const/high16 v0, -0x10000\n    const/high16 v1, 0x0").is_empty());
+        assert!(validate_body("    const-wide/high16 v0, 0x4000000000000000").is_empty());
+    }
+
+    #[test]
+    fn encoded_bits_only() {
+        assert_eq!(validate_body("    const/high16 v0, 0x3f80"), vec![
+            "'const/high16' only encodes the high 16 bits, but '0x3f80' has its low 16 bits set.\nUse '0x3f800000' \
+             instead."
+        ]);
+        assert_eq!(validate_body("    const-wide/high16 v0, -0x4000"), vec![
+            "'const-wide/high16' only encodes the high 16 bits, but '-0x4000' has its low 48 bits set.\nUse \
+             '-0x4000000000000000' instead."
+        ]);
+    }
+
+    #[test]
+    fn unaligned_literal() {
+        assert_eq!(validate_body("    const/high16 v0, 0x3f801000"), vec![
+            "'const/high16' only encodes the high 16 bits, but '0x3f801000' has its low 16 bits set."
+        ]);
+    }
+}
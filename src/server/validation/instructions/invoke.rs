@@ -0,0 +1,130 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    helper::tokens_to_diagnostic,
+    index::ClassIndex,
+    lexer::{Token, TokenType},
+};
+
+/// Checks that `invoke-interface` targets interfaces and `invoke-virtual`
+/// targets classes, for owners found in the workspace index.
+#[derive(Debug)]
+pub struct InvokeKindValidator<'a> {
+    index: &'a ClassIndex,
+}
+
+impl<'a> InvokeKindValidator<'a> {
+    pub fn new(index: &'a ClassIndex) -> Self {
+        Self {
+            index,
+        }
+    }
+}
+
+impl<'a> Validator for InvokeKindValidator<'a> {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        let opcode = &line[0].content;
+        let (kind, range) = match opcode.split_once('/') {
+            Some((kind, range)) if line[0].token_type == TokenType::Invoke => (kind, format!("/{}", range)),
+            None if line[0].token_type == TokenType::Invoke => (opcode.as_str(), String::new()),
+            _ => return diags,
+        };
+
+        let call_idx = match line.iter().position(|token| token.token_type == TokenType::MethodCall) {
+            Some(idx) if idx > 0 && line[idx - 1].token_type == TokenType::Class => idx,
+            _ => return diags,
+        };
+
+        let owner = &line[call_idx - 1];
+        let is_interface = match self.index.get(&owner.content) {
+            Some(class) => class.is_interface,
+            None => return diags,
+        };
+
+        let message = match kind {
+            "invoke-interface" if !is_interface => Some(format!(
+                "'{}' targets '{}', which is a class.\nUse 'invoke-virtual{}' instead.",
+                opcode, owner.content, range
+            )),
+            "invoke-virtual" if is_interface => Some(format!(
+                "'{}' targets '{}', which is an interface.\nUse 'invoke-interface{}' instead.",
+                opcode, owner.content, range
+            )),
+            _ => None,
+        };
+
+        if let Some(message) = message {
+            diags.push(tokens_to_diagnostic(
+                &line[call_idx - 1..=call_idx],
+                message,
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::Url;
+
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::fixture::{messages, validate_body_with},
+    };
+
+    const CLASS: &str = ".class public Lme/l3af/Fixture;
+.super Ljava/lang/Object;
+
+.method public run()V
+    .locals 0
+    return-void
+.end method
+";
+
+    const INTERFACE: &str = ".class public interface abstract Lme/l3af/Runnable;
+.super Ljava/lang/Object;
+
+.method public abstract run()V
+.end method
+";
+
+    fn validate_body(body: &str) -> Vec<String> {
+        let mut index = ClassIndex::default();
+        index.index_content(&Url::parse("file:///Fixture.smali").unwrap(), CLASS);
+        index.index_content(&Url::parse("file:///Runnable.smali").unwrap(), INTERFACE);
+
+        messages(validate_body_with(body, &Settings::default(), &index))
+    }
+
+    #[test]
+    fn matching_kinds() {
+        assert!(validate_body("    invoke-virtual {v0}, Lme/l3af/Fixture;->run()V").is_empty());
+        assert!(validate_body("    invoke-interface {v0}, Lme/l3af/Runnable;->run()V").is_empty());
+        assert!(validate_body("    invoke-interface {v0}, Lfoo/Unknown;->run()V").is_empty());
+    }
+
+    #[test]
+    fn mismatched_kinds() {
+        assert_eq!(validate_body("    invoke-interface {v0}, Lme/l3af/Fixture;->run()V"), vec![
+            "'invoke-interface' targets 'Lme/l3af/Fixture;', which is a class.\nUse 'invoke-virtual' instead."
+        ]);
+        assert_eq!(validate_body("    invoke-virtual/range {v0 .. v0}, Lme/l3af/Runnable;->run()V"), vec![
+            "'invoke-virtual/range' targets 'Lme/l3af/Runnable;', which is an interface.\nUse \
+             'invoke-interface/range' instead."
+        ]);
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::lexer::{Token, TokenType};
+
+#[derive(Debug, Default)]
+pub struct LabelValidator {
+    /// The first definition of each label in the current method.
+    defined:    HashMap<String, Token>,
+    referenced: Vec<Token>,
+    in_payload: bool,
+}
+
+impl Validator for LabelValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        match (&line[0].token_type, line[0].content.as_ref()) {
+            (TokenType::Method, ".method") => {
+                self.defined.clear();
+                self.referenced.clear();
+                self.in_payload = false;
+            },
+            (TokenType::Method, ".end method") => {
+                diags.append(&mut validate_targets(self));
+            },
+            (TokenType::SwitchPayload, directive) => {
+                self.in_payload = !directive.starts_with(".end");
+            },
+            // The targets of a switch payload are references, not definitions
+            (token_type, _) if self.in_payload || token_type.is_branch() => {
+                self.referenced.extend(
+                    line.iter()
+                        .filter(|token| token.token_type == TokenType::Label)
+                        .cloned(),
+                );
+            },
+            (TokenType::Label, _) => {
+                let label = &line[0];
+
+                if let Some(first) = self.defined.get(&label.content) {
+                    diags.push(first.to_diagnostic("Label defined here.", Some(DiagnosticSeverity::Hint)));
+                    diags.push(label.to_diagnostic(
+                        format!("Label '{}' is already defined in this method.", label.content),
+                        Some(DiagnosticSeverity::Error),
+                    ));
+                } else {
+                    self.defined.insert(label.content.clone(), label.clone());
+                }
+            },
+            _ => {},
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+fn validate_targets(validator: &mut LabelValidator) -> Vec<Diagnostic> {
+    let defined = &validator.defined;
+
+    validator
+        .referenced
+        .drain(..)
+        .filter(|label| !defined.contains_key(&label.content))
+        .map(|label| {
+            label.to_diagnostic(
+                format!("Label '{}' is not defined in this method.", label.content),
+                Some(DiagnosticSeverity::Error),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::{validate_body, validate_class};
+
+    #[test]
+    fn defined_targets() {
+        assert!(validate_body("    :goto_0\n    goto :goto_0").is_empty());
+        assert!(validate_body("    goto/16 :goto_1\n    :goto_1").is_empty());
+        assert!(validate_body("    if-eqz p0, :cond_0\n    goto/32 :goto_0\n    :cond_0\n    :goto_0").is_empty());
+    }
+
+    #[test]
+    fn undefined_targets() {
+        assert_eq!(validate_body("    goto :goto_0"), vec!["Label ':goto_0' is not defined in this method."]);
+        assert_eq!(validate_body("    if-nez p0, :cond_1\n    :cond_0"), vec![
+            "Label ':cond_1' is not defined in this method."
+        ]);
+    }
+
+    #[test]
+    fn duplicate_definitions() {
+        assert_eq!(validate_body("    :goto_0\n    goto :goto_0\n    :goto_0"), vec![
+            "Label defined here.",
+            "Label ':goto_0' is already defined in this method."
+        ]);
+    }
+
+    #[test]
+    fn labels_are_method_scoped() {
+        let methods = ".method public static a()V\n    .locals 0\n    :goto_0\n    return-void\n.end method\n\n.method \
+                       public static b()V\n    .locals 0\n    goto :goto_0\n.end method";
+
+        assert_eq!(validate_class(methods), vec!["Label ':goto_0' is not defined in this method."]);
+    }
+}
@@ -0,0 +1,166 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    descriptor::{parameter_types, read_type, return_type},
+    helper::tokens_to_diagnostic,
+    lexer::{Token, TokenType},
+    opcodes::{lookup, split_operands, Operand},
+};
+
+/// Checks that method references parse as `Lclass;->name(params)Return`,
+/// explaining which part is missing rather than rejecting the operand as a
+/// whole.
+#[derive(Debug, Default)]
+pub struct MethodReferenceValidator;
+
+impl Validator for MethodReferenceValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        let opcode = match lookup(&line[0].content) {
+            Some(opcode) if line[0].token_type.is_opcode() => opcode,
+            _ => return diags,
+        };
+
+        // A wrong operand count is reported by the operand validator
+        let operands = split_operands(line);
+        if operands.len() != opcode.operands.len() {
+            return diags;
+        }
+
+        for (tokens, expected) in operands.into_iter().zip(opcode.operands) {
+            if *expected != Operand::MethodRef || !is_reference_attempt(tokens) {
+                continue;
+            }
+
+            if let Some(message) = reference_error(tokens) {
+                diags.push(tokens_to_diagnostic(tokens, message, Some(DiagnosticSeverity::Error)));
+            }
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+/// Whether `tokens` looks like it was meant as a method reference, starting
+/// with a class or naming a method. Other operands are reported by the
+/// operand validator instead.
+pub fn is_reference_attempt(tokens: &[Token]) -> bool {
+    read_type(tokens).is_some()
+        || tokens
+            .iter()
+            .any(|token| matches!(token.token_type, TokenType::MethodCall | TokenType::MethodName))
+}
+
+/// Describes what is wrong with the method reference in `tokens`, `None`
+/// when it is complete.
+fn reference_error(tokens: &[Token]) -> Option<String> {
+    let text = tokens.iter().map(|token| token.content.as_str()).collect::<String>();
+
+    let (owner, consumed) = match read_type(tokens) {
+        Some(owner) => owner,
+        None => {
+            return Some(format!(
+                "Method reference '{}' must start with the declaring class.\n'Lclass/Name;->method()V'",
+                text
+            ))
+        },
+    };
+
+    let rest = &tokens[consumed..];
+    match rest.first() {
+        Some(call) if call.token_type == TokenType::MethodCall => {},
+        Some(name) if name.token_type == TokenType::MethodName => {
+            return Some(format!(
+                "Missing '->' between '{}' and '{}'.",
+                owner,
+                name.content.trim_end_matches('(')
+            ));
+        },
+        // `->name` without a `(` doesn't lex as a method call
+        Some(arrow) if arrow.content.starts_with('-') => {
+            return Some(format!(
+                "Method reference '{}' is missing its signature.\nExpected '(params)ReturnType' after the method name.",
+                text
+            ));
+        },
+        _ => return Some(format!("Missing method name after '{}'.\n'Lclass/Name;->method()V'", owner)),
+    }
+
+    let closing = rest.iter().filter(|token| token.content == ")").count();
+    if closing != 1 || rest[1..].iter().any(|token| token.content == "(") {
+        return Some(format!("Unbalanced parentheses in method reference '{}'.", text));
+    }
+
+    if parameter_types(rest).is_none() {
+        return Some(format!("Invalid parameter types in method reference '{}'.", text));
+    }
+
+    let after = text.splitn(2, ')').nth(1).unwrap_or_default();
+    match return_type(rest) {
+        Some(descriptor) if descriptor == after => None,
+        _ if after.is_empty() => Some(format!(
+            "Method reference '{}' is missing its return type.\nUse 'V' for methods that return nothing.",
+            text
+        )),
+        _ => Some(format!("Invalid return type '{}' in method reference.", after)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_body;
+
+    #[test]
+    fn complete_reference() {
+        assert!(validate_body("    invoke-static {v0, v1}, Lfoo;->bar(I[Ljava/lang/String;)[J").is_empty());
+        assert!(validate_body("    invoke-static {}, Lfoo;-><clinit>()V").is_empty());
+    }
+
+    #[test]
+    fn missing_return_type() {
+        assert_eq!(validate_body("    invoke-static {v0}, Lfoo;->bar(I)"), vec![
+            "Method reference 'Lfoo;->bar(I)' is missing its return type.\nUse 'V' for methods that return nothing."
+        ]);
+    }
+
+    #[test]
+    fn missing_signature() {
+        assert_eq!(validate_body("    invoke-static {}, Lfoo;->bar"), vec![
+            "Method reference 'Lfoo;->bar' is missing its signature.\nExpected '(params)ReturnType' after the method \
+             name."
+        ]);
+    }
+
+    #[test]
+    fn malformed_reference() {
+        assert_eq!(validate_body("    invoke-static {}, Lfoo;bar()V"), vec![
+            "Missing '->' between 'Lfoo;' and 'bar'."
+        ]);
+        assert_eq!(validate_body("    invoke-static {v0}, Lfoo;->bar(IV"), vec![
+            "Unbalanced parentheses in method reference 'Lfoo;->bar(IV'."
+        ]);
+        assert_eq!(validate_body("    invoke-static {}, Lfoo;->bar())V"), vec![
+            "Unbalanced parentheses in method reference 'Lfoo;->bar())V'."
+        ]);
+    }
+
+    #[test]
+    fn invalid_types() {
+        assert_eq!(validate_body("    invoke-static {v0}, Lfoo;->bar(v0)V"), vec![
+            "Invalid parameter types in method reference 'Lfoo;->bar(v0)V'."
+        ]);
+        assert_eq!(validate_body("    invoke-static {}, Lfoo;->bar()VI"), vec![
+            "Invalid return type 'VI' in method reference."
+        ]);
+    }
+}
@@ -0,0 +1,169 @@
+mod aliases;
+mod array;
+mod const_string;
+mod exception;
+mod field;
+mod format_string;
+mod high16;
+mod invoke;
+mod labels;
+mod method_ref;
+mod move_result;
+mod operands;
+mod registers;
+mod strings;
+mod switch;
+mod try_branch;
+mod type_operand;
+mod unresolved;
+
+use lspower::lsp::Diagnostic;
+
+use crate::server::lexer::Token;
+
+use self::{
+    aliases::RegisterAliasValidator,
+    array::ArrayAccessValidator,
+    const_string::ConstStringValidator,
+    exception::ExceptionValidator,
+    field::FieldAccessValidator,
+    format_string::FormatStringValidator,
+    high16::HighLiteralValidator,
+    invoke::InvokeKindValidator,
+    labels::LabelValidator,
+    method_ref::MethodReferenceValidator,
+    move_result::MoveResultValidator,
+    operands::OperandValidator,
+    registers::RegisterValidator,
+    strings::StringLiteralValidator,
+    switch::SwitchValidator,
+    try_branch::TryBranchValidator,
+    type_operand::TypeOperandValidator,
+    unresolved::UnresolvedClassValidator,
+};
+
+use super::{ValidationContext, Validator};
+
+#[derive(Debug)]
+pub struct InstructionsValidator<'a> {
+    field_validator:     FieldAccessValidator<'a>,
+    register_validator:  RegisterValidator,
+    exception_validator: ExceptionValidator,
+    string_validator:    ConstStringValidator,
+    label_validator:     LabelValidator,
+    array_validator:     ArrayAccessValidator,
+    alias_validator:     RegisterAliasValidator,
+    operand_validator:   OperandValidator,
+    literal_validator:   StringLiteralValidator,
+    type_validator:      TypeOperandValidator,
+    format_validator:    FormatStringValidator,
+    invoke_validator:    InvokeKindValidator<'a>,
+    switch_validator:    SwitchValidator,
+    result_validator:    MoveResultValidator,
+    high16_validator:    HighLiteralValidator,
+    reference_validator: MethodReferenceValidator,
+    try_validator:       TryBranchValidator,
+    class_validator:     UnresolvedClassValidator<'a>,
+}
+
+impl<'a> InstructionsValidator<'a> {
+    pub fn new(context: &ValidationContext<'a>) -> Self {
+        Self {
+            field_validator:     FieldAccessValidator::new(context.index),
+            register_validator:  RegisterValidator::new(context.settings),
+            exception_validator: ExceptionValidator::default(),
+            string_validator:    ConstStringValidator,
+            label_validator:     LabelValidator::default(),
+            array_validator:     ArrayAccessValidator,
+            alias_validator:     RegisterAliasValidator::default(),
+            operand_validator:   OperandValidator,
+            literal_validator:   StringLiteralValidator,
+            type_validator:      TypeOperandValidator,
+            format_validator:    FormatStringValidator::new(context.settings),
+            invoke_validator:    InvokeKindValidator::new(context.index),
+            switch_validator:    SwitchValidator::default(),
+            result_validator:    MoveResultValidator::default(),
+            high16_validator:    HighLiteralValidator,
+            reference_validator: MethodReferenceValidator,
+            try_validator:       TryBranchValidator::new(context.settings),
+            class_validator:     UnresolvedClassValidator::new(context),
+        }
+    }
+}
+
+impl<'a> Validator for InstructionsValidator<'a> {
+    fn validate_token(&mut self, token: &Token) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        diags.append(&mut self.field_validator.validate_token(token));
+        diags.append(&mut self.register_validator.validate_token(token));
+        diags.append(&mut self.exception_validator.validate_token(token));
+        diags.append(&mut self.string_validator.validate_token(token));
+        diags.append(&mut self.label_validator.validate_token(token));
+        diags.append(&mut self.array_validator.validate_token(token));
+        diags.append(&mut self.alias_validator.validate_token(token));
+        diags.append(&mut self.operand_validator.validate_token(token));
+        diags.append(&mut self.literal_validator.validate_token(token));
+        diags.append(&mut self.type_validator.validate_token(token));
+        diags.append(&mut self.format_validator.validate_token(token));
+        diags.append(&mut self.invoke_validator.validate_token(token));
+        diags.append(&mut self.switch_validator.validate_token(token));
+        diags.append(&mut self.result_validator.validate_token(token));
+        diags.append(&mut self.high16_validator.validate_token(token));
+        diags.append(&mut self.reference_validator.validate_token(token));
+        diags.append(&mut self.try_validator.validate_token(token));
+        diags.append(&mut self.class_validator.validate_token(token));
+
+        diags
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        diags.append(&mut self.field_validator.validate_line(line));
+        diags.append(&mut self.register_validator.validate_line(line));
+        diags.append(&mut self.exception_validator.validate_line(line));
+        diags.append(&mut self.string_validator.validate_line(line));
+        diags.append(&mut self.label_validator.validate_line(line));
+        diags.append(&mut self.array_validator.validate_line(line));
+        diags.append(&mut self.alias_validator.validate_line(line));
+        diags.append(&mut self.operand_validator.validate_line(line));
+        diags.append(&mut self.literal_validator.validate_line(line));
+        diags.append(&mut self.type_validator.validate_line(line));
+        diags.append(&mut self.format_validator.validate_line(line));
+        diags.append(&mut self.invoke_validator.validate_line(line));
+        diags.append(&mut self.switch_validator.validate_line(line));
+        diags.append(&mut self.result_validator.validate_line(line));
+        diags.append(&mut self.high16_validator.validate_line(line));
+        diags.append(&mut self.reference_validator.validate_line(line));
+        diags.append(&mut self.try_validator.validate_line(line));
+        diags.append(&mut self.class_validator.validate_line(line));
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        diags.append(&mut self.field_validator.validate_end());
+        diags.append(&mut self.register_validator.validate_end());
+        diags.append(&mut self.exception_validator.validate_end());
+        diags.append(&mut self.string_validator.validate_end());
+        diags.append(&mut self.label_validator.validate_end());
+        diags.append(&mut self.array_validator.validate_end());
+        diags.append(&mut self.alias_validator.validate_end());
+        diags.append(&mut self.operand_validator.validate_end());
+        diags.append(&mut self.literal_validator.validate_end());
+        diags.append(&mut self.type_validator.validate_end());
+        diags.append(&mut self.format_validator.validate_end());
+        diags.append(&mut self.invoke_validator.validate_end());
+        diags.append(&mut self.switch_validator.validate_end());
+        diags.append(&mut self.result_validator.validate_end());
+        diags.append(&mut self.high16_validator.validate_end());
+        diags.append(&mut self.reference_validator.validate_end());
+        diags.append(&mut self.try_validator.validate_end());
+        diags.append(&mut self.class_validator.validate_end());
+
+        diags
+    }
+}
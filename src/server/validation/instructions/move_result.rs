@@ -0,0 +1,110 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    helper::tokens_to_diagnostic,
+    lexer::{Token, TokenType},
+};
+
+/// Checks that each `move-result` directly follows the invoke or
+/// `filled-new-array` whose result it moves, with no other instruction or
+/// branch target in between.
+///
+/// The labels closing a try block only mark a range, baksmali places them
+/// and their `.catch` right after the invoke, so they are allowed.
+#[derive(Debug, Default)]
+pub struct MoveResultValidator {
+    /// The first token of the previous instruction or label in the method.
+    previous: Option<Token>,
+}
+
+impl Validator for MoveResultValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+        let first = &line[0];
+
+        if first.token_type == TokenType::Method {
+            self.previous = None;
+
+            return diags;
+        }
+
+        // Debug directives such as `.line` don't produce any code
+        if first.content.starts_with('.') || is_try_label(first) {
+            return diags;
+        }
+
+        let previous = self.previous.replace(first.clone());
+        if first.token_type != TokenType::Move || !first.content.starts_with("move-result") {
+            return diags;
+        }
+
+        let message = match previous {
+            Some(previous) if matches!(previous.token_type, TokenType::Invoke | TokenType::FilledNewArray) => {
+                return diags;
+            },
+            Some(previous) => format!(
+                "'{}' must immediately follow an invoke or 'filled-new-array', found '{}'.",
+                first.content, previous.content
+            ),
+            None => format!("'{}' must immediately follow an invoke or 'filled-new-array'.", first.content),
+        };
+
+        diags.push(tokens_to_diagnostic(line, message, Some(DiagnosticSeverity::Error)));
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+fn is_try_label(token: &Token) -> bool {
+    token.token_type == TokenType::Label
+        && (token.content.starts_with(":try_start") || token.content.starts_with(":try_end"))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_body;
+
+    #[test]
+    fn tight_pairing() {
+        assert!(validate_body("    invoke-static {}, Lfoo;->bar()I\n    move-result v0").is_empty());
+        assert!(validate_body("    invoke-static {}, Lfoo;->bar()I\n    .line 12\n    move-result v0").is_empty());
+        assert!(validate_body("    filled-new-array {v0, v1}, [I\n    move-result-object v0").is_empty());
+    }
+
+    #[test]
+    fn try_end_before_result() {
+        assert!(validate_body(
+            "    :try_start_0\n    invoke-static {}, Lfoo;->bar()I\n    :try_end_0\n    .catch Ljava/lang/Exception; \
+             {:try_start_0 .. :try_end_0} :catch_0\n    move-result v0\n    :catch_0"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn intervening_instruction() {
+        assert_eq!(
+            validate_body("    invoke-static {}, Lfoo;->bar()I\n    const/4 v1, 0x0\n    move-result v0"),
+            vec!["'move-result' must immediately follow an invoke or 'filled-new-array', found 'const/4'."]
+        );
+        assert_eq!(
+            validate_body("    invoke-static {}, Lfoo;->bar()I\n    :cond_0\n    move-result v0"),
+            vec!["'move-result' must immediately follow an invoke or 'filled-new-array', found ':cond_0'."]
+        );
+    }
+
+    #[test]
+    fn no_preceding_instruction() {
+        assert_eq!(validate_body("    move-result v0"), vec![
+            "'move-result' must immediately follow an invoke or 'filled-new-array'."
+        ]);
+    }
+}
@@ -0,0 +1,190 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::{method_ref::is_reference_attempt, Validator};
+use crate::server::{
+    helper::tokens_to_diagnostic,
+    lexer::{Token, TokenType},
+    opcodes::{classify, lookup, split_operands, Operand},
+    registers::{hex_registers, register_number, register_range},
+};
+
+/// Checks the operands of every instruction against the shape declared for
+/// its opcode in the catalog.
+#[derive(Debug, Default)]
+pub struct OperandValidator;
+
+impl Validator for OperandValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        // These have their own validators with more specific messages
+        if matches!(
+            line[0].token_type,
+            TokenType::AGet | TokenType::APut | TokenType::ConstString | TokenType::InstanceOf | TokenType::NewArray
+        ) {
+            return diags;
+        }
+
+        if !line[0].token_type.is_opcode() {
+            return diags;
+        }
+
+        // The lexer groups opcodes by family, so it also accepts combinations
+        // such as `move-result/16` that aren't instructions
+        let opcode = match lookup(&line[0].content) {
+            Some(opcode) => opcode,
+            None => {
+                diags.push(line[0].to_diagnostic(
+                    format!("'{}' is not a valid instruction.", line[0].content),
+                    Some(DiagnosticSeverity::Error),
+                ));
+
+                return diags;
+            },
+        };
+
+        let operands = split_operands(line);
+        if operands.len() != opcode.operands.len() {
+            diags.push(tokens_to_diagnostic(
+                line,
+                format!(
+                    "'{}' expects {} operand{}, found {}.\n'{}'",
+                    opcode.name,
+                    opcode.operands.len(),
+                    if opcode.operands.len() == 1 { "" } else { "s" },
+                    operands.len(),
+                    opcode.usage()
+                ),
+                Some(DiagnosticSeverity::Error),
+            ));
+
+            return diags;
+        }
+
+        for (idx, (tokens, expected)) in operands.into_iter().zip(opcode.operands).enumerate() {
+            // Malformed method references and hex registers get more specific
+            // messages from their own validators
+            let is_reference = *expected == Operand::MethodRef && is_reference_attempt(tokens);
+            if classify(tokens) == Some(*expected) || is_reference || !hex_registers(tokens).is_empty() {
+                continue;
+            }
+
+            diags.push(tokens_to_diagnostic(
+                if tokens.is_empty() { line } else { tokens },
+                format!(
+                    "'{}' expects {} as operand {}.\n'{}'",
+                    opcode.name,
+                    expected.description(),
+                    idx + 1,
+                    opcode.usage()
+                ),
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+
+        if let Some((first, last)) = register_range(line) {
+            if matches!((register_number(first), register_number(last)), (Some(start), Some(end)) if end < start) {
+                diags.push(tokens_to_diagnostic(
+                    &[first.clone(), last.clone()],
+                    format!(
+                        "Register range '{} .. {}' is reversed.\nUse '{} .. {}' instead.",
+                        first.content, last.content, last.content, first.content
+                    ),
+                    Some(DiagnosticSeverity::Error),
+                ));
+            }
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_body;
+
+    #[test]
+    fn valid_operands() {
+        assert!(validate_body("    const/4 v0, 0x1\n    move v1, v0").is_empty());
+        assert!(validate_body("    const v0, -0x1\n    const/high16 v1, 0x7f010000").is_empty());
+        assert!(validate_body("    const-wide/16 v0, 0x0\n    move-wide v0, v0").is_empty());
+        assert!(validate_body("    const-class v0, [Ljava/lang/String;").is_empty());
+        assert!(validate_body("    invoke-static/range {v0 .. v1}, Lfoo;->bar(IZ)V").is_empty());
+        assert!(validate_body("    sget-object v0, Lfoo;->bar:Ljava/lang/String;\n    throw v0").is_empty());
+        assert!(validate_body("    add-int/lit8 v0, v1, 0x2\n    add-int/2addr v0, v1\n    neg-int v1, v0").is_empty());
+    }
+
+    #[test]
+    fn wrong_operand_count() {
+        assert_eq!(validate_body("    move v0"), vec!["'move' expects 2 operands, found 1.\n'move vA, vB'"]);
+        assert_eq!(validate_body("    invoke-static {}, Lfoo;->bar()I\n    move-result v0, v1"), vec![
+            "'move-result' expects 1 operand, found 2.\n'move-result vA'"
+        ]);
+        assert_eq!(validate_body("    const/4 v0, v1, 0x1"), vec![
+            "'const/4' expects 2 operands, found 3.\n'const/4 vA, lit'"
+        ]);
+        assert_eq!(validate_body("    add-int v0, v1"), vec![
+            "'add-int' expects 3 operands, found 2.\n'add-int vA, vB, vC'"
+        ]);
+    }
+
+    #[test]
+    fn unknown_opcode() {
+        assert_eq!(validate_body("    invoke-static {}, Lfoo;->bar()I\n    move-result/16 v0"), vec![
+            "'move-result/16' is not a valid instruction."
+        ]);
+        assert_eq!(validate_body("    move-exception/from16 v0"), vec![
+            "'move-exception/from16' is not a valid instruction."
+        ]);
+        assert!(validate_body("    move-object/from16 v0, v1\n    move-wide/16 v0, v2").is_empty());
+    }
+
+    #[test]
+    fn branch_operands() {
+        assert!(validate_body("    if-eq v0, v1, :cond_0\n    if-gez v0, :cond_0\n    :cond_0").is_empty());
+        assert_eq!(validate_body("    if-eqz v0, v1, :cond_0\n    :cond_0"), vec![
+            "'if-eqz' expects 2 operands, found 3.\n'if-eqz vA, :label'"
+        ]);
+        assert_eq!(validate_body("    if-ne v0, :cond_0\n    :cond_0"), vec![
+            "'if-ne' expects 3 operands, found 2.\n'if-ne vA, vB, :label'"
+        ]);
+    }
+
+    #[test]
+    fn wrong_operand_kind() {
+        assert_eq!(validate_body("    const v0, v1"), vec!["'const' expects a literal as operand 2.\n'const vA, lit'"]);
+        assert_eq!(validate_body("    move v0, 0x1"), vec!["'move' expects a register as operand 2.\n'move vA, vB'"]);
+        assert_eq!(validate_body("    new-instance v0, I"), vec![
+            "'new-instance' expects a type as operand 2.\n'new-instance vA, Lclass/Name;'"
+        ]);
+        assert_eq!(validate_body("    invoke-static v0, Lfoo;->bar()V"), vec![
+            "'invoke-static' expects a register list as operand 1.\n'invoke-static {vC, vD, ...}, \
+             Lclass/Name;->method()V'"
+        ]);
+        assert_eq!(validate_body("    if-eqz v0, v1"), vec![
+            "'if-eqz' expects a label as operand 2.\n'if-eqz vA, :label'"
+        ]);
+        assert_eq!(validate_body("    invoke-static {}, v0"), vec![
+            "'invoke-static' expects a method reference as operand 2.\n'invoke-static {vC, vD, ...}, \
+             Lclass/Name;->method()V'"
+        ]);
+        assert_eq!(validate_body("    mul-int/lit16 v0, v1, v0"), vec![
+            "'mul-int/lit16' expects a literal as operand 3.\n'mul-int/lit16 vA, vB, lit'"
+        ]);
+    }
+
+    #[test]
+    fn reversed_register_range() {
+        assert_eq!(validate_body("    invoke-static/range {v1 .. v0}, Lfoo;->bar(II)V"), vec![
+            "Register range 'v1 .. v0' is reversed.\nUse 'v0 .. v1' instead."
+        ]);
+    }
+}
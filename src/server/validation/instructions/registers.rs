@@ -0,0 +1,639 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::{field::suffix_matches, Validator};
+use crate::server::{
+    descriptor::{expected_suffix, is_reference, is_wide, read_type},
+    helper::{parse_count, tokens_to_diagnostic},
+    lexer::{Token, TokenType},
+    registers::{array_type, hex_registers, list_registers, RegisterTracker, RegisterType},
+    settings::Settings,
+    validation::Category,
+};
+
+#[derive(Debug)]
+pub struct RegisterValidator {
+    tracker:     RegisterTracker,
+    check_casts: bool,
+    check_nulls: bool,
+    check_moves: bool,
+}
+
+impl RegisterValidator {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            tracker:     RegisterTracker::default(),
+            check_casts: settings.check_primitive_casts,
+            check_nulls: settings.lint_null_dereference,
+            check_moves: settings.lint_redundant_moves,
+        }
+    }
+}
+
+impl Validator for RegisterValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        diags.append(&mut validate_hex_registers(line));
+
+        if self.check_nulls {
+            diags.append(&mut validate_null_dereference(line, &self.tracker));
+        }
+
+        match line[0].token_type {
+            TokenType::Invoke => {
+                diags.append(&mut validate_uninitialized_use(line, &self.tracker));
+            },
+            TokenType::IPut | TokenType::SPut => {
+                diags.append(&mut validate_field_store(line, &self.tracker));
+            },
+            TokenType::CheckCast if self.check_casts => {
+                diags.append(&mut validate_check_cast(line, &self.tracker));
+            },
+            TokenType::AGet | TokenType::APut => {
+                diags.append(&mut validate_array_access(line, &self.tracker));
+            },
+            TokenType::FilledNewArray => {
+                diags.append(&mut validate_filled_new_array(line, &self.tracker));
+            },
+            TokenType::Move | TokenType::Return => {
+                diags.append(&mut validate_width(line, &self.tracker));
+
+                if self.check_moves && line[0].token_type == TokenType::Move {
+                    diags.append(&mut validate_self_move(line));
+                }
+            },
+            _ => {},
+        }
+
+        self.tracker.update(line);
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+/// Reports registers numbered in hex, only decimal register numbers are valid.
+fn validate_hex_registers(line: &[Token]) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    for tokens in hex_registers(line) {
+        let name = format!("{}{}", tokens[0].content, tokens[1].content);
+        let message = match parse_count(&name[1..]) {
+            Some(number) => format!(
+                "'{}' is not a valid register.\nRegister numbers are decimal, use '{}{}' instead.",
+                name,
+                &name[..1],
+                number
+            ),
+            None => format!("'{}' is not a valid register.\nRegister numbers are decimal.", name),
+        };
+
+        diags.push(tokens_to_diagnostic(tokens, message, Some(DiagnosticSeverity::Error)));
+    }
+
+    diags
+}
+
+fn validate_uninitialized_use(line: &[Token], tracker: &RegisterTracker) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let is_init = line
+        .iter()
+        .any(|token| token.token_type == TokenType::MethodCall && token.content == "-><init>(");
+
+    for (idx, token) in line.iter().filter(|token| token.token_type == TokenType::Register).enumerate() {
+        // The object being constructed is the first argument to its <init>
+        if is_init && idx == 0 {
+            continue;
+        }
+
+        if let Some(RegisterType::Uninitialized(class)) = tracker.get(&token.content) {
+            diags.push(token.to_diagnostic(
+                format!("Object used before <init>.\n'{}' holds an uninitialized '{}'.", token.content, class),
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+    }
+
+    diags
+}
+
+/// Reports the receiver of an instance call or field access when it was most
+/// recently set to a zero constant.
+///
+/// Only the straight line above is considered, so a register assigned on
+/// another branch can still be flagged, hence the informational severity.
+fn validate_null_dereference(line: &[Token], tracker: &RegisterTracker) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let mut registers = line.iter().filter(|token| token.token_type == TokenType::Register);
+    let receiver = match line[0].token_type {
+        TokenType::Invoke if !line[0].content.starts_with("invoke-static") => registers.next(),
+        TokenType::IGet | TokenType::IPut => registers.nth(1),
+        _ => None,
+    };
+
+    if let Some(receiver) = receiver {
+        if tracker.get(&receiver.content) == Some(&RegisterType::Null) {
+            diags.push(Category::Info.tag(receiver.to_diagnostic(
+                format!("Possible null dereference.\n'{}' was last set to null.", receiver.content),
+                Some(DiagnosticSeverity::Information),
+            )));
+        }
+    }
+
+    diags
+}
+
+/// Reports moves from a register to itself, which leave every register as
+/// it was.
+fn validate_self_move(line: &[Token]) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let registers = line
+        .iter()
+        .filter(|token| token.token_type == TokenType::Register)
+        .collect::<Vec<_>>();
+
+    if let [dest, src] = registers.as_slice() {
+        if dest.content == src.content {
+            diags.push(Category::Info.tag(tokens_to_diagnostic(
+                line,
+                format!("Moving '{}' to itself has no effect.\nRemove the instruction.", dest.content),
+                Some(DiagnosticSeverity::Information),
+            )));
+        }
+    }
+
+    diags
+}
+
+/// Checks that moves and returns use the wide variant exactly when the
+/// source register holds a wide value.
+fn validate_width(line: &[Token], tracker: &RegisterTracker) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let opcode = &line[0].content;
+    if opcode.starts_with("move-result") || opcode == "move-exception" {
+        return diags;
+    }
+
+    let mut registers = line.iter().filter(|token| token.token_type == TokenType::Register);
+    let source = match line[0].token_type {
+        TokenType::Move => registers.nth(1),
+        _ => registers.next(),
+    };
+    let source = match source {
+        Some(source) => source,
+        None => return diags,
+    };
+
+    // A zero could be a null reference or a number, so it can't tell which
+    // narrow variant is right
+    let (kind, value) = match tracker.get(&source.content) {
+        Some(RegisterType::Primitive(primitive)) if is_wide(primitive) => ("-wide", primitive),
+        Some(RegisterType::Primitive(primitive)) => ("", primitive),
+        Some(RegisterType::Reference(class)) | Some(RegisterType::Uninitialized(class)) => ("-object", class),
+        _ => return diags,
+    };
+
+    let is_wide_op = opcode.contains("-wide");
+    if is_wide_op == (kind == "-wide") {
+        return diags;
+    }
+
+    let (base, suffix) = opcode.split_at(opcode.find('/').unwrap_or(opcode.len()));
+    let family = base.split('-').next().unwrap_or(base);
+    let message = if is_wide_op {
+        format!(
+            "'{}' holds a narrow '{}', but '{}' expects a register pair.\nUse '{}{}{}' instead.",
+            source.content, value, opcode, family, kind, suffix
+        )
+    } else {
+        format!(
+            "'{}' holds a wide '{}', but '{}' expects a single register.\nUse '{}-wide{}' instead.",
+            source.content, value, opcode, family, suffix
+        )
+    };
+
+    diags.push(source.to_diagnostic(message, Some(DiagnosticSeverity::Warning)));
+
+    diags
+}
+
+fn validate_field_store(line: &[Token], tracker: &RegisterTracker) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let register = match line.iter().find(|token| token.token_type == TokenType::Register) {
+        Some(register) => register,
+        None => return diags,
+    };
+
+    let field_type = line
+        .iter()
+        .position(|token| token.token_type == TokenType::FieldAccess)
+        .and_then(|idx| read_type(&line[idx + 1..]))
+        .map(|(descriptor, _)| descriptor);
+
+    let (field_type, value) = match (field_type, tracker.get(&register.content)) {
+        (Some(field_type), Some(value)) => (field_type, value),
+        _ => return diags,
+    };
+
+    let message = match value {
+        RegisterType::Primitive(primitive)
+            if is_reference(&field_type) || is_wide(primitive) != is_wide(&field_type) =>
+        {
+            Some(format!(
+                "'{}' holds a primitive '{}', but the field is of type '{}'.",
+                register.content, primitive, field_type
+            ))
+        },
+        RegisterType::Reference(class) | RegisterType::Uninitialized(class) if !is_reference(&field_type) => {
+            Some(format!(
+                "'{}' holds a '{}', but the field is of primitive type '{}'.",
+                register.content, class, field_type
+            ))
+        },
+        _ => None,
+    };
+
+    if let Some(message) = message {
+        diags.push(register.to_diagnostic(message, Some(DiagnosticSeverity::Warning)));
+    }
+
+    diags
+}
+
+fn validate_check_cast(line: &[Token], tracker: &RegisterTracker) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let register = match line.iter().find(|token| token.token_type == TokenType::Register) {
+        Some(register) => register,
+        None => return diags,
+    };
+
+    if let Some(RegisterType::Primitive(primitive)) = tracker.get(&register.content) {
+        diags.push(register.to_diagnostic(
+            format!("Cannot check-cast a primitive value.\n'{}' holds a primitive '{}'.", register.content, primitive),
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    diags
+}
+
+fn validate_array_access(line: &[Token], tracker: &RegisterTracker) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let registers = line
+        .iter()
+        .filter(|token| token.token_type == TokenType::Register)
+        .collect::<Vec<_>>();
+
+    let (array, index) = match registers.as_slice() {
+        [_, array, index] => (*array, *index),
+        _ => return diags,
+    };
+
+    let index_type = match tracker.get(&index.content) {
+        Some(RegisterType::Primitive(primitive)) if is_wide(primitive) => Some(primitive),
+        Some(RegisterType::Reference(class)) | Some(RegisterType::Uninitialized(class)) => Some(class),
+        _ => None,
+    };
+
+    if let Some(index_type) = index_type {
+        diags.push(index.to_diagnostic(
+            format!("'{}' holds a '{}', but an array index must be an 'I'.", index.content, index_type),
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    let opcode = &line[0].content;
+    let message = match tracker.get(&array.content) {
+        Some(RegisterType::Reference(descriptor)) if descriptor.starts_with('[') => {
+            let element = &descriptor[1..];
+
+            if suffix_matches(&opcode[4..], element) {
+                None
+            } else {
+                Some(format!(
+                    "'{}' cannot access an array of type '{}'.\nUse '{}{}' instead.",
+                    opcode,
+                    descriptor,
+                    &opcode[..4],
+                    expected_suffix(element)
+                ))
+            }
+        },
+        Some(RegisterType::Primitive(value))
+        | Some(RegisterType::Reference(value))
+        | Some(RegisterType::Uninitialized(value)) => {
+            Some(format!("'{}' holds a '{}', which is not an array.", array.content, value))
+        },
+        _ => None,
+    };
+
+    if let Some(message) = message {
+        diags.push(array.to_diagnostic(message, Some(DiagnosticSeverity::Error)));
+    }
+
+    diags
+}
+
+fn validate_filled_new_array(line: &[Token], tracker: &RegisterTracker) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    let opcode = &line[0].content;
+    let descriptor = match array_type(line) {
+        Some(descriptor) => descriptor,
+        None => return diags,
+    };
+    let element = &descriptor[1..];
+
+    if !is_reference(element) && element != "I" {
+        diags.push(tokens_to_diagnostic(
+            line,
+            format!(
+                "'{}' cannot create an array of type '{}'.\nOnly 'I' and reference arrays are supported.",
+                opcode, descriptor
+            ),
+            Some(DiagnosticSeverity::Error),
+        ));
+
+        return diags;
+    }
+
+    let registers = list_registers(line);
+    if !opcode.ends_with("/range") && registers.len() > 5 {
+        diags.push(tokens_to_diagnostic(
+            line,
+            format!(
+                "'{}' takes at most 5 registers, found {}.\nUse '{}/range' instead.",
+                opcode,
+                registers.len(),
+                opcode
+            ),
+            Some(DiagnosticSeverity::Error),
+        ));
+    }
+
+    let list = line
+        .iter()
+        .filter(|token| matches!(token.token_type, TokenType::Register | TokenType::RangeOp))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for register in registers {
+        let message = match tracker.get(&register) {
+            Some(RegisterType::Primitive(primitive)) if is_reference(element) => format!(
+                "'{}' holds a primitive '{}', but the array elements are of type '{}'.",
+                register, primitive, element
+            ),
+            Some(RegisterType::Reference(class)) | Some(RegisterType::Uninitialized(class))
+                if !is_reference(element) =>
+            {
+                format!(
+                    "'{}' holds a '{}', but the array elements are of primitive type '{}'.",
+                    register, class, element
+                )
+            },
+            _ => continue,
+        };
+
+        // Registers inside a range aren't written out, so point at the range
+        let tokens = match list.iter().find(|token| token.content == register) {
+            Some(token) => vec![token.clone()],
+            None => list.clone(),
+        };
+
+        diags.push(tokens_to_diagnostic(&tokens, message, Some(DiagnosticSeverity::Error)));
+    }
+
+    diags
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::fixture::{validate_body, validate_body_with, validate_class},
+    };
+
+    #[test]
+    fn hex_registers() {
+        assert_eq!(validate_body("    const/4 v0x1, 0x0"), vec![
+            "'v0x1' is not a valid register.\nRegister numbers are decimal, use 'v1' instead."
+        ]);
+        assert_eq!(validate_body("    const/4 v1x, 0x0"), vec![
+            "'v1x' is not a valid register.\nRegister numbers are decimal."
+        ]);
+    }
+
+    #[test]
+    fn initialized_new_instance() {
+        assert!(validate_body(
+            "    new-instance v0, Ljava/lang/StringBuilder;
+    invoke-direct {v0}, Ljava/lang/StringBuilder;-><init>()V
+    invoke-virtual {v0}, Ljava/lang/StringBuilder;->toString()Ljava/lang/String;"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn uninitialized_new_instance() {
+        assert_eq!(
+            validate_body(
+                "    new-instance v0, Ljava/lang/StringBuilder;
+    invoke-virtual {v0}, Ljava/lang/StringBuilder;->toString()Ljava/lang/String;"
+            ),
+            vec!["Object used before <init>.\n'v0' holds an uninitialized 'Ljava/lang/StringBuilder;'."]
+        );
+    }
+
+    #[test]
+    fn null_dereference() {
+        let body = "    const/4 v0, 0x0
+    invoke-virtual {v0}, Ljava/lang/Object;->hashCode()I";
+
+        assert_eq!(validate_body(body), vec!["Possible null dereference.\n'v0' was last set to null."]);
+        let settings = Settings {
+            lint_null_dereference: false,
+            ..Settings::default()
+        };
+        assert!(validate_body_with(body, &settings, &ClassIndex::default()).is_empty());
+
+        // Passing null as an argument is fine
+        assert!(validate_body(
+            "    const/4 v0, 0x0
+    invoke-static {v0}, Ljava/util/Objects;->hashCode(Ljava/lang/Object;)I"
+        )
+        .is_empty());
+    }
+
+    fn validate_return(return_type: &str, body: &str) -> Vec<String> {
+        validate_class(&format!(".method public static test(){}\n    .locals 4\n{}\n.end method", return_type, body))
+    }
+
+    #[test]
+    fn wide_returns() {
+        assert!(validate_return("J", "    const-wide v0, 0x1\n    return-wide v0").is_empty());
+        assert_eq!(validate_return("I", "    const-wide v0, 0x1\n    return v0"), vec![
+            "'v0' holds a wide 'J', but 'return' expects a single register.\nUse 'return-wide' instead."
+        ]);
+        assert_eq!(validate_return("J", "    const/4 v0, 0x1\n    return-wide v0"), vec![
+            "'v0' holds a narrow 'I', but 'return-wide' expects a register pair.\nUse 'return' instead."
+        ]);
+    }
+
+    #[test]
+    fn wide_moves() {
+        assert!(validate_body("    const-wide/16 v0, 0x1\n    move-wide/from16 v2, v0").is_empty());
+        assert_eq!(validate_body("    const-wide v0, 0x1\n    move/from16 v2, v0"), vec![
+            "'v0' holds a wide 'J', but 'move/from16' expects a single register.\nUse 'move-wide/from16' instead."
+        ]);
+        assert_eq!(validate_body("    const-string v0, \"a\"\n    move-wide v2, v0"), vec![
+            "'v0' holds a narrow 'Ljava/lang/String;', but 'move-wide' expects a register pair.\nUse \
+             'move-object' instead."
+        ]);
+    }
+
+    #[test]
+    fn self_moves() {
+        assert_eq!(validate_body("    move v0, v0"), vec![
+            "Moving 'v0' to itself has no effect.\nRemove the instruction."
+        ]);
+        assert!(validate_body("    move v0, v1").is_empty());
+        let settings = Settings {
+            lint_redundant_moves: false,
+            ..Settings::default()
+        };
+        assert!(validate_body_with("    move-object v0, v0", &settings, &ClassIndex::default()).is_empty());
+    }
+
+    #[test]
+    fn compatible_field_store() {
+        assert!(validate_body(
+            "    const-string v0, \"a\"
+    sput-object v0, Lfoo;->bar:Ljava/lang/String;
+    const/4 v0, 0x0
+    sput-object v0, Lfoo;->bar:Ljava/lang/String;
+    const/4 v1, 0x1
+    sput v1, Lfoo;->count:I"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn incompatible_field_store() {
+        assert_eq!(
+            validate_body(
+                "    const/4 v0, 0x1
+    sput-object v0, Lfoo;->bar:Ljava/lang/String;"
+            ),
+            vec!["'v0' holds a primitive 'I', but the field is of type 'Ljava/lang/String;'."]
+        );
+        assert_eq!(
+            validate_body(
+                "    const-string v0, \"a\"
+    sput v0, Lfoo;->count:I"
+            ),
+            vec!["'v0' holds a 'Ljava/lang/String;', but the field is of primitive type 'I'."]
+        );
+    }
+
+    #[test]
+    fn check_cast_primitive() {
+        let body = "    const/4 v0, 0x1\n    check-cast v0, Lfoo;";
+
+        assert_eq!(validate_body(body), vec!["Cannot check-cast a primitive value.\n'v0' holds a primitive 'I'."]);
+        let settings = Settings {
+            check_primitive_casts: false,
+            ..Settings::default()
+        };
+        assert!(validate_body_with(body, &settings, &ClassIndex::default()).is_empty());
+        assert!(validate_body("    const/4 v0, 0x0\n    check-cast v0, Lfoo;").is_empty());
+    }
+
+    #[test]
+    fn array_access_types() {
+        assert!(validate_body(
+            "    invoke-static {}, Lfoo;->bar()[Ljava/lang/String;
+    move-result-object v1
+    const/4 v0, 0x1
+    aget-object v0, v1, v0"
+        )
+        .is_empty());
+        assert_eq!(
+            validate_body(
+                "    invoke-static {}, Lfoo;->bar()[I
+    move-result-object v1
+    const/4 v0, 0x1
+    aget-object v0, v1, v0"
+            ),
+            vec!["'aget-object' cannot access an array of type '[I'.\nUse 'aget' instead."]
+        );
+        assert_eq!(
+            validate_body(
+                "    const-string v1, \"a\"
+    invoke-static {}, Lfoo;->bar()J
+    move-result-wide v0
+    aget v0, v1, v0"
+            ),
+            vec![
+                "'v0' holds a 'J', but an array index must be an 'I'.",
+                "'v1' holds a 'Ljava/lang/String;', which is not an array."
+            ]
+        );
+    }
+
+    #[test]
+    fn filled_new_array() {
+        assert!(validate_body(
+            "    const/4 v0, 0x1
+    const/4 v1, 0x2
+    filled-new-array {v0, v1}, [I
+    move-result-object v0"
+        )
+        .is_empty());
+
+        assert_eq!(
+            validate_body(
+                "    const/4 v0, 0x1
+    const/4 v1, 0x2
+    filled-new-array {v0, v1}, [Ljava/lang/String;"
+            ),
+            vec![
+                "'v0' holds a primitive 'I', but the array elements are of type 'Ljava/lang/String;'.",
+                "'v1' holds a primitive 'I', but the array elements are of type 'Ljava/lang/String;'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn filled_new_array_type() {
+        assert_eq!(validate_body("    filled-new-array {v0, v1}, [J"), vec![
+            "'filled-new-array' cannot create an array of type '[J'.\nOnly 'I' and reference arrays are supported."
+        ]);
+    }
+
+    #[test]
+    fn uninitialized_argument_to_init() {
+        assert_eq!(
+            validate_body(
+                "    new-instance v0, Ljava/lang/StringBuilder;
+    new-instance v1, Ljava/lang/StringBuilder;
+    invoke-direct {v0, v1}, Ljava/lang/StringBuilder;-><init>(Ljava/lang/CharSequence;)V"
+            ),
+            vec!["Object used before <init>.\n'v1' holds an uninitialized 'Ljava/lang/StringBuilder;'."]
+        );
+    }
+}
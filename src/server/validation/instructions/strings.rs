@@ -0,0 +1,71 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity, Position, Range};
+
+use super::Validator;
+use crate::server::lexer::{Token, TokenType};
+
+/// Reports string literals that are opened but never closed before the end
+/// of the line, the lexer can't match those so they come back as errors.
+#[derive(Debug, Default)]
+pub struct StringLiteralValidator;
+
+impl Validator for StringLiteralValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        let quote = line
+            .iter()
+            .find(|token| token.token_type == TokenType::Error && token.content.starts_with('"'));
+
+        if let Some(quote) = quote {
+            let start = quote.range.start;
+            let opening = Token {
+                range:      Range::new(start, Position::new(start.line, start.character + 1)),
+                content:    "\"".to_string(),
+                token_type: TokenType::Error,
+            };
+
+            diags.push(opening.to_diagnostic(
+                "Unterminated string literal.\nStrings must be closed on the same line.",
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Diagnostic, Position, Range};
+
+    use crate::server::{index::ClassIndex, settings::Settings, validation::fixture::validate_body_with};
+
+    fn validate_body(body: &str) -> Vec<Diagnostic> {
+        validate_body_with(body, &Settings::default(), &ClassIndex::default())
+    }
+
+    #[test]
+    fn closed_string() {
+        assert!(validate_body("    const-string v0, \"hello world\"").is_empty());
+    }
+
+    #[test]
+    fn unterminated_string() {
+        let diags = validate_body("    const-string v0, \"hello world\n    const-string v0, \"done\"");
+        let unterminated = diags
+            .iter()
+            .filter(|diag| diag.message.starts_with("Unterminated string literal."))
+            .collect::<Vec<_>>();
+
+        assert_eq!(unterminated.len(), 1);
+        assert_eq!(unterminated[0].range, Range::new(Position::new(5, 21), Position::new(5, 22)));
+    }
+}
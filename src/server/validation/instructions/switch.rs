@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::lexer::{Token, TokenType};
+
+/// Matches `packed-switch`/`sparse-switch` instructions with the payload
+/// blocks they reference, each payload has to be used by exactly one switch
+/// of the same kind.
+#[derive(Debug, Default)]
+pub struct SwitchValidator {
+    /// The label on the previous line, which names a payload starting on
+    /// this one.
+    last_label: Option<Token>,
+    in_payload: bool,
+    labels:     HashSet<String>,
+    /// Each payload label with the kind of its payload, e.g. `packed-switch`.
+    payloads:   Vec<(Token, String)>,
+    /// Each switch opcode with the payload label it references.
+    switches:   Vec<(String, Token)>,
+}
+
+impl Validator for SwitchValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        let last_label = self.last_label.take();
+
+        match (&line[0].token_type, line[0].content.as_ref()) {
+            (TokenType::Method, ".method") => {
+                self.in_payload = false;
+                self.labels.clear();
+                self.payloads.clear();
+                self.switches.clear();
+            },
+            (TokenType::Method, ".end method") => {
+                diags.append(&mut validate_payloads(self));
+            },
+            (TokenType::SwitchPayload, directive) if directive.starts_with(".end") => {
+                self.in_payload = false;
+            },
+            (TokenType::SwitchPayload, directive) => {
+                self.in_payload = true;
+
+                match last_label {
+                    Some(label) => self.payloads.push((label, directive[1..].to_string())),
+                    None => diags.push(line[0].to_diagnostic(
+                        "Missing label before switch payload.\nThe payload can't be referenced without one.",
+                        Some(DiagnosticSeverity::Error),
+                    )),
+                }
+            },
+            (TokenType::Label, _) if !self.in_payload => {
+                self.labels.insert(line[0].content.clone());
+                self.last_label = Some(line[0].clone());
+            },
+            (TokenType::Switch, opcode) => {
+                if let Some(label) = line.iter().find(|token| token.token_type == TokenType::Label) {
+                    self.switches.push((opcode.to_string(), label.clone()));
+                }
+            },
+            _ => {},
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+fn validate_payloads(validator: &mut SwitchValidator) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    for (label, kind) in &validator.payloads {
+        let mut references = validator
+            .switches
+            .iter()
+            .filter(|(_, reference)| reference.content == label.content);
+
+        match references.next() {
+            Some((opcode, reference)) if opcode != kind => {
+                diags.push(reference.to_diagnostic(
+                    format!("'{}' references a '.{}' payload.\nUse '{}' instead.", opcode, kind, kind),
+                    Some(DiagnosticSeverity::Error),
+                ));
+            },
+            Some(_) => {},
+            None => {
+                diags.push(label.to_diagnostic(
+                    format!("Switch payload '{}' is not referenced by a switch instruction.", label.content),
+                    Some(DiagnosticSeverity::Error),
+                ));
+            },
+        }
+
+        for (_, reference) in references {
+            diags.push(reference.to_diagnostic(
+                format!("Switch payload '{}' is already referenced by another switch instruction.", label.content),
+                Some(DiagnosticSeverity::Error),
+            ));
+        }
+    }
+
+    for (opcode, reference) in &validator.switches {
+        if validator.payloads.iter().any(|(label, _)| label.content == reference.content) {
+            continue;
+        }
+
+        let message = if validator.labels.contains(&reference.content) {
+            format!(
+                "Label '{}' is not a switch payload.\n'{}' expects a '.{}' block.",
+                reference.content, opcode, opcode
+            )
+        } else {
+            format!("Switch payload '{}' is not defined in this method.", reference.content)
+        };
+
+        diags.push(reference.to_diagnostic(message, Some(DiagnosticSeverity::Error)));
+    }
+
+    diags
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_body;
+
+    #[test]
+    fn matched_payloads() {
+        assert!(validate_body(
+            "    packed-switch p0, :pswitch_data_0
+    sparse-switch p0, :sswitch_data_0
+
+    :pswitch_0
+    :sswitch_0
+    return-void
+
+    :pswitch_data_0
+    .packed-switch 0x1
+        :pswitch_0
+    .end packed-switch
+
+    :sswitch_data_0
+    .sparse-switch
+        0x5 -> :sswitch_0
+    .end sparse-switch"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn orphan_payload() {
+        assert_eq!(
+            validate_body(
+                "    :pswitch_0
+    return-void
+
+    :pswitch_data_0
+    .packed-switch 0x1
+        :pswitch_0
+    .end packed-switch"
+            ),
+            vec!["Switch payload ':pswitch_data_0' is not referenced by a switch instruction."]
+        );
+    }
+
+    #[test]
+    fn dangling_references() {
+        assert_eq!(validate_body("    packed-switch p0, :pswitch_data_0\n    return-void"), vec![
+            "Switch payload ':pswitch_data_0' is not defined in this method."
+        ]);
+        assert_eq!(validate_body("    :cond_0\n    sparse-switch p0, :cond_0\n    return-void"), vec![
+            "Label ':cond_0' is not a switch payload.\n'sparse-switch' expects a '.sparse-switch' block."
+        ]);
+    }
+}
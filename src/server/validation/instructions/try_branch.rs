@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    catch::{parse_catch, CatchDirective},
+    lexer::{Token, TokenType},
+    settings::Settings,
+    validation::Category,
+};
+
+/// Reports branches leaving a try block, or entering one past its start,
+/// as the instructions on the other side are covered by different handlers.
+///
+/// Compiled code does this regularly, e.g. to skip over a handler, so the
+/// lint is opt-in and only informational.
+#[derive(Debug)]
+pub struct TryBranchValidator {
+    enabled:  bool,
+    /// The line of the instruction each label of the current method marks.
+    labels:   HashMap<String, u32>,
+    /// Labels waiting for the next instruction to mark.
+    pending:  Vec<String>,
+    /// The target label of each branch, alongside the line it is on.
+    branches: Vec<(Token, u32)>,
+    catches:  Vec<CatchDirective>,
+}
+
+impl TryBranchValidator {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            enabled:  settings.lint_try_branches,
+            labels:   HashMap::new(),
+            pending:  Vec::new(),
+            branches: Vec::new(),
+            catches:  Vec::new(),
+        }
+    }
+}
+
+impl Validator for TryBranchValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        if !self.enabled {
+            return diags;
+        }
+
+        match (&line[0].token_type, line[0].content.as_ref()) {
+            (TokenType::Method, ".method") => {
+                self.labels.clear();
+                self.pending.clear();
+                self.branches.clear();
+                self.catches.clear();
+            },
+            (TokenType::Method, ".end method") => {
+                diags.append(&mut validate_branches(self));
+            },
+            (TokenType::Label, _) => self.pending.push(line[0].content.clone()),
+            (TokenType::Catch, _) => self.catches.extend(parse_catch(line)),
+            (token_type, _) if token_type.is_opcode() => {
+                let current = line[0].range.start.line;
+                for label in self.pending.drain(..) {
+                    self.labels.insert(label, current);
+                }
+
+                if token_type.is_branch() {
+                    if let Some(target) = line.iter().find(|token| token.token_type == TokenType::Label) {
+                        self.branches.push((target.clone(), current));
+                    }
+                }
+            },
+            _ => {},
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+fn validate_branches(validator: &mut TryBranchValidator) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    let labels = &validator.labels;
+
+    // Several handlers can share a try block, each block is the instructions
+    // from the one marked by its start label up to the one marked by its end
+    let mut tries = validator
+        .catches
+        .drain(..)
+        .filter_map(|catch| {
+            let start = *labels.get(&catch.try_start.content)?;
+            let end = labels.get(&catch.try_end.content).copied().unwrap_or(u32::MAX);
+
+            Some((catch.try_start.content, start, end))
+        })
+        .collect::<Vec<_>>();
+    tries.dedup();
+
+    for (target, line) in validator.branches.drain(..) {
+        // Branches to labels that don't exist are reported elsewhere
+        let destination = match labels.get(&target.content) {
+            Some(destination) => *destination,
+            None => continue,
+        };
+
+        for (name, start, end) in &tries {
+            let contains = |line: u32| *start <= line && line < *end;
+
+            let message = if contains(line) && !contains(destination) {
+                format!(
+                    "Branch to '{}' leaves the try block starting at '{}'.\nInstructions after the jump aren't \
+                     covered by its handlers.",
+                    target.content, name
+                )
+            } else if !contains(line) && contains(destination) && destination != *start {
+                format!("Branch to '{}' enters the try block starting at '{}' past its start.", target.content, name)
+            } else {
+                continue;
+            };
+
+            diags.push(Category::Info.tag(target.to_diagnostic(message, Some(DiagnosticSeverity::Information))));
+            break;
+        }
+    }
+
+    diags
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::fixture::{messages, validate_body_with},
+    };
+
+    fn validate_body(body: &str, enabled: bool) -> Vec<String> {
+        let settings = Settings {
+            lint_try_branches: enabled,
+            ..Settings::default()
+        };
+
+        messages(validate_body_with(body, &settings, &ClassIndex::default()))
+    }
+
+    const LEAVING_TRY: &str = "    :try_start_0
+    invoke-static {}, Lme/l3af/Test;->run()V
+    goto :done
+    :try_end_0
+    .catch Ljava/lang/Exception; {:try_start_0 .. :try_end_0} :catch_0
+
+    :catch_0
+    move-exception v0
+
+    :done
+    return-void";
+
+    #[test]
+    fn goto_out_of_try() {
+        assert_eq!(validate_body(LEAVING_TRY, true), vec![
+            "Branch to ':done' leaves the try block starting at ':try_start_0'.\nInstructions after the jump aren't \
+             covered by its handlers."
+        ]);
+        assert!(validate_body(LEAVING_TRY, false).is_empty());
+    }
+
+    #[test]
+    fn goto_within_try() {
+        assert!(validate_body(
+            "    :try_start_0
+    :loop
+    invoke-static {}, Lme/l3af/Test;->run()V
+    goto :loop
+    :try_end_0
+    .catch Ljava/lang/Exception; {:try_start_0 .. :try_end_0} :catch_0
+
+    :catch_0
+    move-exception v0
+    return-void",
+            true
+        )
+        .is_empty());
+    }
+}
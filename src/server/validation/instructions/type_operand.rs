@@ -0,0 +1,124 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    descriptor::{is_reference, read_type},
+    helper::tokens_to_diagnostic,
+    lexer::{Token, TokenType},
+    opcodes::{classify, lookup, split_operands, Operand},
+};
+
+/// Checks `instance-of` and `new-array`, which take two registers followed
+/// by a type that has to be of a particular kind.
+#[derive(Debug, Default)]
+pub struct TypeOperandValidator;
+
+impl Validator for TypeOperandValidator {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        if !matches!(line[0].token_type, TokenType::InstanceOf | TokenType::NewArray) {
+            return diags;
+        }
+
+        let opcode = &line[0].content;
+        let usage = lookup(opcode).map(|opcode| opcode.usage()).unwrap_or_default();
+
+        let (registers, operand) = match split_operands(line).as_slice() {
+            [dest, src, operand] => ([*dest, *src], *operand),
+            _ => {
+                diags.push(tokens_to_diagnostic(
+                    line,
+                    format!("'{}' expects two registers and a type.\n'{}'", opcode, usage),
+                    Some(DiagnosticSeverity::Error),
+                ));
+
+                return diags;
+            },
+        };
+
+        for (idx, register) in registers.iter().enumerate() {
+            if classify(register) != Some(Operand::Register) {
+                diags.push(tokens_to_diagnostic(
+                    if register.is_empty() { line } else { register },
+                    format!("'{}' expects a register as operand {}.\n'{}'", opcode, idx + 1, usage),
+                    Some(DiagnosticSeverity::Error),
+                ));
+            }
+        }
+
+        let descriptor = match read_type(operand) {
+            Some((descriptor, consumed)) if consumed == operand.len() => descriptor,
+            _ => {
+                diags.push(tokens_to_diagnostic(
+                    if operand.is_empty() { line } else { operand },
+                    format!("'{}' expects a type as operand 3.\n'{}'", opcode, usage),
+                    Some(DiagnosticSeverity::Error),
+                ));
+
+                return diags;
+            },
+        };
+
+        let message = match line[0].token_type {
+            TokenType::InstanceOf if !is_reference(&descriptor) => {
+                Some(format!("'{}' expects a reference type, found primitive '{}'.", opcode, descriptor))
+            },
+            TokenType::NewArray if !descriptor.starts_with('[') => Some(format!(
+                "'{}' expects an array type, found '{}'.\nUse '[{}' instead.",
+                opcode, descriptor, descriptor
+            )),
+            _ => None,
+        };
+
+        if let Some(message) = message {
+            diags.push(tokens_to_diagnostic(operand, message, Some(DiagnosticSeverity::Error)));
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::validation::fixture::validate_body;
+
+    #[test]
+    fn valid_type_operands() {
+        assert!(validate_body("    instance-of v0, v1, Lfoo;").is_empty());
+        assert!(validate_body("    instance-of v0, v1, [I").is_empty());
+        assert!(validate_body("    new-array v0, v1, [Ljava/lang/String;").is_empty());
+    }
+
+    #[test]
+    fn primitive_instance_of() {
+        assert_eq!(validate_body("    instance-of v0, v1, I"), vec![
+            "'instance-of' expects a reference type, found primitive 'I'."
+        ]);
+    }
+
+    #[test]
+    fn non_array_new_array() {
+        assert_eq!(validate_body("    new-array v0, v1, Lfoo;"), vec![
+            "'new-array' expects an array type, found 'Lfoo;'.\nUse '[Lfoo;' instead."
+        ]);
+    }
+
+    #[test]
+    fn wrong_arity() {
+        assert_eq!(validate_body("    instance-of v0, Lfoo;"), vec![
+            "'instance-of' expects two registers and a type.\n'instance-of vA, vB, Lclass/Name;'"
+        ]);
+        assert_eq!(validate_body("    new-array v0, 0x1, [I"), vec![
+            "'new-array' expects a register as operand 2.\n'new-array vA, vB, Lclass/Name;'"
+        ]);
+    }
+}
@@ -0,0 +1,124 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity};
+
+use super::Validator;
+use crate::server::{
+    index::ClassIndex,
+    lexer::{Token, TokenType},
+    validation::ValidationContext,
+};
+
+/// Reports classes referenced by instructions that aren't declared anywhere
+/// in the workspace, unless their package is listed as external.
+///
+/// Members of unresolved classes aren't checked by the other validators, so
+/// this also covers their method and field references. Nothing is reported
+/// until the index holds at least one class.
+#[derive(Debug)]
+pub struct UnresolvedClassValidator<'a> {
+    index:    &'a ClassIndex,
+    enabled:  bool,
+    external: Vec<String>,
+    /// The class declared by the file, which may not be indexed yet.
+    declared: Option<String>,
+}
+
+impl<'a> UnresolvedClassValidator<'a> {
+    pub fn new(context: &ValidationContext<'a>) -> Self {
+        Self {
+            index:    context.index,
+            enabled:  context.settings.lint_unresolved_classes,
+            external: context.settings.external_packages.clone(),
+            declared: None,
+        }
+    }
+}
+
+impl<'a> Validator for UnresolvedClassValidator<'a> {
+    fn validate_token(&mut self, _: &Token) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        if !self.enabled || self.index.is_empty() {
+            return diags;
+        }
+
+        if line[0].token_type == TokenType::Directive && line[0].content == ".class" {
+            self.declared = line
+                .iter()
+                .find(|token| token.token_type == TokenType::Class)
+                .map(|class| class.content.clone());
+        }
+
+        if !line[0].token_type.is_opcode() {
+            return diags;
+        }
+
+        for class in line.iter().filter(|token| token.token_type == TokenType::Class) {
+            let is_known = self.declared.as_ref() == Some(&class.content)
+                || self.index.get(&class.content).is_some()
+                || self.external.iter().any(|package| class.content.starts_with(package.as_str()));
+
+            if !is_known {
+                diags.push(class.to_diagnostic(
+                    format!(
+                        "'{}' isn't declared in the workspace.\nAdd its package to 'externalPackages' if it comes \
+                         from a library.",
+                        class.content
+                    ),
+                    Some(DiagnosticSeverity::Warning),
+                ));
+            }
+        }
+
+        diags
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::Url;
+
+    use crate::server::{
+        index::ClassIndex,
+        settings::Settings,
+        validation::fixture::{messages, validate_body_with},
+    };
+
+    fn validate_body(body: &str, enabled: bool) -> Vec<String> {
+        let mut index = ClassIndex::default();
+        index.index_content(
+            &Url::parse("file:///Target.smali").unwrap(),
+            ".class public Lme/l3af/Target;\n.super Ljava/lang/Object;\n",
+        );
+
+        let settings = Settings {
+            lint_unresolved_classes: enabled,
+            ..Settings::default()
+        };
+
+        messages(validate_body_with(body, &settings, &index))
+    }
+
+    #[test]
+    fn external_packages() {
+        assert!(validate_body("    const-class v0, Ljava/lang/String;", true).is_empty());
+        assert!(validate_body("    new-instance v0, Lme/l3af/Target;", true).is_empty());
+        assert!(validate_body("    invoke-static {}, Lme/l3af/Test;->run()V", true).is_empty());
+    }
+
+    #[test]
+    fn unresolved_class() {
+        assert_eq!(validate_body("    const-class v0, Lmyapp/Missing;", true), vec![
+            "'Lmyapp/Missing;' isn't declared in the workspace.\nAdd its package to 'externalPackages' if it comes \
+             from a library."
+        ]);
+        assert!(validate_body("    const-class v0, Lmyapp/Missing;", false).is_empty());
+    }
+}
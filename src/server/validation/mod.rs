@@ -1,15 +1,71 @@
 mod directives;
+#[cfg(test)]
+mod fixture;
+mod instructions;
+mod style;
 
-use lspower::lsp::Diagnostic;
+use lspower::lsp::{Diagnostic, DiagnosticSeverity, NumberOrString, Position};
+use serde_json::Value;
 
-use self::directives::DirectivesValidator;
-use super::{helper::trim_space_tokens, lexer::{lex_str, Token, TokenType}};
+use self::{directives::DirectivesValidator, instructions::InstructionsValidator, style::StyleValidator};
+use super::{
+    blocks::MethodEdit,
+    error::AnalysisError,
+    helper::trim_space_tokens,
+    index::ClassIndex,
+    lexer::{lex_str, Token, TokenType},
+    registers::register_number,
+    settings::{CategorySeverity, Settings},
+};
 
-pub fn validate(content: String) -> Result<Vec<Diagnostic>, String> {
+/// The groups diagnostics are sorted into, so the severity of a whole group
+/// can be overridden at once. Its name is reported as the diagnostic code.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Category {
+    Correctness,
+    Style,
+    Info,
+}
+
+impl Category {
+    fn name(self) -> &'static str {
+        match self {
+            Category::Correctness => "correctness",
+            Category::Style => "style",
+            Category::Info => "info",
+        }
+    }
+
+    /// Marks `diag` as part of the category, diagnostics that aren't marked
+    /// are correctness ones.
+    pub fn tag(self, mut diag: Diagnostic) -> Diagnostic {
+        diag.data = Some(Value::String(self.name().to_string()));
+
+        diag
+    }
+
+    fn of(diag: &Diagnostic) -> Self {
+        match &diag.data {
+            Some(Value::String(name)) if name == "info" => Category::Info,
+            Some(Value::String(name)) if name == "style" => Category::Style,
+            _ => Category::Correctness,
+        }
+    }
+}
+
+pub struct ValidationContext<'a> {
+    pub index:    &'a ClassIndex,
+    pub settings: &'a Settings,
+}
+
+pub fn validate(content: String, context: &ValidationContext) -> Result<Vec<Diagnostic>, AnalysisError> {
     let tokens = lex_str(&content);
     let mut diags = Vec::new();
+    let mut style_diags = Vec::new();
 
-    let mut directives_validator = DirectivesValidator::default();
+    let mut directives_validator = DirectivesValidator::new(context);
+    let mut instructions_validator = InstructionsValidator::new(context);
+    let mut style_validator = StyleValidator::new(context.settings);
 
     let mut current_line = Vec::new();
     for token in tokens {
@@ -17,6 +73,8 @@ pub fn validate(content: String) -> Result<Vec<Diagnostic>, String> {
             let line = trim_space_tokens(current_line);
             if !line.is_empty() {
                 diags.append(&mut directives_validator.validate_line(&line));
+                diags.append(&mut instructions_validator.validate_line(&line));
+                style_diags.append(&mut style_validator.validate_line(&line));
             }
 
             current_line = Vec::new();
@@ -25,15 +83,401 @@ pub fn validate(content: String) -> Result<Vec<Diagnostic>, String> {
         }
 
         diags.append(&mut directives_validator.validate_token(&token));
+        diags.append(&mut instructions_validator.validate_token(&token));
+        style_diags.append(&mut style_validator.validate_token(&token));
+    }
+
+    // The last line has no newline to end it when the file doesn't end with one
+    let line = trim_space_tokens(current_line);
+    if !line.is_empty() {
+        diags.append(&mut directives_validator.validate_line(&line));
+        diags.append(&mut instructions_validator.validate_line(&line));
+        style_diags.append(&mut style_validator.validate_line(&line));
     }
 
     diags.append(&mut directives_validator.validate_end());
+    diags.append(&mut instructions_validator.validate_end());
+    style_diags.append(&mut style_validator.validate_end());
+
+    // Style lints are grouped by the validator reporting them, everything
+    // else by the category it was tagged with
+    let categories = &context.settings.categories;
+    let mut diags = diags
+        .into_iter()
+        .chain(style_diags.into_iter().map(|diag| Category::Style.tag(diag)))
+        .filter_map(|mut diag| {
+            let category = Category::of(&diag);
+            let severity = match category {
+                Category::Correctness => categories.correctness,
+                Category::Style => categories.style,
+                Category::Info => categories.info,
+            };
+            diag.data = None;
+            // Used as the rule id in exported reports
+            diag.code.get_or_insert_with(|| NumberOrString::String(category.name().to_string()));
+
+            apply_category(diag, severity)
+        })
+        .collect::<Vec<_>>();
+
+    // Upgraded last so style lints and categories overridden to warnings
+    // are covered too
+    if context.settings.treat_warnings_as_errors {
+        for diag in diags.iter_mut().filter(|diag| diag.severity == Some(DiagnosticSeverity::Warning)) {
+            diag.severity = Some(DiagnosticSeverity::Error);
+        }
+    }
 
     Ok(diags)
 }
 
+/// Validates only the method changed by `edit`, keeping the diagnostics in
+/// `previous` for the rest of the file and moving those after the method by
+/// the number of lines the change added or removed.
+///
+/// The bodies of the other methods are left out rather than validated again,
+/// their signatures are kept so class level checks still see them.
+pub fn validate_method(
+    content: &str,
+    edit: MethodEdit,
+    previous: &[Diagnostic],
+    context: &ValidationContext,
+) -> Result<Vec<Diagnostic>, AnalysisError> {
+    let mut in_other = false;
+    let stub = content
+        .split('\n')
+        .enumerate()
+        .map(|(idx, line)| {
+            let directive = line.trim_start();
+
+            if directive.starts_with(".end method") {
+                in_other = false;
+            } else if in_other {
+                return "";
+            } else if directive.starts_with(".method") && idx as u32 != edit.start {
+                in_other = true;
+            }
+
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let shift = |position: Position| Position::new((position.line as i64 + edit.delta) as u32, position.character);
+
+    let mut diags = previous
+        .iter()
+        .filter(|diag| diag.range.start.line < edit.start)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let new_end = edit.new_end();
+    diags.extend(
+        validate(stub, context)?
+            .into_iter()
+            .filter(|diag| edit.start <= diag.range.start.line && diag.range.start.line <= new_end),
+    );
+
+    diags.extend(
+        previous
+            .iter()
+            .filter(|diag| diag.range.start.line > edit.end)
+            .cloned()
+            .map(|mut diag| {
+                diag.range.start = shift(diag.range.start);
+                diag.range.end = shift(diag.range.end);
+
+                diag
+            }),
+    );
+
+    Ok(diags)
+}
+
+/// Reports `diag` at the severity configured for its category, or drops it
+/// when the category is turned off.
+fn apply_category(mut diag: Diagnostic, severity: Option<CategorySeverity>) -> Option<Diagnostic> {
+    if let Some(severity) = severity {
+        diag.severity = Some(severity.to_lsp()?);
+    }
+
+    Some(diag)
+}
+
+/// Reports the `pN` `register` when it isn't one of the parameters `first`
+/// to `count - 1`, shared by the `.param` and the instruction checks.
+fn validate_parameter_register(register: &Token, first: u32, count: u32) -> Option<Diagnostic> {
+    let idx = register_number(register)?;
+    if idx >= first && idx < count {
+        return None;
+    }
+
+    let hint = if first >= count {
+        "Method takes no parameters.".to_string()
+    } else {
+        format!("Parameters are p{}..p{}.", first, count - 1)
+    };
+
+    Some(register.to_diagnostic(
+        format!("'{}' is not a parameter register.\n{}", register.content, hint),
+        Some(DiagnosticSeverity::Error),
+    ))
+}
+
+/// Finds blank lines as the tokens of a file come in, a newline ends a blank
+/// line when the last token other than a space was a newline too.
+#[derive(Debug, Default)]
+struct BlankLineTracker {
+    last_token: Option<Token>,
+}
+
+impl BlankLineTracker {
+    /// Whether `token` is the newline ending a blank line.
+    fn update(&mut self, token: &Token) -> bool {
+        let is_blank = token.token_type == TokenType::NewLine
+            && matches!(&self.last_token, Some(last) if last.token_type == TokenType::NewLine);
+
+        if token.token_type != TokenType::Space {
+            self.last_token = Some(token.clone());
+        }
+
+        is_blank
+    }
+}
+
 trait Validator {
     fn validate_token(&mut self, token: &Token) -> Vec<Diagnostic>;
     fn validate_line(&mut self, line: &[Token]) -> Vec<Diagnostic>;
     fn validate_end(&self) -> Vec<Diagnostic>;
 }
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+
+    use super::{validate, validate_method, ValidationContext};
+    use crate::server::{
+        blocks::edited_method,
+        index::ClassIndex,
+        settings::{CategorySeverities, CategorySeverity, Settings},
+    };
+
+    const FIRST: &str = ".class public Lme/l3af/First;
+.super Ljava/lang/Object;
+
+.field private count:I
+
+.method static constructor <clinit>()V
+    .locals 0
+
+    :done
+    return-void
+.end method
+";
+
+    const SECOND: &str = ".class public Lme/l3af/Second;
+.super Ljava/lang/Object;
+
+.field private count:I
+
+.method static constructor <clinit>()V
+    .locals 0
+
+    goto :done
+.end method
+";
+
+    fn messages(content: &str, index: &ClassIndex, settings: &Settings) -> Vec<String> {
+        validate(content.to_string(), &ValidationContext {
+            index,
+            settings,
+        })
+        .unwrap()
+        .into_iter()
+        .map(|diag| diag.message)
+        .collect()
+    }
+
+    #[test]
+    fn documents_are_independent() {
+        let index = ClassIndex::default();
+        let settings = Settings::default();
+
+        let second_alone = messages(SECOND, &index, &settings);
+        assert_eq!(second_alone, vec!["Label ':done' is not defined in this method."]);
+
+        // Fields, constructors and labels seen in the first file must not
+        // carry over to the second.
+        assert!(messages(FIRST, &index, &settings).is_empty());
+        assert_eq!(messages(SECOND, &index, &settings), second_alone);
+        assert!(messages(FIRST, &index, &settings).is_empty());
+    }
+
+    #[test]
+    fn final_line_without_newline() {
+        let index = ClassIndex::default();
+        let settings = Settings::default();
+
+        // The `.end method` closing the method is on the last line
+        assert_eq!(messages(SECOND.trim_end(), &index, &settings), vec![
+            "Label ':done' is not defined in this method."
+        ]);
+    }
+
+    #[test]
+    fn warnings_as_errors() {
+        let index = ClassIndex::default();
+        let severities = |content: &str, settings: &Settings| {
+            validate(content.to_string(), &ValidationContext {
+                index:    &index,
+                settings,
+            })
+            .unwrap()
+            .into_iter()
+            .map(|diag| diag.severity)
+            .collect::<Vec<_>>()
+        };
+
+        let content = ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n.method public test()V\n    .locals \
+                       0x0\n    return-void\n.end method\n";
+        assert_eq!(severities(content, &Settings::default()), vec![Some(DiagnosticSeverity::Warning)]);
+        assert_eq!(
+            severities(content, &Settings {
+                treat_warnings_as_errors: true,
+                ..Settings::default()
+            }),
+            vec![Some(DiagnosticSeverity::Error)]
+        );
+
+        // Style lints are upgraded too
+        let content = ".class public Lme/l3af/Test;\n\n\n.super Ljava/lang/Object;\n";
+        let settings = |treat_warnings_as_errors| Settings {
+            lint_blank_lines: true,
+            treat_warnings_as_errors,
+            ..Settings::default()
+        };
+        assert_eq!(severities(content, &settings(false)), vec![Some(DiagnosticSeverity::Warning)]);
+        assert_eq!(severities(content, &settings(true)), vec![Some(DiagnosticSeverity::Error)]);
+
+        // As are categories overridden to warnings
+        let content = ".class public Lme/l3af/Test; \n.super Ljava/lang/Object;\n";
+        assert_eq!(
+            severities(content, &Settings {
+                lint_trailing_whitespace: true,
+                treat_warnings_as_errors: true,
+                categories: CategorySeverities {
+                    style: Some(CategorySeverity::Warning),
+                    ..CategorySeverities::default()
+                },
+                ..Settings::default()
+            }),
+            vec![Some(DiagnosticSeverity::Error)]
+        );
+    }
+
+    #[test]
+    fn category_severities() {
+        let content = ".class public Lme/l3af/Test; \n.super Ljava/lang/Object;\n\n.method public test()V\n    .locals \
+                       0\n    goto :done\n.end method\n";
+        let index = ClassIndex::default();
+        let settings = |style| Settings {
+            lint_trailing_whitespace: true,
+            categories: CategorySeverities {
+                style,
+                ..CategorySeverities::default()
+            },
+            ..Settings::default()
+        };
+
+        assert_eq!(messages(content, &index, &settings(None)), vec![
+            "Label ':done' is not defined in this method.",
+            "Trailing whitespace."
+        ]);
+        assert_eq!(messages(content, &index, &settings(Some(CategorySeverity::Off))), vec![
+            "Label ':done' is not defined in this method."
+        ]);
+    }
+
+    #[test]
+    fn info_and_correctness_categories() {
+        let content = ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n.field private count:I\n\n.field \
+                       private count:I\n\n.method public test()V\n    .locals 1\n    const/4 v0, 0x0\n    move v0, \
+                       v0\n    return-void\n.end method\n";
+        let index = ClassIndex::default();
+        let settings = |correctness, info| Settings {
+            categories: CategorySeverities {
+                correctness,
+                info,
+                ..CategorySeverities::default()
+            },
+            ..Settings::default()
+        };
+
+        // The hint pointing at the first field goes along with the error
+        assert_eq!(messages(content, &index, &settings(None, Some(CategorySeverity::Off))), vec![
+            "Field declared here.",
+            "Field 'count' already declared."
+        ]);
+        assert_eq!(messages(content, &index, &settings(Some(CategorySeverity::Off), None)), vec![
+            "Moving 'v0' to itself has no effect.\nRemove the instruction."
+        ]);
+
+        let diags = validate(content.to_string(), &ValidationContext {
+            index:    &index,
+            settings: &settings(None, Some(CategorySeverity::Warning)),
+        })
+        .unwrap();
+        assert_eq!(diags.iter().map(|diag| diag.severity).collect::<Vec<_>>(), vec![
+            Some(DiagnosticSeverity::Hint),
+            Some(DiagnosticSeverity::Error),
+            Some(DiagnosticSeverity::Warning)
+        ]);
+        assert!(diags.iter().all(|diag| diag.data.is_none()));
+        assert_eq!(diags.iter().map(|diag| diag.code.clone()).collect::<Vec<_>>(), vec![
+            Some(NumberOrString::String("correctness".to_string())),
+            Some(NumberOrString::String("correctness".to_string())),
+            Some(NumberOrString::String("info".to_string()))
+        ]);
+    }
+
+    #[test]
+    fn incremental_method() {
+        let before = ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n.method public static a()V\n    \
+                      .locals 0\n    goto :first\n.end method\n\n.method public static b()V\n    .locals 0\n    \
+                      return-void\n.end method\n";
+        let index = ClassIndex::default();
+        let settings = Settings::default();
+        let context = ValidationContext {
+            index:    &index,
+            settings: &settings,
+        };
+
+        // Stands in for the diagnostics published for the first method, it
+        // is only kept if that method isn't validated again
+        let cached = Diagnostic::new_simple(
+            Range::new(Position::new(5, 4), Position::new(5, 15)),
+            "Cached.".to_string(),
+        );
+
+        let range = Range::new(Position::new(10, 4), Position::new(10, 4));
+        let edit = edited_method(before, range, "goto :second\n    ").unwrap();
+        let after = before.replace("    return-void", "    goto :second\n    return-void");
+
+        let diags = validate_method(&after, edit, &[cached.clone()], &context).unwrap();
+        assert_eq!(diags.iter().map(|diag| diag.message.as_str()).collect::<Vec<_>>(), vec![
+            "Cached.",
+            "Label ':second' is not defined in this method."
+        ]);
+        assert_eq!(diags[1].range.start.line, 10);
+
+        // Lines added to the first method move the diagnostics after it
+        let range = Range::new(Position::new(5, 4), Position::new(5, 4));
+        let edit = edited_method(&after, range, "nop\n    ").unwrap();
+        let moved = after.replace("    goto :first", "    nop\n    goto :first");
+        let previous = validate(after.clone(), &context).unwrap();
+
+        let diags = validate_method(&moved, edit, &previous, &context).unwrap();
+        assert_eq!(diags, validate(moved, &context).unwrap());
+        assert_eq!(diags[1].range.start.line, 11);
+    }
+}
@@ -0,0 +1,304 @@
+use lspower::lsp::{Diagnostic, DiagnosticSeverity, Position, Range};
+
+use super::{BlankLineTracker, Validator};
+use crate::server::{
+    lexer::{Token, TokenType},
+    settings::Settings,
+};
+
+/// Whitespace lints, each only reported when enabled in the settings.
+#[derive(Debug)]
+pub struct StyleValidator {
+    trailing_whitespace: bool,
+    final_newline:       bool,
+    blank_lines:         bool,
+    max_line_length:     Option<u32>,
+    /// The ending every line should use, taken from the first line when
+    /// the setting is `auto`.
+    line_ending:         Option<String>,
+    /// The last two tokens seen, most recent last.
+    last_tokens:         Vec<Token>,
+    line_tracker:        BlankLineTracker,
+    /// Blank lines in a row up to the current one.
+    blank_run:           u32,
+    /// The tokens of the current line so far, comments included.
+    line:                Vec<Token>,
+}
+
+impl StyleValidator {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            trailing_whitespace: settings.lint_trailing_whitespace,
+            final_newline:       settings.lint_final_newline,
+            blank_lines:         settings.lint_blank_lines,
+            max_line_length:     settings.max_line_length,
+            line_ending:         settings.line_ending.as_str().map(str::to_string),
+            last_tokens:         Vec::new(),
+            line_tracker:        BlankLineTracker::default(),
+            blank_run:           0,
+            line:                Vec::new(),
+        }
+    }
+
+    /// Reports the current line when it is longer than `maxLineLength`,
+    /// measured as written, trailing comments and whitespace included.
+    fn validate_line_length(&self) -> Option<Diagnostic> {
+        let max = self.max_line_length?;
+        let end = self.line.last()?.range.end;
+        if end.character <= max {
+            return None;
+        }
+
+        // A string literal can't be split, so a line it runs past the limit
+        // can't be made any shorter
+        let is_unsplittable = self.line.iter().any(|token| {
+            token.token_type == TokenType::String
+                && token.range.start.character < max
+                && token.range.end.character > max
+        });
+        if is_unsplittable {
+            return None;
+        }
+
+        Some(Diagnostic::new(
+            Range::new(Position::new(end.line, max), end),
+            Some(DiagnosticSeverity::Information),
+            None,
+            None,
+            format!("Line is {} characters long, the maximum is {}.", end.character, max),
+            None,
+            None,
+        ))
+    }
+}
+
+impl Validator for StyleValidator {
+    fn validate_token(&mut self, token: &Token) -> Vec<Diagnostic> {
+        let mut diags = Vec::new();
+
+        if let Some(last) = self.last_tokens.last() {
+            if self.trailing_whitespace
+                && token.token_type == TokenType::NewLine
+                && last.token_type == TokenType::Space
+            {
+                diags.push(last.to_diagnostic("Trailing whitespace.", Some(DiagnosticSeverity::Information)));
+            }
+        }
+
+        if token.token_type == TokenType::NewLine {
+            match &self.line_ending {
+                Some(expected) if *expected != token.content => diags.push(token.to_diagnostic(
+                    format!("Line ends with {}, expected {}.", ending_name(&token.content), ending_name(expected)),
+                    Some(DiagnosticSeverity::Information),
+                )),
+                Some(_) => {},
+                None => self.line_ending = Some(token.content.clone()),
+            }
+        }
+
+        let is_blank = self.line_tracker.update(token);
+        if token.token_type == TokenType::NewLine {
+            self.blank_run = if is_blank { self.blank_run + 1 } else { 0 };
+
+            // Reported once for each run, on its second blank line
+            if self.blank_lines && self.blank_run == 2 {
+                diags.push(token.to_diagnostic(
+                    "Multiple consecutive blank lines.\nSeparate sections with a single blank line.",
+                    Some(DiagnosticSeverity::Warning),
+                ));
+            }
+        }
+
+        if token.token_type == TokenType::NewLine {
+            diags.extend(self.validate_line_length());
+            self.line.clear();
+        } else {
+            self.line.push(token.clone());
+        }
+
+        if self.last_tokens.len() == 2 {
+            self.last_tokens.remove(0);
+        }
+        self.last_tokens.push(token.clone());
+
+        diags
+    }
+
+    fn validate_line(&mut self, _: &[Token]) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn validate_end(&self) -> Vec<Diagnostic> {
+        // The last line has no newline to end it when the file doesn't end
+        // with one
+        let mut diags = self.validate_line_length().into_iter().collect::<Vec<_>>();
+
+        if !self.final_newline {
+            return diags;
+        }
+
+        match self.last_tokens.as_slice() {
+            [.., last] if last.token_type != TokenType::NewLine => {
+                diags.push(last.to_diagnostic(
+                    "Missing newline at end of file.",
+                    Some(DiagnosticSeverity::Information),
+                ));
+            },
+            [first, last] if first.token_type == TokenType::NewLine && last.token_type == TokenType::NewLine => {
+                diags.push(last.to_diagnostic(
+                    "Extra blank lines at end of file.\nEnd the file with a single newline.",
+                    Some(DiagnosticSeverity::Information),
+                ));
+            },
+            _ => {},
+        }
+
+        diags
+    }
+}
+
+fn ending_name(ending: &str) -> &'static str {
+    if ending == "\r\n" {
+        "CRLF"
+    } else {
+        "LF"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lspower::lsp::{Position, Range};
+
+    use crate::server::{
+        index::ClassIndex,
+        settings::{LineEnding, Settings},
+        validation::fixture::{validate_file, validate_file_with},
+    };
+
+    fn validate_with(content: &str, settings: &Settings) -> Vec<(String, Range)> {
+        validate_file_with(content, settings, &ClassIndex::default())
+            .into_iter()
+            .map(|diag| (diag.message, diag.range))
+            .collect()
+    }
+
+    fn validate_style(content: &str) -> Vec<(String, Range)> {
+        validate_with(content, &Settings {
+            lint_trailing_whitespace: true,
+            lint_final_newline: true,
+            lint_blank_lines: true,
+            ..Settings::default()
+        })
+    }
+
+    #[test]
+    fn clean_file() {
+        assert!(validate_style(".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n").is_empty());
+    }
+
+    #[test]
+    fn trailing_whitespace() {
+        assert_eq!(validate_style(".class public Lme/l3af/Test;  \n.super Ljava/lang/Object;\n"), vec![(
+            "Trailing whitespace.".to_string(),
+            Range::new(Position::new(0, 28), Position::new(0, 30))
+        )]);
+    }
+
+    #[test]
+    fn final_newline() {
+        assert_eq!(validate_style(".class public Lme/l3af/Test;\n.super Ljava/lang/Object;"), vec![(
+            "Missing newline at end of file.".to_string(),
+            Range::new(Position::new(1, 7), Position::new(1, 25))
+        )]);
+        assert_eq!(
+            validate_style(".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n\n")
+                .into_iter()
+                .map(|(message, _)| message)
+                .collect::<Vec<_>>(),
+            vec!["Extra blank lines at end of file.\nEnd the file with a single newline."]
+        );
+    }
+
+    #[test]
+    fn consecutive_blank_lines() {
+        assert!(validate_style(".class public Lme/l3af/Test;\n\n.super Ljava/lang/Object;\n").is_empty());
+        assert_eq!(validate_style(".class public Lme/l3af/Test;\n\n  \n\n.super Ljava/lang/Object;\n"), vec![
+            ("Trailing whitespace.".to_string(), Range::new(Position::new(2, 0), Position::new(2, 2))),
+            (
+                "Multiple consecutive blank lines.\nSeparate sections with a single blank line.".to_string(),
+                Range::new(Position::new(2, 2), Position::new(3, 0))
+            ),
+        ]);
+    }
+
+    fn validate_length(content: &str) -> Vec<(String, Range)> {
+        validate_with(content, &Settings {
+            max_line_length: Some(30),
+            ..Settings::default()
+        })
+    }
+
+    #[test]
+    fn max_line_length() {
+        let content = ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;\n";
+        assert!(validate_length(content).is_empty());
+        assert_eq!(validate_length(&format!("{}.implements Ljava/lang/Runnable;\n", content)), vec![(
+            "Line is 32 characters long, the maximum is 30.".to_string(),
+            Range::new(Position::new(2, 30), Position::new(2, 32))
+        )]);
+        assert!(validate_length(&format!("{}.source \"VeryLongSourceFileName.java\"\n", content)).is_empty());
+    }
+
+    #[test]
+    fn max_line_length_comments() {
+        let content = ".class public Lme/l3af/Test;\n.super Ljava/lang/Object;";
+        assert_eq!(validate_length(&format!("{} # Base class\n", content)), vec![(
+            "Line is 38 characters long, the maximum is 30.".to_string(),
+            Range::new(Position::new(1, 30), Position::new(1, 38))
+        )]);
+        assert_eq!(validate_length(&format!("{}\n# A comment that runs far too long", content)), vec![(
+            "Line is 34 characters long, the maximum is 30.".to_string(),
+            Range::new(Position::new(2, 30), Position::new(2, 34))
+        )]);
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(validate_file(".class public Lme/l3af/Test; \n.super Ljava/lang/Object;").is_empty());
+    }
+
+    fn validate_line_endings(content: &str, line_ending: LineEnding) -> Vec<(String, Range)> {
+        validate_with(content, &Settings {
+            line_ending,
+            ..Settings::default()
+        })
+    }
+
+    #[test]
+    fn line_endings() {
+        let content = ".class public Lme/l3af/Test;\r\n.super Ljava/lang/Object;\r\n";
+
+        assert!(validate_line_endings(content, LineEnding::Crlf).is_empty());
+        assert!(validate_line_endings(content, LineEnding::Auto).is_empty());
+        assert_eq!(validate_line_endings(content, LineEnding::Lf), vec![
+            (
+                "Line ends with CRLF, expected LF.".to_string(),
+                Range::new(Position::new(0, 28), Position::new(1, 0))
+            ),
+            (
+                "Line ends with CRLF, expected LF.".to_string(),
+                Range::new(Position::new(1, 25), Position::new(2, 0))
+            ),
+        ]);
+    }
+
+    #[test]
+    fn mixed_line_endings() {
+        let content = ".class public Lme/l3af/Test;\r\n.super Ljava/lang/Object;\n";
+
+        assert_eq!(validate_line_endings(content, LineEnding::Auto), vec![(
+            "Line ends with LF, expected CRLF.".to_string(),
+            Range::new(Position::new(1, 25), Position::new(2, 0))
+        )]);
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use serde_json::Value;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/lint");
+
+fn lint(args: &[&str]) -> (i32, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_smali-lsp"))
+        .arg("lint")
+        .args(args)
+        .output()
+        .unwrap();
+
+    (output.status.code().unwrap(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn lint_directory() {
+    let (code, stdout) = lint(&[FIXTURE, "--jobs", "2"]);
+
+    assert_eq!(code, 1);
+    assert!(stdout.ends_with("2 files checked, 1 errors, 1 warnings\n"), "{}", stdout);
+}
+
+#[test]
+fn lint_warnings_as_errors() {
+    let (code, stdout) = lint(&[FIXTURE, "--warnings-as-errors"]);
+
+    assert_eq!(code, 1);
+    assert!(stdout.ends_with("2 files checked, 2 errors, 0 warnings\n"), "{}", stdout);
+}
+
+#[test]
+fn lint_clean_file() {
+    let (code, stdout) = lint(&[&format!("{}/me/l3af/Valid.smali", FIXTURE)]);
+
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "1 files checked, 0 errors, 0 warnings\n");
+}
+
+#[test]
+fn lint_json() {
+    let (code, stdout) = lint(&[FIXTURE, "--format", "json"]);
+    let reports = serde_json::from_str::<Value>(&stdout).unwrap();
+    let reports = reports.as_array().unwrap();
+
+    assert_eq!(code, 1);
+    assert_eq!(reports.len(), 2);
+    assert!(reports[0]["path"].as_str().unwrap().ends_with("Valid.smali"));
+    assert_eq!(reports[1]["errors"], 1);
+    assert_eq!(reports[1]["warnings"], 1);
+}
+
+fn lint_stdin(args: &[&str], content: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_smali-lsp"))
+        .args(&["lint", "--stdin"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(content.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    (output.status.code().unwrap(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn lint_piped_content() {
+    let (code, stdout) = lint_stdin(&["--filename", "Foo.smali"], ".class public LFoo;\n");
+
+    assert_eq!(code, 1);
+    assert_eq!(
+        stdout,
+        "Foo.smali:1:1: error: Missing super directive. Extend 'Ljava/lang/Object;' by default\n1 files checked, 1 \
+         errors, 0 warnings\n"
+    );
+
+    let (code, stdout) = lint_stdin(&["--format", "json"], ".class public LFoo;\n.super Ljava/lang/Object;\n");
+    let reports = serde_json::from_str::<Value>(&stdout).unwrap();
+
+    assert_eq!(code, 0);
+    assert_eq!(reports[0]["path"], "<stdin>");
+    assert_eq!(reports[0]["diagnostics"], serde_json::json!([]));
+}
+
+#[test]
+fn lint_usage() {
+    assert_eq!(lint(&[]).0, 2);
+    assert_eq!(lint(&[FIXTURE, "--jobs", "many"]).0, 2);
+    assert_eq!(lint(&[FIXTURE, "--stdin"]).0, 2);
+    assert_eq!(lint(&[FIXTURE, "--filename", "Foo.smali"]).0, 2);
+}